@@ -0,0 +1,122 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use demmit::{cell_size_m, matrix_to_image, shade, shade_to_image, tile_to_matrix};
+use nalgebra::DMatrix;
+use nasadem::Tile;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Wraps the system allocator to track the high-water mark of live
+/// heap bytes.
+struct PeakAlloc;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let live = LIVE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(live, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: PeakAlloc = PeakAlloc;
+
+/// Returns the peak heap bytes allocated by `f` above what was live
+/// when it was called.
+fn peak_bytes<T>(f: impl FnOnce() -> T) -> usize {
+    let baseline = LIVE.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    black_box(f());
+    PEAK.load(Ordering::Relaxed) - baseline
+}
+
+fn one_arcsecond_tile_path() -> PathBuf {
+    [
+        env!("CARGO_MANIFEST_DIR"),
+        "..",
+        "data",
+        "nasadem",
+        "1arcsecond",
+        "N44W072.hgt",
+    ]
+    .iter()
+    .collect()
+}
+
+/// Sample spacing of the 1-arcsecond fixture.
+fn cell_m() -> (f32, f32) {
+    cell_size_m(1, 44.5)
+}
+
+fn separate(data: &DMatrix<f32>) -> image::ImageBuffer<image::Luma<u16>, Vec<u16>> {
+    let shaded = shade(
+        45_f32.to_radians(),
+        315_f32.to_radians(),
+        cell_m(),
+        1.0,
+        data,
+    );
+    matrix_to_image::<u16>(&shaded)
+}
+
+fn fused(data: &DMatrix<f32>) -> image::ImageBuffer<image::Luma<u16>, Vec<u16>> {
+    shade_to_image::<u16>(
+        45_f32.to_radians(),
+        315_f32.to_radians(),
+        cell_m(),
+        1.0,
+        data,
+    )
+}
+
+fn render_one_arcsecond_hillshade(c: &mut Criterion) {
+    const MIB: usize = 1 << 20;
+    let tile = Tile::load(one_arcsecond_tile_path()).unwrap();
+    let data = tile_to_matrix(&tile);
+
+    println!(
+        "Peak memory: shade then matrix_to_image {} MiB, shade_to_image {} MiB",
+        peak_bytes(|| separate(&data)) / MIB,
+        peak_bytes(|| fused(&data)) / MIB,
+    );
+
+    let mut group = c.benchmark_group("Render 1-arcsecond hillshade");
+    group.sample_size(10);
+    group.bench_function("shade then matrix_to_image", |b| {
+        b.iter(|| black_box(separate(&data)));
+    });
+    group.bench_function("shade_to_image", |b| b.iter(|| black_box(fused(&data))));
+}
+
+fn shade_one_arcsecond(c: &mut Criterion) {
+    let tile = Tile::load(one_arcsecond_tile_path()).unwrap();
+    let data = tile_to_matrix(&tile);
+    let (elev, azimuth) = (45_f32.to_radians(), 315_f32.to_radians());
+
+    let mut group = c.benchmark_group("Shade 1-arcsecond tile");
+    group.sample_size(10);
+    group.bench_function("shade", |b| {
+        b.iter(|| black_box(shade(elev, azimuth, cell_m(), 1.0, &data)));
+    });
+    #[cfg(feature = "simd")]
+    group.bench_function("shade_simd", |b| {
+        b.iter(|| black_box(demmit::shade_simd(elev, azimuth, cell_m(), 1.0, &data)));
+    });
+}
+
+criterion_group!(benches, render_one_arcsecond_hillshade, shade_one_arcsecond);
+criterion_main!(benches);
@@ -0,0 +1,89 @@
+use crate::shade_to_image;
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    Delay, DynamicImage, Frame, ImageResult,
+};
+use nalgebra::DMatrix;
+use std::{io::Write, time::Duration};
+
+/// Writes an animated GIF of `data`'s hillshade, for samples `cell_m`
+/// apart as with [`shade`](crate::shade), as the sun sweeps across
+/// `azimuths_rad` at `sun_elev_rad` above the horizon.
+///
+/// Each azimuth is one frame, displayed for `1 / fps` seconds. The
+/// animation loops forever.
+pub fn sun_sweep_gif<W: Write>(
+    data: &DMatrix<f32>,
+    cell_m: (f32, f32),
+    sun_elev_rad: f32,
+    azimuths_rad: impl IntoIterator<Item = f32>,
+    fps: f32,
+    w: W,
+) -> ImageResult<()> {
+    let delay = Delay::from_saturating_duration(Duration::from_secs_f32(1.0 / fps));
+    let mut encoder = GifEncoder::new_with_speed(w, 10);
+    encoder.set_repeat(Repeat::Infinite)?;
+    for azimuth_rad in azimuths_rad {
+        let shaded = shade_to_image::<u8>(sun_elev_rad, azimuth_rad, cell_m, 1.0, data);
+        let frame = DynamicImage::ImageLuma8(shaded).into_rgba8();
+        encoder.encode_frame(Frame::from_parts(frame, 0, 0, delay))?;
+    }
+    Ok(())
+}
+
+/// Returns `frames` evenly spaced values from `start` to `end`,
+/// inclusive.
+#[allow(clippy::cast_precision_loss)]
+pub fn sweep(start: f32, end: f32, frames: usize) -> impl Iterator<Item = f32> {
+    let step = if frames > 1 {
+        (end - start) / (frames - 1) as f32
+    } else {
+        0.0
+    };
+    (0..frames).map(move |i| start + step * i as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sun_sweep_gif, sweep};
+    use image::{codecs::gif::GifDecoder, AnimationDecoder};
+    use nalgebra::DMatrix;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_sweep() {
+        assert_eq!(
+            sweep(0.0, 90.0, 4).collect::<Vec<_>>(),
+            vec![0.0, 30.0, 60.0, 90.0]
+        );
+        assert_eq!(sweep(45.0, 90.0, 1).collect::<Vec<_>>(), vec![45.0]);
+        assert_eq!(sweep(45.0, 90.0, 0).count(), 0);
+    }
+
+    #[test]
+    fn test_sun_sweep_gif() {
+        #[allow(clippy::cast_precision_loss)]
+        let ridge = DMatrix::from_fn(24, 32, |_, x| (x as f32 - 16.0).abs() * -0.5);
+        let frames = 5;
+        let mut gif = Vec::new();
+        sun_sweep_gif(
+            &ridge,
+            (30.0, 30.0),
+            30_f32.to_radians(),
+            sweep(0.0, 360_f32.to_radians(), frames),
+            12.0,
+            &mut gif,
+        )
+        .unwrap();
+
+        let decoded = GifDecoder::new(Cursor::new(gif))
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+        assert_eq!(decoded.len(), frames);
+        for frame in decoded {
+            assert_eq!(frame.buffer().dimensions(), (32, 24));
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use crate::locate_tile;
+use nalgebra::{DMatrix, Scalar};
+use nasadem::{
+    geo::{Coord, Rect},
+    Elev, NasademError, Tile, VOID,
+};
+use serde_json::Value;
+use std::{io, path::Path};
+
+/// Distance, in samples, within which a bounding box edge is
+/// considered to lie exactly on a sample.
+const EDGE_EPSILON: f64 = 1e-6;
+
+/// Returns the bounding box of the geometry in the `GeoJSON` file at
+/// `path`.
+///
+/// The file may hold a geometry, a `Feature`, or a
+/// `FeatureCollection`. Its top-level `bbox` member is used if
+/// present; otherwise the box spans every position in the file.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read, isn't JSON, or has no
+/// positions.
+pub fn geojson_bbox<P: AsRef<Path>>(path: P) -> io::Result<Rect<f64>> {
+    let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+    let path = path.as_ref();
+    let json: Value = serde_json::from_str(&std::fs::read_to_string(path)?)
+        .map_err(|e| invalid(format!("{}: {e}", path.display())))?;
+    if let Some([w, s, e, n]) = json["bbox"]
+        .as_array()
+        .and_then(|bbox| bbox.iter().map(Value::as_f64).collect::<Option<Vec<_>>>())
+        .as_deref()
+    {
+        return Ok(Rect::new(Coord { x: *w, y: *s }, Coord { x: *e, y: *n }));
+    }
+    let mut bounds: Option<Rect<f64>> = None;
+    visit_positions(&json, &mut |coord| {
+        bounds = Some(bounds.map_or(Rect::new(coord, coord), |rect| {
+            Rect::new(
+                Coord {
+                    x: rect.min().x.min(coord.x),
+                    y: rect.min().y.min(coord.y),
+                },
+                Coord {
+                    x: rect.max().x.max(coord.x),
+                    y: rect.max().y.max(coord.y),
+                },
+            )
+        }));
+    });
+    bounds.ok_or_else(|| invalid(format!("{} has no coordinates", path.display())))
+}
+
+/// Calls `f` with every `[lon, lat]` position under `json`'s
+/// `coordinates`, `geometry`, `geometries`, and `features` members.
+fn visit_positions(json: &Value, f: &mut impl FnMut(Coord<f64>)) {
+    if !json.is_object() {
+        return;
+    }
+    visit_coordinates(&json["coordinates"], f);
+    visit_positions(&json["geometry"], f);
+    for key in ["geometries", "features"] {
+        if let Some(children) = json[key].as_array() {
+            for child in children {
+                visit_positions(child, f);
+            }
+        }
+    }
+}
+
+/// Calls `f` with each position in a (possibly nested) `coordinates`
+/// array.
+fn visit_coordinates(coordinates: &Value, f: &mut impl FnMut(Coord<f64>)) {
+    let Some(array) = coordinates.as_array() else {
+        return;
+    };
+    match (
+        array.first().and_then(Value::as_f64),
+        array.get(1).and_then(Value::as_f64),
+    ) {
+        (Some(x), Some(y)) => f(Coord { x, y }),
+        _ => array.iter().for_each(|nested| visit_coordinates(nested, f)),
+    }
+}
+
+/// Returns the SW corners of the one-degree tiles covering `rect`,
+/// west to east, then south to north.
+///
+/// An edge lying exactly on a whole degree doesn't pull in the tile
+/// beyond it.
+#[allow(clippy::cast_possible_truncation)]
+pub fn tiles_covering(rect: Rect<f64>) -> Vec<Coord<i16>> {
+    let (min, max) = (rect.min(), rect.max());
+    let (west, south) = (min.x.floor() as i16, min.y.floor() as i16);
+    let east = (max.x.ceil() as i16 - 1).max(west);
+    let north = (max.y.ceil() as i16 - 1).max(south);
+    (south..=north)
+        .flat_map(|y| (west..=east).map(move |x| Coord { x, y }))
+        .collect()
+}
+
+/// Returns a `(rows, cols)` matrix of the samples whose centers lie
+/// within `rect`, read from the tiles in `dir` covering it.
+///
+/// Element `(0, 0)` is the NW-most sample. Samples lie on the grid of
+/// the covering tiles, which must share a resolution; voids keep the
+/// raw [`VOID`] value, as with [`tile_to_matrix`](crate::tile_to_matrix).
+///
+/// # Errors
+///
+/// Returns an error if a covering tile is missing or can't be loaded,
+/// the tiles' resolutions differ, or no sample lies within `rect`.
+pub fn mosaic<T>(dir: &Path, rect: Rect<f64>) -> Result<DMatrix<T>, NasademError>
+where
+    T: From<Elev> + Scalar + Copy,
+{
+    let tiles = tiles_covering(rect)
+        .into_iter()
+        .map(|Coord { x, y }| {
+            let center = Coord {
+                x: f64::from(x) + 0.5,
+                y: f64::from(y) + 0.5,
+            };
+            Tile::load(locate_tile(dir, center)?)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let resolution = tiles[0].resolution();
+    if tiles.iter().any(|tile| tile.resolution() != resolution) {
+        return Err(NasademError::IncompatibleGrids);
+    }
+
+    let samples_per_deg = 3600.0 / f64::from(resolution);
+    let (min, max) = (rect.min(), rect.max());
+    #[allow(clippy::cast_possible_truncation)]
+    let [west, east, south, north] = [
+        (min.x * samples_per_deg - EDGE_EPSILON).ceil(),
+        (max.x * samples_per_deg + EDGE_EPSILON).floor(),
+        (min.y * samples_per_deg - EDGE_EPSILON).ceil(),
+        (max.y * samples_per_deg + EDGE_EPSILON).floor(),
+    ]
+    .map(|v| v as i64);
+    let (Ok(cols), Ok(rows)) = (
+        usize::try_from(east - west + 1),
+        usize::try_from(north - south + 1),
+    ) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "no samples lie within the bounding box",
+        )
+        .into());
+    };
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_wrap)]
+    Ok(DMatrix::from_fn(rows, cols, |row, col| {
+        let coord = Coord {
+            x: (west + col as i64) as f64 / samples_per_deg,
+            y: (north - row as i64) as f64 / samples_per_deg,
+        };
+        let elev = tiles
+            .iter()
+            .find_map(|tile| tile.get(coord))
+            .unwrap_or(VOID);
+        T::from(elev)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{geojson_bbox, mosaic, tiles_covering};
+    use nasadem::{
+        geo::{Coord, Rect},
+        Tile,
+    };
+
+    #[test]
+    fn test_geojson_bbox() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("aoi.geojson");
+        std::fs::write(
+            &path,
+            r#"{"type": "FeatureCollection", "features": [
+                {"type": "Feature", "properties": {}, "geometry": {"type": "Polygon",
+                  "coordinates": [[[-71.4, 44.2], [-71.2, 44.2], [-71.3, 44.35], [-71.4, 44.2]]]}},
+                {"type": "Feature", "properties": {}, "geometry": {"type": "Point",
+                  "coordinates": [-71.5, 44.3]}}
+            ]}"#,
+        )
+        .unwrap();
+        let rect = geojson_bbox(&path).unwrap();
+        assert_eq!(rect.min(), Coord { x: -71.5, y: 44.2 });
+        assert_eq!(rect.max(), Coord { x: -71.2, y: 44.35 });
+
+        std::fs::write(
+            &path,
+            r#"{"type": "Feature", "bbox": [-72, 44, -71, 45], "geometry": null}"#,
+        )
+        .unwrap();
+        let rect = geojson_bbox(&path).unwrap();
+        assert_eq!(rect.min(), Coord { x: -72.0, y: 44.0 });
+        assert_eq!(rect.max(), Coord { x: -71.0, y: 45.0 });
+
+        for bad in ["", "{}", r#"{"type": "Point", "coordinates": []}"#] {
+            std::fs::write(&path, bad).unwrap();
+            let err = geojson_bbox(&path).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData, "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn test_tiles_covering() {
+        let rect = |w, s, e, n| Rect::new(Coord { x: w, y: s }, Coord { x: e, y: n });
+        assert_eq!(
+            tiles_covering(rect(-71.4, 44.2, -71.2, 44.35)),
+            vec![Coord { x: -72, y: 44 }]
+        );
+        // Whole-degree edges stay on this side.
+        assert_eq!(
+            tiles_covering(rect(-72.0, 44.0, -71.0, 45.0)),
+            vec![Coord { x: -72, y: 44 }]
+        );
+        assert_eq!(
+            tiles_covering(rect(-0.5, -0.5, 0.5, 0.5)),
+            vec![
+                Coord { x: -1, y: -1 },
+                Coord { x: 0, y: -1 },
+                Coord { x: -1, y: 0 },
+                Coord { x: 0, y: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mosaic() {
+        let dir = crate::three_arcsecond_dir();
+        let tile = Tile::load(dir.join("N44W072.hgt")).unwrap();
+        let rect = Rect::new(Coord { x: -71.4, y: 44.2 }, Coord { x: -71.2, y: 44.35 });
+        let matrix = mosaic::<i16>(&dir, rect).unwrap();
+        // 0.2° by 0.15° at 1200 samples per degree.
+        assert_eq!(matrix.shape(), (181, 241));
+        let nw = tile
+            .geo_to_xy_mode(Coord { x: -71.4, y: 44.35 }, nasadem::SnapMode::Nearest)
+            .unwrap();
+        for (row, col) in [(0, 0), (90, 120), (180, 240)] {
+            assert_eq!(Some(matrix[(row, col)]), tile.get((nw.0 + col, nw.1 + row)));
+        }
+        let (lo, hi) = tile.elevation_range_in(rect).unwrap();
+        assert_eq!(matrix.min(), lo);
+        assert_eq!(matrix.max(), hi);
+
+        let missing = Rect::new(Coord { x: -71.5, y: 44.5 }, Coord { x: -70.5, y: 44.6 });
+        assert!(mosaic::<i16>(&dir, missing).is_err());
+    }
+}
@@ -0,0 +1,44 @@
+use image::{
+    imageops::{self, FilterType},
+    ImageBuffer, Pixel,
+};
+
+/// Returns `img`, a north-up raster on an equal-angle grid, resized
+/// so each pixel covers as much ground east-west as it does
+/// north-south at latitude `lat_deg`.
+///
+/// A degree of longitude spans `cos(lat)` as much ground as a degree
+/// of latitude, so the width is scaled by `cos(lat_deg)` and the
+/// height is unchanged.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+pub fn equal_ground_aspect<P>(
+    img: &ImageBuffer<P, Vec<P::Subpixel>>,
+    lat_deg: f64,
+) -> ImageBuffer<P, Vec<P::Subpixel>>
+where
+    P: Pixel + 'static,
+{
+    let (width, height) = img.dimensions();
+    let width = (f64::from(width) * lat_deg.to_radians().cos())
+        .round()
+        .max(1.0) as u32;
+    imageops::resize(img, width, height, FilterType::Triangle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::equal_ground_aspect;
+    use image::GrayImage;
+
+    #[test]
+    fn test_equal_ground_aspect() {
+        let img = GrayImage::new(100, 80);
+        assert_eq!(equal_ground_aspect(&img, 0.0).dimensions(), (100, 80));
+        assert_eq!(equal_ground_aspect(&img, 60.0).dimensions(), (50, 80));
+        assert_eq!(equal_ground_aspect(&img, -60.0).dimensions(), (50, 80));
+    }
+}
@@ -0,0 +1,101 @@
+use nasadem::Tile;
+use std::{fs::File, io::BufWriter, path::Path};
+use tiff::{
+    encoder::{colortype::ColorType, TiffEncoder, TiffValue},
+    tags::Tag,
+    TiffResult,
+};
+
+/// `GTModelTypeGeoKey`: raster space is geographic lat/lon.
+const MODEL_TYPE_GEOGRAPHIC: [u16; 4] = [1024, 0, 1, 2];
+
+/// `GTRasterTypeGeoKey`: each pixel covers an area.
+const RASTER_PIXEL_IS_AREA: [u16; 4] = [1025, 0, 1, 1];
+
+/// `GeographicTypeGeoKey`: WGS 84.
+const GEOGRAPHIC_TYPE_WGS84: [u16; 4] = [2048, 0, 1, 4326];
+
+/// Writes `data`, a row-major raster with the same dimensions as
+/// `tile`, as a single-band `GeoTIFF` georeferenced to `tile`.
+///
+/// The color type `C` determines the band's sample type,
+/// e.g. [`Gray16`] for a `u16` hillshade or [`GrayI16`] for raw
+/// elevation.
+///
+/// [`Gray16`]: tiff::encoder::colortype::Gray16
+/// [`GrayI16`]: tiff::encoder::colortype::GrayI16
+#[allow(clippy::cast_possible_truncation)]
+pub fn save_geotiff<C, P>(tile: &Tile, data: &[C::Inner], path: P) -> TiffResult<()>
+where
+    C: ColorType,
+    [C::Inner]: TiffValue,
+    P: AsRef<Path>,
+{
+    let (cols, rows) = tile.dimensions();
+    let deg_per_sample = f64::from(tile.resolution()) / 3600.0;
+    // The geotransform is anchored at the NW corner of the NW
+    // sample's area, which is half a sample from its center.
+    let nw_center = tile.iter().next().expect("tiles are never empty").geo();
+    let ul_lon = nw_center.x - deg_per_sample / 2.0;
+    let ul_lat = nw_center.y + deg_per_sample / 2.0;
+
+    let mut geo_keys = vec![1, 1, 0, 3];
+    geo_keys.extend(MODEL_TYPE_GEOGRAPHIC);
+    geo_keys.extend(RASTER_PIXEL_IS_AREA);
+    geo_keys.extend(GEOGRAPHIC_TYPE_WGS84);
+
+    let mut encoder = TiffEncoder::new(BufWriter::new(File::create(path)?))?;
+    let mut image = encoder.new_image::<C>(cols as u32, rows as u32)?;
+    image.encoder().write_tag(
+        Tag::ModelPixelScaleTag,
+        &[deg_per_sample, deg_per_sample, 0.0][..],
+    )?;
+    image.encoder().write_tag(
+        Tag::ModelTiepointTag,
+        &[0.0, 0.0, 0.0, ul_lon, ul_lat, 0.0][..],
+    )?;
+    image
+        .encoder()
+        .write_tag(Tag::GeoKeyDirectoryTag, &geo_keys[..])?;
+    image.write_data(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::save_geotiff;
+    use crate::{cell_size_m, matrix_to_image, shade, tile_to_matrix};
+    use approx::assert_relative_eq;
+    use nasadem::Tile;
+    use std::fs::File;
+    use tiff::{decoder::Decoder, encoder::colortype::Gray16, tags::Tag, ColorType};
+
+    #[test]
+    fn test_hillshade_geotiff() {
+        let mut path = crate::three_arcsecond_dir();
+        path.push("N44W072.hgt");
+        let tile = Tile::load(path).unwrap();
+        let shaded = shade(
+            45_f32.to_radians(),
+            315_f32.to_radians(),
+            cell_size_m(tile.resolution(), 44.5),
+            1.0,
+            &tile_to_matrix(&tile),
+        );
+        let img = matrix_to_image::<u16>(&shaded);
+
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("N44W072.tif");
+        save_geotiff::<Gray16, _>(&tile, img.as_raw(), &out).unwrap();
+
+        let mut decoder = Decoder::new(File::open(&out).unwrap()).unwrap();
+        assert_eq!(decoder.colortype().unwrap(), ColorType::Gray(16));
+        assert_eq!(decoder.dimensions().unwrap(), (1201, 1201));
+        let tiepoint = decoder.get_tag_f64_vec(Tag::ModelTiepointTag).unwrap();
+        let half_sample = 1.5 / 3600.0;
+        assert_relative_eq!(tiepoint[3], -72.0 - half_sample);
+        assert_relative_eq!(tiepoint[4], 45.0 + half_sample);
+        let scale = decoder.get_tag_f64_vec(Tag::ModelPixelScaleTag).unwrap();
+        assert_relative_eq!(scale[0], 3.0 / 3600.0);
+        assert_relative_eq!(scale[1], 3.0 / 3600.0);
+    }
+}
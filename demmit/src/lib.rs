@@ -0,0 +1,48 @@
+//! Rendering routines backing the `demmit` CLI.
+
+mod animate;
+mod aoi;
+mod aspect;
+mod geotiff;
+mod locate;
+mod ocean;
+mod par;
+mod photo;
+mod ramp;
+mod relief;
+mod shade;
+mod sun;
+
+#[cfg(feature = "simd")]
+pub use crate::shade::shade_simd;
+pub use crate::{
+    animate::{sun_sweep_gif, sweep},
+    aoi::{geojson_bbox, mosaic, tiles_covering},
+    aspect::equal_ground_aspect,
+    geotiff::save_geotiff,
+    locate::{locate_tile, parse_lat_lon},
+    ocean::{mask_ocean, OCEAN_COLOR},
+    par::par_chunks_mut,
+    photo::photo_time_and_place,
+    ramp::ColorRamp,
+    relief::{blend_relief, hypsometric_tint, multiply_blend},
+    shade::{
+        cell_size_m, matrix_to_image, shade, shade_multidirectional,
+        shade_multidirectional_to_image, shade_to_image, tile_to_matrix,
+        MULTIDIRECTIONAL_AZIMUTHS_DEG,
+    },
+    sun::sun_position,
+};
+
+#[cfg(test)]
+fn three_arcsecond_dir() -> std::path::PathBuf {
+    [
+        env!("CARGO_MANIFEST_DIR"),
+        "..",
+        "data",
+        "nasadem",
+        "3arcsecond",
+    ]
+    .iter()
+    .collect()
+}
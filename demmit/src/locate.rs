@@ -0,0 +1,81 @@
+use nasadem::{geo::Coord, tile_name_for};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Parses a `lat,lon` pair of decimal degrees into a coordinate.
+pub fn parse_lat_lon(s: &str) -> Result<Coord<f64>, String> {
+    let (lat, lon) = s
+        .split_once(',')
+        .ok_or_else(|| format!("expected 'lat,lon', got '{s}'"))?;
+    let lat: f64 = lat
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid latitude '{lat}': {e}"))?;
+    let lon: f64 = lon
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid longitude '{lon}': {e}"))?;
+    if !(-90.0..=90.0).contains(&lat) {
+        return Err(format!("latitude {lat} out of range"));
+    }
+    if !(-180.0..=180.0).contains(&lon) {
+        return Err(format!("longitude {lon} out of range"));
+    }
+    Ok(Coord { x: lon, y: lat })
+}
+
+/// Returns the path of the tile in `dir` covering `coord`.
+///
+/// Both the canonical (`N44W072.hgt`) and lowercase file names are
+/// tried.
+pub fn locate_tile(dir: &Path, coord: Coord<f64>) -> io::Result<PathBuf> {
+    let file_name = tile_name_for(coord);
+    [file_name.clone(), file_name.to_lowercase()]
+        .into_iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("expected tile {file_name} not found in {}", dir.display()),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{locate_tile, parse_lat_lon};
+    use nasadem::geo::Coord;
+
+    #[test]
+    fn test_parse_lat_lon() {
+        assert_eq!(
+            parse_lat_lon("44.2705,-71.30325"),
+            Ok(Coord {
+                x: -71.30325,
+                y: 44.2705
+            })
+        );
+        assert_eq!(parse_lat_lon(" -0.5 , 0.5 "), Ok(Coord { x: 0.5, y: -0.5 }));
+        assert!(parse_lat_lon("44.2705").is_err());
+        assert!(parse_lat_lon("91,0").is_err());
+        assert!(parse_lat_lon("0,east").is_err());
+    }
+
+    #[test]
+    fn test_locate_tile() {
+        let dir = crate::three_arcsecond_dir();
+        let mt_washington = Coord {
+            x: -71.30325,
+            y: 44.2705,
+        };
+        let path = locate_tile(&dir, mt_washington).unwrap();
+        assert_eq!(path, dir.join("N44W072.hgt"));
+
+        let err = locate_tile(&dir, Coord { x: 0.5, y: -0.5 }).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+        assert!(err.to_string().contains("S01E000.hgt"));
+    }
+}
@@ -1,12 +1,33 @@
-use camino::Utf8PathBuf;
-use clap::{Args, Parser, Subcommand, ValueEnum};
-use nasadem::Tile;
+use anyhow::bail;
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use demmit::{
+    blend_relief, cell_size_m, equal_ground_aspect, geojson_bbox, hypsometric_tint, locate_tile,
+    mask_ocean, mosaic, multiply_blend, par_chunks_mut, parse_lat_lon, photo_time_and_place,
+    save_geotiff, shade_multidirectional, shade_multidirectional_to_image, shade_to_image,
+    sun_position, sun_sweep_gif, sweep, tile_to_matrix, tiles_covering, ColorRamp,
+    MULTIDIRECTIONAL_AZIMUTHS_DEG,
+};
+use image::{EncodableLayout, ImageBuffer, ImageFormat, Luma, PixelWithColorType, RgbImage};
+use nalgebra::DMatrix;
+use nasadem::{extract_resolution, geo::Coord, parse_sw_corner, Elev, Tile, VOID};
+use num_traits::AsPrimitive;
+use std::{
+    fs::File,
+    io::{BufWriter, Cursor},
+    time::Instant,
+};
+use tiff::encoder::colortype::{Gray16, GrayI16};
 
 type AnyRes = anyhow::Result<()>;
 
 /// A NASADEM/SRTM '.hgt' file multitool.
 #[derive(Clone, Parser)]
 struct Cli {
+    /// Log progress to stderr.
+    #[clap(long, short, global = true)]
+    verbose: bool,
+
     #[command(subcommand)]
     command: SubCmd,
 }
@@ -15,75 +36,977 @@ struct Cli {
 enum SubCmd {
     /// Render a NASADEM/SRTM '.hgt' file as an image.
     Render(RenderArgs),
+
+    /// Render an animated GIF of a hillshade as the sun sweeps
+    /// across the sky.
+    Animate(AnimateArgs),
+
+    /// Write successively halved overviews of a NASADEM/SRTM '.hgt'
+    /// file as images.
+    Pyramid(PyramidArgs),
+
+    /// Render every NASADEM/SRTM '.hgt' file in a directory as an
+    /// image.
+    RenderDir(RenderDirArgs),
+
+    /// Print what can be determined about a NASADEM/SRTM '.hgt' file.
+    Info(InfoArgs),
+
+    /// Print the elevation, in meters, at a coordinate.
+    Sample(SampleArgs),
 }
 
 #[derive(Clone, Args)]
+#[allow(clippy::struct_excessive_bools)]
+#[clap(group(ArgGroup::new("region").args(["coord", "bbox_file"])))]
 struct RenderArgs {
     /// Bit depth
     #[clap(long, short)]
     depth: Option<BitDepth>,
 
+    /// Render a hillshade instead of scaled elevation.
+    #[clap(long)]
+    hillshade: bool,
+
+    /// Sun azimuth in degrees clockwise from north (hillshade and
+    /// blend only).
+    #[clap(long, default_value_t = 315.0)]
+    azimuth: f32,
+
+    /// Sun elevation in degrees above the horizon (hillshade and
+    /// blend only).
+    #[clap(long, default_value_t = 45.0)]
+    elevation: f32,
+
+    /// Vertical exaggeration applied to elevations before shading
+    /// (hillshade and blend only).
+    ///
+    /// Unitless; values above 1 bring out relief in flat terrain.
+    #[clap(long, default_value_t = 1.0)]
+    z_factor: f32,
+
+    /// Soften the hillshade by averaging light from the southwest,
+    /// west, northwest, and north, ignoring `--azimuth` (hillshade and
+    /// blend only).
+    #[clap(long, conflicts_with = "match_photo")]
+    multidirectional: bool,
+
+    /// Light the render like this photo by placing the sun where it
+    /// was when and where the photo was taken.
+    ///
+    /// The sun position is computed from the photo's EXIF capture
+    /// time and GPS location. If either is missing, `--azimuth` and
+    /// `--elevation` are used instead.
+    #[clap(long)]
+    match_photo: Option<Utf8PathBuf>,
+
+    /// Write a georeferenced `GeoTIFF`.
+    ///
+    /// Hillshades are written as 16-bit unsigned shade values, and
+    /// elevation as raw signed 16-bit meters.
+    #[clap(long)]
+    geotiff: bool,
+
+    /// Paint cells at or below this elevation (meters) as ocean.
+    ///
+    /// Produces an RGB image of the hillshade with a solid ocean
+    /// color. Voids are also painted as ocean.
+    #[clap(
+        long,
+        requires = "hillshade",
+        conflicts_with = "geotiff",
+        allow_hyphen_values = true
+    )]
+    sea_level: Option<i16>,
+
+    /// Blend a hypsometric color layer with the hillshade.
+    ///
+    /// Produces an RGB shaded color relief image using `--azimuth`
+    /// and `--elevation` for the sun.
+    #[clap(long, conflicts_with_all = ["geotiff", "sea_level"])]
+    blend: Option<Blend>,
+
+    /// Strength of the hillshade's shadows with `--blend relief`, from
+    /// 0 (none) to 1 (full).
+    #[clap(long, default_value_t = 1.0, requires = "blend")]
+    opacity: f32,
+
+    /// Color elevation with a custom ramp read from a CSV file of
+    /// `elevation,r,g,b` stops.
+    ///
+    /// Colors are interpolated between stops, and elevations beyond
+    /// the lowest or highest stop take its color. With `--blend`, the
+    /// ramp replaces the built-in hypsometric tint.
+    #[clap(long, conflicts_with_all = ["geotiff", "sea_level", "hillshade"])]
+    ramp_file: Option<Utf8PathBuf>,
+
+    /// Resize the output so each pixel covers equal east-west and
+    /// north-south ground distance at the tile's center latitude.
+    ///
+    /// Tiles are one degree square, but a degree of longitude shrinks
+    /// by `cos(lat)`, so the image width is scaled by that factor.
+    #[clap(long, conflicts_with = "geotiff")]
+    equal_ground_aspect: bool,
+
+    /// Print how long each phase (load, matrix, shade, encode,
+    /// write) takes to stderr.
+    #[clap(long)]
+    timing: bool,
+
+    /// What to do when an output file already exists.
+    #[clap(long, value_enum, default_value_t = OverwritePolicy::Overwrite)]
+    overwrite_policy: OverwritePolicy,
+
+    /// Render the tile covering this 'lat,lon' coordinate instead of
+    /// `src`.
+    ///
+    /// The tile is looked up by name in `--dir`. When rendering by
+    /// coordinate, the only positional argument is the optional
+    /// output file name.
+    #[clap(long, value_parser = parse_lat_lon, requires = "dir", allow_hyphen_values = true)]
+    coord: Option<Coord<f64>>,
+
+    /// Render the bounding box of the geometry in this `GeoJSON` file
+    /// instead of `src`.
+    ///
+    /// The box is cut from a mosaic of the tiles in `--dir` covering
+    /// it, which must all be present and share a resolution. As with
+    /// `--coord`, the only positional argument is the optional output
+    /// file name, which defaults to the `GeoJSON` file's name.
+    #[clap(long, requires = "dir", conflicts_with = "geotiff")]
+    bbox_file: Option<Utf8PathBuf>,
+
+    /// Directory of NASADEM/SRTM hgt files to search with `--coord`
+    /// or `--bbox-file`.
+    #[clap(long, requires = "region")]
+    dir: Option<Utf8PathBuf>,
+
     /// Source NASADEM/SRTM hgt file.
-    src: Utf8PathBuf,
+    #[clap(required_unless_present = "region")]
+    src: Option<Utf8PathBuf>,
 
     /// Optional output file name.
     ///
     /// Image format will be based on `dest`'s extension.
     ///
-    /// If not specified, a png will be written with the tile's
-    /// basename in the tile's dir.
+    /// If not specified, a png (or tif with `--geotiff`) will be
+    /// written with the tile's basename in the tile's dir.
     dest: Option<Utf8PathBuf>,
 }
 
+#[derive(Clone, Args)]
+struct AnimateArgs {
+    /// Sun azimuth of the first frame in degrees clockwise from north.
+    #[clap(long, default_value_t = 0.0)]
+    az_start: f32,
+
+    /// Sun azimuth of the last frame in degrees clockwise from north.
+    #[clap(long, default_value_t = 360.0)]
+    az_end: f32,
+
+    /// Number of frames.
+    #[clap(long, default_value_t = 36)]
+    frames: usize,
+
+    /// Frames per second.
+    #[clap(long, default_value_t = 10.0)]
+    fps: f32,
+
+    /// Sun elevation in degrees above the horizon.
+    #[clap(long, default_value_t = 45.0)]
+    elevation: f32,
+
+    /// Source NASADEM/SRTM hgt file.
+    src: Utf8PathBuf,
+
+    /// Output GIF file name.
+    dest: Utf8PathBuf,
+}
+
+#[derive(Clone, Args)]
+struct PyramidArgs {
+    /// Number of overview levels.
+    ///
+    /// Level `n` averages `2^n` x `2^n` blocks of the source tile.
+    #[clap(long, default_value_t = 4)]
+    levels: u32,
+
+    /// Overview image file extension, which determines the format.
+    #[clap(long, default_value = "png")]
+    ext: String,
+
+    /// Directory to write overviews to, named '<tile>-<level>.<ext>'.
+    #[clap(long)]
+    out_dir: Utf8PathBuf,
+
+    /// Source NASADEM/SRTM hgt file.
+    src: Utf8PathBuf,
+}
+
+#[derive(Clone, Args)]
+#[allow(clippy::struct_excessive_bools)]
+struct RenderDirArgs {
+    /// Render hillshades instead of scaled elevation.
+    #[clap(long)]
+    hillshade: bool,
+
+    /// Image file extension, which determines the format.
+    #[clap(long, default_value = "png")]
+    ext: String,
+
+    /// List each source and the output it would be rendered to on
+    /// stdout without rendering anything.
+    #[clap(long)]
+    dry_run: bool,
+
+    /// Print how long each phase (load, matrix, shade, encode,
+    /// write) takes to stderr.
+    #[clap(long)]
+    timing: bool,
+
+    /// What to do when an output file already exists.
+    #[clap(long, value_enum, default_value_t = OverwritePolicy::Overwrite)]
+    overwrite_policy: OverwritePolicy,
+
+    /// Scale every tile's elevation to the lowest and highest
+    /// elevation across all tiles, found in a first pass, so
+    /// brightness matches across tile edges.
+    #[clap(long, conflicts_with = "hillshade")]
+    two_pass: bool,
+
+    /// Directory of NASADEM/SRTM hgt files.
+    src_dir: Utf8PathBuf,
+
+    /// Directory to write images to, named after their tiles.
+    dest_dir: Utf8PathBuf,
+}
+
+#[derive(Clone, Args)]
+struct InfoArgs {
+    /// Only report what can be derived from the file name and length,
+    /// without reading any samples.
+    #[clap(long)]
+    raw: bool,
+
+    /// Source NASADEM/SRTM hgt file.
+    src: Utf8PathBuf,
+}
+
+#[derive(Clone, Args)]
+struct SampleArgs {
+    /// 'lat,lon' coordinate to sample.
+    #[clap(long, value_parser = parse_lat_lon, allow_hyphen_values = true)]
+    coord: Coord<f64>,
+
+    /// Bilinearly interpolate between the four surrounding samples
+    /// instead of using the nearest one.
+    #[clap(long)]
+    interpolate: bool,
+
+    /// Source NASADEM/SRTM hgt file.
+    src: Utf8PathBuf,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Blend {
+    /// Multiply each color channel by the normalized hillshade.
+    Multiply,
+    /// Multiply each color channel by the hillshade at `--opacity`.
+    Relief,
+}
+
+impl Blend {
+    /// Returns `tint` blended with `shaded`, lit by `light`.
+    fn apply(
+        self,
+        tint: &RgbImage,
+        shaded: &DMatrix<f32>,
+        light: &Light,
+        opacity: f32,
+    ) -> RgbImage {
+        match self {
+            Self::Multiply => multiply_blend(tint, shaded, light.sun_elev_rad),
+            Self::Relief => blend_relief(tint, shaded, opacity),
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OverwritePolicy {
+    /// Fail if the output exists.
+    Error,
+    /// Leave an existing output untouched and move on.
+    Skip,
+    /// Replace an existing output.
+    Overwrite,
+}
+
 #[derive(Clone, Copy, ValueEnum)]
 enum BitDepth {
     _8,
     _16,
 }
 
-fn render(RenderArgs { depth, src, dest }: RenderArgs) -> AnyRes {
+fn render(
+    RenderArgs {
+        depth,
+        hillshade,
+        mut azimuth,
+        mut elevation,
+        z_factor,
+        multidirectional,
+        match_photo,
+        opacity,
+        geotiff,
+        sea_level,
+        blend,
+        ramp_file,
+        equal_ground_aspect,
+        timing,
+        overwrite_policy,
+        coord,
+        dir,
+        src,
+        dest,
+        ..
+    }: RenderArgs,
+    verbose: bool,
+) -> AnyRes {
+    if let Some(photo) = match_photo {
+        if let Some(sun) = photo_sun(&photo, verbose)? {
+            (azimuth, elevation) = sun;
+        }
+    }
+    let (src, dest) = render_src_dest(coord, dir, src, dest)?;
+    let out = render_out_path(&src, dest, if geotiff { "tif" } else { "png" });
+    if !should_write(&out, overwrite_policy, verbose)? {
+        return Ok(());
+    }
+    let ramp = ramp_file.map(ColorRamp::from_csv).transpose()?;
+    let mut timing = Timing::new(timing);
     let tile = Tile::load(&src)?;
-    let out = dest.map_or_else(
-        || {
-            let mut out = src.clone();
-            out.set_extension("png");
-            out
-        },
+    timing.phase("load");
+    let center_lat = tile.row_latitude(tile.dimensions().1 / 2);
+    let aspect_lat = equal_ground_aspect.then_some(center_lat);
+    let cell_m = cell_size_m(tile.resolution(), center_lat);
+    let light = Light::new((azimuth, elevation), multidirectional, cell_m, z_factor);
+
+    if geotiff {
+        if hillshade {
+            let img = light.shade_to_image::<u16>(&tile_to_matrix(&tile));
+            timing.phase("shade");
+            save_geotiff::<Gray16, _>(&tile, img.as_raw(), &out)?;
+        } else {
+            let elevations = tile_to_matrix::<i16>(&tile).transpose();
+            timing.phase("matrix");
+            save_geotiff::<GrayI16, _>(&tile, elevations.as_slice(), &out)?;
+        }
+        timing.phase("encode and write");
+        return Ok(());
+    }
+
+    let style = Style {
+        depth,
+        hillshade,
+        sea_level,
+        blend,
+        opacity,
+        ramp,
+        aspect_lat,
+    };
+    render_tile(&tile, &light, style, &out, &mut timing)
+}
+
+/// Renders the bounding box of the geometry in `--bbox-file` from a
+/// mosaic of the tiles in `--dir` covering it.
+///
+/// Supports the same outputs as a single tile render, except
+/// `--geotiff`.
+fn render_bbox(
+    RenderArgs {
+        depth,
+        hillshade,
+        mut azimuth,
+        mut elevation,
+        z_factor,
+        multidirectional,
+        match_photo,
+        opacity,
+        sea_level,
+        blend,
+        ramp_file,
+        equal_ground_aspect,
+        timing,
+        overwrite_policy,
+        bbox_file,
+        dir,
+        src,
+        dest,
+        ..
+    }: RenderArgs,
+    verbose: bool,
+) -> AnyRes {
+    let (Some(bbox_file), Some(dir)) = (bbox_file, dir) else {
+        bail!("`--bbox-file` requires `--dir`");
+    };
+    if dest.is_some() {
+        bail!("too many paths; `--bbox-file` replaces `src`");
+    }
+    if let Some(photo) = match_photo {
+        if let Some(sun) = photo_sun(&photo, verbose)? {
+            (azimuth, elevation) = sun;
+        }
+    }
+    // The only positional argument is the output, which clap parses
+    // as `src`.
+    let out = render_out_path(&bbox_file, src, "png");
+    if !should_write(&out, overwrite_policy, verbose)? {
+        return Ok(());
+    }
+    let ramp = ramp_file.map(ColorRamp::from_csv).transpose()?;
+    let mut timing = Timing::new(timing);
+    let rect = geojson_bbox(&bbox_file)?;
+    let elevations = mosaic::<Elev>(dir.as_std_path(), rect)?;
+    let (resolution, _) = extract_resolution(locate_tile(dir.as_std_path(), rect.center())?)?;
+    // Only the samples are rendered, so the mosaic's corner is that
+    // of its SW tile rather than the box's exact one.
+    let tile = Tile::from_samples(
+        tiles_covering(rect)[0],
+        resolution,
+        (elevations.ncols(), elevations.nrows()),
+        elevations.transpose().as_slice().to_vec(),
+    );
+    timing.phase("load");
+    let cell_m = cell_size_m(resolution, rect.center().y);
+    let light = Light::new((azimuth, elevation), multidirectional, cell_m, z_factor);
+    let style = Style {
+        depth,
+        hillshade,
+        sea_level,
+        blend,
+        opacity,
+        ramp,
+        aspect_lat: equal_ground_aspect.then(|| rect.center().y),
+    };
+    render_tile(&tile, &light, style, &out, &mut timing)
+}
+
+/// How [`render_tile`] draws a tile's samples.
+struct Style {
+    depth: Option<BitDepth>,
+    hillshade: bool,
+    sea_level: Option<i16>,
+    blend: Option<Blend>,
+    opacity: f32,
+    ramp: Option<ColorRamp>,
+
+    /// Latitude to resize the image to equal ground aspect at, if
+    /// any.
+    aspect_lat: Option<f64>,
+}
+
+/// Renders `tile` to `out` as `style` asks, shading it with `light`.
+///
+/// Shared by single tile and bounding box renders, which differ only
+/// in where their samples come from.
+fn render_tile(
+    tile: &Tile,
+    light: &Light,
+    Style {
+        depth,
+        hillshade,
+        sea_level,
+        blend,
+        opacity,
+        ramp,
+        aspect_lat,
+    }: Style,
+    out: &Utf8Path,
+    timing: &mut Timing,
+) -> AnyRes {
+    if let Some(blend) = blend {
+        let data = tile_to_matrix(tile);
+        timing.phase("matrix");
+        let shaded = light.shade(&data);
+        let tint = ramp.map_or_else(|| hypsometric_tint(&data), |ramp| ramp.apply(&data));
+        let img = blend.apply(&tint, &shaded, light, opacity);
+        timing.phase("shade");
+        return save_image(&img, aspect_lat, out, timing);
+    }
+
+    if let Some(ramp) = ramp {
+        let img = ramp.apply(&tile_to_matrix(tile));
+        timing.phase("matrix");
+        return save_image(&img, aspect_lat, out, timing);
+    }
+
+    if hillshade {
+        let data = tile_to_matrix(tile);
+        timing.phase("matrix");
+        if let Some(sea_level) = sea_level {
+            let shaded = light.shade(&data);
+            let img = mask_ocean(&shaded, &tile_to_matrix(tile), sea_level);
+            timing.phase("shade");
+            return save_image(&img, aspect_lat, out, timing);
+        }
+        if sixteen_bit_gray(depth, out) {
+            let img = light.shade_to_image::<u16>(&data);
+            timing.phase("shade");
+            return save_image(&img, aspect_lat, out, timing);
+        }
+        let img = light.shade_to_image::<u8>(&data);
+        timing.phase("shade");
+        return save_image(&img, aspect_lat, out, timing);
+    }
+
+    if sixteen_bit_gray(depth, out) {
+        let img = tile.to_image::<u16>();
+        timing.phase("matrix");
+        return save_image(&img, aspect_lat, out, timing);
+    }
+    let img = tile.to_image::<u8>();
+    timing.phase("matrix");
+    save_image(&img, aspect_lat, out, timing)
+}
+
+/// Returns whether to render grayscale at 16 bits, either because
+/// `depth` asks for it or by default for formats that support it.
+fn sixteen_bit_gray(depth: Option<BitDepth>, out: &Utf8Path) -> bool {
+    matches!(
+        (depth, out.extension()),
+        (None | Some(BitDepth::_16), Some("png" | "tif" | "tiff")) | (Some(BitDepth::_16), _)
+    )
+}
+
+/// Returns the tile to render, either `src` or the one covering
+/// `coord` in `dir`, and the optional output path.
+///
+/// When rendering by coordinate, the only positional argument is the
+/// output, which clap parses as `src`.
+fn render_src_dest(
+    coord: Option<Coord<f64>>,
+    dir: Option<Utf8PathBuf>,
+    src: Option<Utf8PathBuf>,
+    dest: Option<Utf8PathBuf>,
+) -> anyhow::Result<(Utf8PathBuf, Option<Utf8PathBuf>)> {
+    Ok(match (coord, dir, src, dest) {
+        (Some(coord), Some(dir), dest, None) => {
+            let src = locate_tile(dir.as_std_path(), coord)?;
+            (Utf8PathBuf::try_from(src)?, dest)
+        }
+        (Some(_), _, _, Some(_)) => bail!("too many paths; `--coord` replaces `src`"),
+        (_, _, Some(src), dest) => (src, dest),
+        _ => bail!("either `src` or `--coord` and `--dir` are required"),
+    })
+}
+
+/// Returns where to render `src`: `dest`, or a file in `dest` if it
+/// is a directory, or next to `src` if there is no `dest`, where
+/// files not named by `dest` take `src`'s name with `default_ext`.
+fn render_out_path(src: &Utf8Path, dest: Option<Utf8PathBuf>, default_ext: &str) -> Utf8PathBuf {
+    dest.map_or_else(
+        || src.with_extension(default_ext),
         |mut out| {
             if out.is_dir() {
                 let name = src.file_name().expect("we already know src is a file");
                 out.push(name);
-                out.set_extension("png");
+                out.set_extension(default_ext);
             }
             out
         },
-    );
+    )
+}
+
+/// Returns the sun's azimuth and elevation in degrees when and where
+/// `photo` was taken, or `None` with a warning if its EXIF metadata
+/// doesn't say.
+#[allow(clippy::cast_possible_truncation)]
+fn photo_sun(photo: &Utf8Path, verbose: bool) -> anyhow::Result<Option<(f32, f32)>> {
+    let Some((time, place)) = photo_time_and_place(photo.as_std_path())? else {
+        eprintln!(
+            "warning: {photo} has no EXIF capture time and GPS location; \
+             using --azimuth and --elevation"
+        );
+        return Ok(None);
+    };
+    let (azimuth, elevation) = sun_position(time, place);
+    let (azimuth, elevation) = (azimuth.to_degrees() as f32, elevation.to_degrees() as f32);
+    if verbose {
+        eprintln!("sun at azimuth {azimuth:.1}°, elevation {elevation:.1}° in {photo}");
+    }
+    Ok(Some((azimuth, elevation)))
+}
+
+/// Returns whether to write `out` under `policy`, or an error if it
+/// exists and `policy` is [`OverwritePolicy::Error`].
+fn should_write(out: &Utf8Path, policy: OverwritePolicy, verbose: bool) -> anyhow::Result<bool> {
+    if !out.exists() {
+        return Ok(true);
+    }
+    match policy {
+        OverwritePolicy::Overwrite => Ok(true),
+        OverwritePolicy::Skip => {
+            if verbose {
+                eprintln!("skipping {out}: already exists");
+            }
+            Ok(false)
+        }
+        OverwritePolicy::Error => bail!("{out} already exists"),
+    }
+}
+
+/// Reports how long each phase of a render takes on stderr.
+struct Timing {
+    enabled: bool,
+    phase_start: Instant,
+}
+
+impl Timing {
+    fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            phase_start: Instant::now(),
+        }
+    }
+
+    /// Ends the phase named `name`, printing its duration if enabled,
+    /// and starts the next one.
+    fn phase(&mut self, name: &str) {
+        if self.enabled {
+            eprintln!("timing: {name}: {:.3?}", self.phase_start.elapsed());
+        }
+        self.phase_start = Instant::now();
+    }
+}
+
+/// The sun and surface a render's hillshade is lit with.
+struct Light {
+    sun_elev_rad: f32,
+
+    /// The sun's azimuth, or several to average.
+    azimuths_rad: Vec<f32>,
 
-    match (depth, out.extension()) {
-        (None | Some(BitDepth::_8), Some("jpg")) => {
-            let img = tile.to_image::<u8>();
-            img.save(out)?;
+    cell_m: (f32, f32),
+    z_factor: f32,
+}
+
+impl Light {
+    fn new(
+        (azimuth, elevation): (f32, f32),
+        multidirectional: bool,
+        cell_m: (f32, f32),
+        z_factor: f32,
+    ) -> Self {
+        let azimuths_rad = if multidirectional {
+            MULTIDIRECTIONAL_AZIMUTHS_DEG.map(f32::to_radians).to_vec()
+        } else {
+            vec![azimuth.to_radians()]
+        };
+        Self {
+            sun_elev_rad: elevation.to_radians(),
+            azimuths_rad,
+            cell_m,
+            z_factor,
         }
-        (None | Some(BitDepth::_16), Some("png" | "tif" | "tiff")) => {
-            let img = tile.to_image::<u16>();
-            img.save(out)?;
+    }
+
+    fn shade(&self, data: &DMatrix<f32>) -> DMatrix<f32> {
+        shade_multidirectional(
+            self.sun_elev_rad,
+            &self.azimuths_rad,
+            self.cell_m,
+            self.z_factor,
+            data,
+        )
+    }
+
+    /// Returns the hillshade of `data` as an image, shading straight
+    /// into it.
+    fn shade_to_image<Pix>(&self, data: &DMatrix<f32>) -> ImageBuffer<Luma<Pix>, Vec<Pix>>
+    where
+        Pix: image::Primitive + Send + 'static,
+        f32: AsPrimitive<Pix> + From<Pix>,
+    {
+        shade_multidirectional_to_image(
+            self.sun_elev_rad,
+            &self.azimuths_rad,
+            self.cell_m,
+            self.z_factor,
+            data,
+        )
+    }
+}
+
+/// Saves `img` to `out`, first resizing it to equal ground aspect at
+/// latitude `aspect_lat` if given.
+///
+/// The image is encoded in memory before it is written so the two
+/// phases can be timed separately.
+fn save_image<P>(
+    img: &ImageBuffer<P, Vec<P::Subpixel>>,
+    aspect_lat: Option<f64>,
+    out: &Utf8Path,
+    timing: &mut Timing,
+) -> AnyRes
+where
+    P: PixelWithColorType + 'static,
+    [P::Subpixel]: EncodableLayout,
+{
+    let format = ImageFormat::from_path(out)?;
+    let mut encoded = Cursor::new(Vec::new());
+    match aspect_lat {
+        Some(lat) => equal_ground_aspect(img, lat).write_to(&mut encoded, format)?,
+        None => img.write_to(&mut encoded, format)?,
+    }
+    timing.phase("encode");
+    std::fs::write(out, encoded.into_inner())?;
+    timing.phase("write");
+    Ok(())
+}
+
+fn animate(
+    AnimateArgs {
+        az_start,
+        az_end,
+        frames,
+        fps,
+        elevation,
+        src,
+        dest,
+    }: AnimateArgs,
+) -> AnyRes {
+    if fps <= 0.0 {
+        bail!("`--fps` must be positive");
+    }
+    let tile = Tile::load(&src)?;
+    let data = tile_to_matrix(&tile);
+    let cell_m = cell_size_m(
+        tile.resolution(),
+        tile.row_latitude(tile.dimensions().1 / 2),
+    );
+    let azimuths = sweep(az_start.to_radians(), az_end.to_radians(), frames);
+    let out = BufWriter::new(File::create(dest)?);
+    sun_sweep_gif(&data, cell_m, elevation.to_radians(), azimuths, fps, out)?;
+    Ok(())
+}
+
+fn pyramid(
+    PyramidArgs {
+        levels,
+        ext,
+        out_dir,
+        src,
+    }: PyramidArgs,
+) -> AnyRes {
+    let tile = Tile::load(&src)?;
+    let (cols, rows) = tile.dimensions();
+    let Some(max_factor) = 1_usize.checked_shl(levels) else {
+        bail!("too many levels: {levels}");
+    };
+    if (cols - 1) % max_factor != 0 || (rows - 1) % max_factor != 0 {
+        bail!("{src} cannot be halved {levels} times");
+    }
+    let stem = src.file_stem().expect("we already know src is a file");
+    std::fs::create_dir_all(&out_dir)?;
+    for level in 1..=levels {
+        let overview = tile.downsample(1 << level);
+        let out = out_dir.join(format!("{stem}-{level}.{ext}"));
+        match ext.as_str() {
+            "png" | "tif" | "tiff" => overview.to_image::<u16>().save(out)?,
+            _ => overview.to_image::<u8>().save(out)?,
         }
-        (Some(BitDepth::_16), _) => {
-            let img = tile.to_image::<u16>();
-            img.save(out)?;
+    }
+    Ok(())
+}
+
+fn render_dir(
+    RenderDirArgs {
+        hillshade,
+        ext,
+        dry_run,
+        timing,
+        overwrite_policy,
+        two_pass,
+        src_dir,
+        dest_dir,
+    }: RenderDirArgs,
+    verbose: bool,
+) -> AnyRes {
+    let mut srcs = Vec::new();
+    for entry in src_dir.read_dir_utf8()? {
+        let path = entry?.into_path();
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("hgt"))
+        {
+            srcs.push(path);
         }
-        (_, _) => {
-            let img = tile.to_image::<u8>();
-            img.save(out)?;
+    }
+    srcs.sort();
+    if verbose {
+        eprintln!("found {} tiles in {src_dir}", srcs.len());
+    }
+    if !dry_run {
+        std::fs::create_dir_all(&dest_dir)?;
+    }
+    let mut jobs = Vec::new();
+    for src in srcs {
+        let stem = src.file_stem().expect("we already know src is a file");
+        let out = dest_dir.join(format!("{stem}.{ext}"));
+        if dry_run {
+            println!("{src} -> {out}");
+        } else if should_write(&out, overwrite_policy, verbose)? {
+            jobs.push((src, out));
         }
+    }
+
+    let extent = if two_pass {
+        let ranges = par_map(&jobs, |(src, _)| Ok(Tile::memmap(src)?.elevation_range()))?;
+        let Some(extent) = ranges
+            .into_iter()
+            .filter(|&(lo, _)| lo != VOID)
+            .reduce(|(lo, hi), (tile_lo, tile_hi)| (lo.min(tile_lo), hi.max(tile_hi)))
+        else {
+            bail!("no elevation samples in {src_dir}");
+        };
+        eprintln!("global extent: {} m to {} m", extent.0, extent.1);
+        Some(extent)
+    } else {
+        None
     };
 
+    par_map(&jobs, |(src, out)| {
+        if verbose {
+            eprintln!("rendering {src} to {out}");
+        }
+        let mut timing = Timing::new(timing);
+        let tile = Tile::load(src)?;
+        timing.phase("load");
+        if hillshade {
+            let data = tile_to_matrix(&tile);
+            let cell_m = cell_size_m(
+                tile.resolution(),
+                tile.row_latitude(tile.dimensions().1 / 2),
+            );
+            timing.phase("matrix");
+            let img = shade_to_image::<u16>(
+                45_f32.to_radians(),
+                315_f32.to_radians(),
+                cell_m,
+                1.0,
+                &data,
+            );
+            timing.phase("shade");
+            save_image(&img, None, out, &mut timing)
+        } else {
+            let img = match extent {
+                Some(extent) => scaled_image(&tile, extent),
+                None => tile.to_image::<u16>(),
+            };
+            timing.phase("matrix");
+            save_image(&img, None, out, &mut timing)
+        }
+    })?;
+    Ok(())
+}
+
+/// Returns `tile`'s elevation as an image scaled so that `lo` is `0`
+/// and `hi` is [`u16::MAX`], as [`Tile::to_image`] does with the
+/// tile's own range, leaving voids black.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn scaled_image(tile: &Tile, (lo, hi): (Elev, Elev)) -> ImageBuffer<Luma<u16>, Vec<u16>> {
+    let (cols, rows) = tile.dimensions();
+    let (lo, range) = (
+        f32::from(lo),
+        f32::from(hi).max(f32::from(lo) + 1.0) - f32::from(lo),
+    );
+    ImageBuffer::from_fn(cols as u32, rows as u32, |x, y| {
+        let elev = tile.get((x as usize, y as usize)).unwrap_or(VOID);
+        let scaled = if elev == VOID {
+            0.0
+        } else {
+            ((f32::from(elev) - lo) / range).clamp(0.0, 1.0)
+        };
+        Luma([(scaled * f32::from(u16::MAX)).round() as u16])
+    })
+}
+
+/// Returns `f` of each item, in order, computed on as many threads as
+/// there are cores, or the first error.
+fn par_map<T, R>(items: &[T], f: impl Fn(&T) -> anyhow::Result<R> + Sync) -> anyhow::Result<Vec<R>>
+where
+    T: Sync,
+    R: Send,
+{
+    let mut results: Vec<Option<anyhow::Result<R>>> = items.iter().map(|_| None).collect();
+    par_chunks_mut(&mut results, 1, |start, chunk| {
+        for (item, result) in items[start..].iter().zip(chunk) {
+            *result = Some(f(item));
+        }
+    });
+    results
+        .into_iter()
+        .map(|result| result.expect("every item is mapped"))
+        .collect()
+}
+
+fn info(InfoArgs { raw, src }: InfoArgs) -> AnyRes {
+    println!("file: {src}");
+    println!("size: {} bytes", src.metadata()?.len());
+    let sw_corner = parse_sw_corner(&src);
+    match &sw_corner {
+        Ok(Coord { x, y }) => println!("sw corner: ({x}, {y})"),
+        Err(e) => println!("sw corner: {e}"),
+    }
+    let resolution = extract_resolution(&src);
+    match &resolution {
+        Ok((arcsec, (cols, rows))) => {
+            println!("resolution: {arcsec} arcseconds ({cols} x {rows} samples)");
+        }
+        Err(e) => println!("resolution: {e}"),
+    }
+    // Report the first failure as the reason the file can't load.
+    sw_corner?;
+    resolution?;
+    if !raw {
+        let tile = Tile::memmap(&src)?;
+        println!("min elevation: {} m", tile.min_elevation());
+        println!("max elevation: {} m", tile.max_elevation());
+    }
+    Ok(())
+}
+
+fn sample(
+    SampleArgs {
+        coord,
+        interpolate,
+        src,
+    }: SampleArgs,
+) -> AnyRes {
+    let tile = Tile::memmap(&src)?;
+    // The nearest sample distinguishes off-tile and void coordinates
+    // for both modes.
+    let nearest = tile.try_get(coord)?;
+    if interpolate {
+        let Some(elev) = tile.get_bilinear(coord) else {
+            bail!("a sample surrounding ({}, {}) is void", coord.x, coord.y);
+        };
+        println!("{elev:.2}");
+    } else {
+        println!("{nearest}");
+    }
     Ok(())
 }
 
 fn main() -> AnyRes {
     let cli = Cli::parse();
     match cli.command {
-        SubCmd::Render(args) => render(args),
+        SubCmd::Render(args) if args.bbox_file.is_some() => render_bbox(args, cli.verbose),
+        SubCmd::Render(args) => render(args, cli.verbose),
+        SubCmd::Animate(args) => animate(args),
+        SubCmd::Pyramid(args) => pyramid(args),
+        SubCmd::RenderDir(args) => render_dir(args, cli.verbose),
+        SubCmd::Info(args) => info(args),
+        SubCmd::Sample(args) => sample(args),
     }
 }
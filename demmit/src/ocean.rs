@@ -0,0 +1,60 @@
+use image::{Rgb, RgbImage};
+use nalgebra::DMatrix;
+use nasadem::{Elev, VOID};
+
+/// Color of cells at or below sea level.
+pub const OCEAN_COLOR: Rgb<u8> = Rgb([0x1f, 0x4e, 0x79]);
+
+/// Returns an RGB image of `land`, a `[0, 1]` intensity layer such
+/// as a hillshade, with every cell of `elevations` at or below
+/// `sea_level_m` painted [`OCEAN_COLOR`].
+///
+/// Voids are treated as ocean.
+///
+/// # Panics
+///
+/// Panics if `land` and `elevations` differ in shape.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn mask_ocean(land: &DMatrix<f32>, elevations: &DMatrix<Elev>, sea_level_m: Elev) -> RgbImage {
+    assert_eq!(land.shape(), elevations.shape());
+    let (rows, cols) = land.shape();
+    RgbImage::from_fn(cols as u32, rows as u32, |x, y| {
+        let idx = (y as usize, x as usize);
+        let elev = elevations[idx];
+        if elev == VOID || elev <= sea_level_m {
+            OCEAN_COLOR
+        } else {
+            let val = (land[idx].clamp(0.0, 1.0) * f32::from(u8::MAX)).round() as u8;
+            Rgb([val, val, val])
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mask_ocean, OCEAN_COLOR};
+    use image::Rgb;
+    use nalgebra::DMatrix;
+    use nasadem::VOID;
+
+    #[test]
+    fn test_mask_ocean() {
+        // West half is below sea level, east half is land, and the NE
+        // corner is a void.
+        let mut elevations = DMatrix::from_fn(4, 4, |_, x| if x < 2 { -5 } else { 10 });
+        elevations[(0, 3)] = VOID;
+        let land = DMatrix::from_element(4, 4, 1.0);
+        let img = mask_ocean(&land, &elevations, 0);
+        for y in 0..4 {
+            assert_eq!(img.get_pixel(0, y), &OCEAN_COLOR);
+            assert_eq!(img.get_pixel(1, y), &OCEAN_COLOR);
+            assert_eq!(img.get_pixel(2, y), &Rgb([255, 255, 255]));
+        }
+        assert_eq!(img.get_pixel(3, 0), &OCEAN_COLOR);
+        assert_eq!(img.get_pixel(3, 1), &Rgb([255, 255, 255]));
+
+        // Cells exactly at sea level are ocean.
+        let img = mask_ocean(&land, &elevations, 10);
+        assert_eq!(img.get_pixel(2, 0), &OCEAN_COLOR);
+    }
+}
@@ -0,0 +1,50 @@
+/// Calls `f` on consecutive chunks of `items`, one chunk per core,
+/// each on its own thread, passing the index of the chunk's first
+/// item.
+///
+/// Chunk lengths are a multiple of `granule`, e.g. an image's width
+/// so chunks are bands of whole rows. A panic in `f` is resumed on
+/// the calling thread.
+pub fn par_chunks_mut<T, F>(items: &mut [T], granule: usize, f: F)
+where
+    T: Send,
+    F: Fn(usize, &mut [T]) + Sync,
+{
+    let granule = granule.max(1);
+    let threads = std::thread::available_parallelism().map_or(1, std::num::NonZero::get);
+    let chunk_len = items.len().div_ceil(granule).div_ceil(threads).max(1) * granule;
+    std::thread::scope(|scope| {
+        let f = &f;
+        let handles: Vec<_> = items
+            .chunks_mut(chunk_len)
+            .enumerate()
+            .map(|(chunk, chunk_items)| scope.spawn(move || f(chunk * chunk_len, chunk_items)))
+            .collect();
+        for handle in handles {
+            handle
+                .join()
+                .unwrap_or_else(|e| std::panic::resume_unwind(e));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::par_chunks_mut;
+
+    #[test]
+    fn test_par_chunks_mut() {
+        for (len, granule) in [(0, 1), (1, 1), (97, 1), (7 * 13, 13), (5, 0)] {
+            let mut items = vec![usize::MAX; len];
+            par_chunks_mut(&mut items, granule, |start, chunk| {
+                if granule > 0 {
+                    assert_eq!(start % granule, 0);
+                }
+                for (idx, item) in (start..).zip(chunk) {
+                    *item = idx;
+                }
+            });
+            assert!(items.iter().copied().eq(0..len));
+        }
+    }
+}
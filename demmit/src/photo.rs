@@ -0,0 +1,97 @@
+use crate::sun::days_from_civil;
+use exif::{DateTime, Exif, In, Reader, Tag, Value};
+use nasadem::geo::Coord;
+use std::{fs::File, io::BufReader, path::Path};
+
+/// Returns when and where the photo at `path` was taken, as seconds
+/// since the Unix epoch and a coordinate, from its EXIF metadata.
+///
+/// The capture time is `DateTimeOriginal`, shifted to UTC by
+/// `OffsetTimeOriginal` if present and otherwise assumed to already
+/// be UTC. Returns `None` if the photo has no EXIF metadata or lacks
+/// either the capture time or a GPS position.
+pub fn photo_time_and_place(path: &Path) -> Result<Option<(f64, Coord<f64>)>, exif::Error> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let exif = match Reader::new().read_from_container(&mut reader) {
+        Ok(exif) => exif,
+        Err(exif::Error::NotFound(_)) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    Ok(capture_time(&exif).zip(gps_coord(&exif)))
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn capture_time(exif: &Exif) -> Option<f64> {
+    let mut dt = DateTime::from_ascii(ascii(exif, Tag::DateTimeOriginal)?).ok()?;
+    if let Some(offset) = ascii(exif, Tag::OffsetTimeOriginal) {
+        dt.parse_offset(offset).ok()?;
+    }
+    let days = days_from_civil(i64::from(dt.year), u32::from(dt.month), u32::from(dt.day));
+    let secs = days * 86_400
+        + i64::from(dt.hour) * 3600
+        + i64::from(dt.minute) * 60
+        + i64::from(dt.second)
+        - i64::from(dt.offset.unwrap_or(0)) * 60;
+    Some(secs as f64)
+}
+
+fn gps_coord(exif: &Exif) -> Option<Coord<f64>> {
+    let lat = dms(exif, Tag::GPSLatitude)?;
+    let lon = dms(exif, Tag::GPSLongitude)?;
+    let lat = match ascii(exif, Tag::GPSLatitudeRef)? {
+        b"S" => -lat,
+        _ => lat,
+    };
+    let lon = match ascii(exif, Tag::GPSLongitudeRef)? {
+        b"W" => -lon,
+        _ => lon,
+    };
+    Some(Coord { x: lon, y: lat })
+}
+
+/// Returns the first string of an ASCII field.
+fn ascii(exif: &Exif, tag: Tag) -> Option<&[u8]> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Ascii(strings) => strings.first().map(Vec::as_slice),
+        _ => None,
+    }
+}
+
+/// Returns a degrees, minutes, seconds field in decimal degrees.
+fn dms(exif: &Exif, tag: Tag) -> Option<f64> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Rational(dms) if dms.len() == 3 => {
+            Some(dms[0].to_f64() + dms[1].to_f64() / 60.0 + dms[2].to_f64() / 3600.0)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::photo_time_and_place;
+    use approx::assert_abs_diff_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_photo_time_and_place() {
+        let fixture: PathBuf = [
+            env!("CARGO_MANIFEST_DIR"),
+            "tests",
+            "fixtures",
+            "mt-washington-photo.jpg",
+        ]
+        .iter()
+        .collect();
+        let (time, coord) = photo_time_and_place(&fixture).unwrap().unwrap();
+        // 2023-07-15 14:30:00 -04:00
+        assert_abs_diff_eq!(time, 1_689_445_800.0);
+        assert_abs_diff_eq!(coord.y, 44.2705, epsilon = 1e-9);
+        assert_abs_diff_eq!(coord.x, -71.30325, epsilon = 1e-9);
+
+        let tmp = tempfile::tempdir().unwrap();
+        let no_exif = tmp.path().join("no-exif.jpg");
+        image::GrayImage::new(8, 8).save(&no_exif).unwrap();
+        assert!(photo_time_and_place(&no_exif).unwrap().is_none());
+    }
+}
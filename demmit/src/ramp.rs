@@ -0,0 +1,122 @@
+use image::{Rgb, RgbImage};
+use nalgebra::DMatrix;
+use nasadem::{Palette, VOID};
+use std::{io, path::Path};
+
+/// A color ramp of elevation control stops, linearly interpolated
+/// between them.
+///
+/// Elevations below the lowest stop or above the highest take that
+/// stop's color.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorRamp {
+    /// A palette whose stops are elevations in meters.
+    palette: Palette,
+}
+
+impl ColorRamp {
+    /// Reads a ramp from a CSV file of `elevation,r,g,b` stops, one
+    /// per line, in any order.
+    ///
+    /// Blank lines and lines starting with `#` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, a line is
+    /// malformed, or there are no stops.
+    pub fn from_csv<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let text = std::fs::read_to_string(&path)?;
+        let mut stops = Vec::new();
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let bad_line = || invalid(format!("line {}: expected `elevation,r,g,b`", line_no + 1));
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [elev, r, g, b] = fields[..] else {
+                return Err(bad_line());
+            };
+            let elev: f32 = elev.parse().map_err(|_| bad_line())?;
+            if !elev.is_finite() {
+                return Err(bad_line());
+            }
+            let channel = |c: &str| c.parse::<u8>().map_err(|_| bad_line());
+            stops.push((elev, [channel(r)?, channel(g)?, channel(b)?]));
+        }
+        if stops.is_empty() {
+            return Err(invalid(format!(
+                "{} has no color stops",
+                path.as_ref().display()
+            )));
+        }
+        Ok(Self {
+            palette: Palette::new(stops),
+        })
+    }
+
+    /// Returns the color of `elev` meters.
+    pub fn color(&self, elev: f32) -> Rgb<u8> {
+        self.palette.color(elev)
+    }
+
+    /// Returns an RGB image coloring each cell of `elevations`.
+    ///
+    /// Voids are painted black, as by
+    /// [`hypsometric_tint`](crate::hypsometric_tint).
+    #[allow(clippy::cast_possible_truncation, clippy::float_cmp)]
+    pub fn apply(&self, elevations: &DMatrix<f32>) -> RgbImage {
+        let void = f32::from(VOID);
+        let (rows, cols) = elevations.shape();
+        RgbImage::from_fn(cols as u32, rows as u32, |x, y| {
+            match elevations[(y as usize, x as usize)] {
+                elev if elev == void => self.palette.void_color(),
+                elev => self.color(elev),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ColorRamp;
+    use image::Rgb;
+    use nalgebra::DMatrix;
+    use nasadem::VOID;
+
+    #[test]
+    fn test_from_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ramp.csv");
+        std::fs::write(
+            &path,
+            "# elevation,r,g,b\n1000, 255, 255, 255\n\n0,0,100,0\n",
+        )
+        .unwrap();
+        let ramp = ColorRamp::from_csv(&path).unwrap();
+        assert_eq!(ramp.color(-50.0), Rgb([0, 100, 0]));
+        assert_eq!(ramp.color(0.0), Rgb([0, 100, 0]));
+        assert_eq!(ramp.color(500.0), Rgb([128, 178, 128]));
+        assert_eq!(ramp.color(1000.0), Rgb([255, 255, 255]));
+        assert_eq!(ramp.color(9000.0), Rgb([255, 255, 255]));
+
+        let mut elevations = DMatrix::from_element(1, 2, 1000.0);
+        elevations[(0, 1)] = f32::from(VOID);
+        let img = ramp.apply(&elevations);
+        assert_eq!(img.get_pixel(0, 0), &Rgb([255, 255, 255]));
+        assert_eq!(img.get_pixel(1, 0), &Rgb([0, 0, 0]));
+
+        for bad in [
+            "",
+            "# only a comment\n",
+            "0,1,2\n",
+            "0,1,2,300\n",
+            "x,1,2,3\n",
+        ] {
+            std::fs::write(&path, bad).unwrap();
+            let err = ColorRamp::from_csv(&path).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData, "{bad:?}");
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use image::{Rgb, RgbImage};
+use nalgebra::DMatrix;
+use nasadem::{Palette, VOID};
+
+/// Returns an RGB image coloring each cell of `elevations` by its
+/// height within the matrix's elevation range, with the default
+/// hypsometric [`Palette`].
+///
+/// Voids are excluded from the range and painted black.
+#[allow(clippy::cast_possible_truncation, clippy::float_cmp)]
+pub fn hypsometric_tint(elevations: &DMatrix<f32>) -> RgbImage {
+    let palette = Palette::default();
+    let void = f32::from(VOID);
+    let (min, max) = elevations
+        .iter()
+        .filter(|&&elev| elev != void)
+        .fold((f32::MAX, f32::MIN), |(min, max), &elev| {
+            (min.min(elev), max.max(elev))
+        });
+    let range = (max - min).max(f32::EPSILON);
+    let (rows, cols) = elevations.shape();
+    RgbImage::from_fn(cols as u32, rows as u32, |x, y| {
+        let elev = elevations[(y as usize, x as usize)];
+        if elev == void {
+            return palette.void_color();
+        }
+        palette.color(((elev - min) / range).clamp(0.0, 1.0))
+    })
+}
+
+/// Returns `tint` multiply-blended with the hillshade `shaded`, as
+/// produced by [`shade`](crate::shade) with a sun `sun_elev_rad`
+/// above the horizon.
+///
+/// The hillshade is normalized by the reflectance of flat ground, so
+/// flat and sun-facing cells keep their tint while cells turned away
+/// from the sun darken toward black.
+///
+/// # Panics
+///
+/// Panics if `tint` and `shaded` differ in shape.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn multiply_blend(tint: &RgbImage, shaded: &DMatrix<f32>, sun_elev_rad: f32) -> RgbImage {
+    let (rows, cols) = shaded.shape();
+    assert_eq!(tint.dimensions(), (cols as u32, rows as u32));
+    let flat = sun_elev_rad.sin().max(f32::EPSILON);
+    RgbImage::from_fn(cols as u32, rows as u32, |x, y| {
+        let intensity = (shaded[(y as usize, x as usize)] / flat).clamp(0.0, 1.0);
+        let Rgb(color) = *tint.get_pixel(x, y);
+        Rgb(color.map(|c| (f32::from(c) * intensity).round() as u8))
+    })
+}
+
+/// Returns `color` shaded by the hillshade `shade`, as produced by
+/// [`shade`](crate::shade), for a classic shaded relief map.
+///
+/// Each cell's color is multiplied by its reflectance, mixed with
+/// white by `opacity`: at `1.0` the full hillshade darkens the color,
+/// at `0.0` the color is unchanged, and values between soften the
+/// shadows. Unlike [`multiply_blend`], the reflectance isn't
+/// normalized, so flat ground darkens too. `opacity` is clamped to
+/// `[0, 1]`.
+///
+/// # Panics
+///
+/// Panics if `color` and `shade` differ in shape.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn blend_relief(color: &RgbImage, shade: &DMatrix<f32>, opacity: f32) -> RgbImage {
+    let (rows, cols) = shade.shape();
+    assert_eq!(color.dimensions(), (cols as u32, rows as u32));
+    let opacity = opacity.clamp(0.0, 1.0);
+    RgbImage::from_fn(cols as u32, rows as u32, |x, y| {
+        let reflectance = shade[(y as usize, x as usize)].clamp(0.0, 1.0);
+        let intensity = 1.0 - opacity * (1.0 - reflectance);
+        let Rgb(color) = *color.get_pixel(x, y);
+        Rgb(color.map(|c| (f32::from(c) * intensity).round() as u8))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blend_relief, hypsometric_tint, multiply_blend};
+    use crate::shade;
+    use image::{Rgb, RgbImage};
+    use nalgebra::DMatrix;
+    use nasadem::VOID;
+
+    #[test]
+    fn test_hypsometric_tint() {
+        let ramp = DMatrix::from_fn(1, 3, |_, x| [0.0, 500.0, f32::from(VOID)][x]);
+        let tint = hypsometric_tint(&ramp);
+        assert_eq!(tint.get_pixel(0, 0), &Rgb([0x4a, 0x7c, 0x3e]));
+        assert_eq!(tint.get_pixel(1, 0), &Rgb([0xff, 0xff, 0xff]));
+        assert_eq!(tint.get_pixel(2, 0), &Rgb([0, 0, 0]));
+    }
+
+    #[test]
+    fn test_multiply_blend() {
+        // A plateau dropping off steeply to the east, lit from the
+        // west.
+        #[allow(clippy::cast_precision_loss)]
+        let data = DMatrix::from_fn(8, 16, |_, x| {
+            if x < 8 {
+                1000.0
+            } else {
+                1000.0 - 50.0 * (x - 7) as f32
+            }
+        });
+        let sun_elev_rad = 30_f32.to_radians();
+        let shaded = shade(sun_elev_rad, 270_f32.to_radians(), (30.0, 30.0), 1.0, &data);
+        let tint = hypsometric_tint(&data);
+        let blended = multiply_blend(&tint, &shaded, sun_elev_rad);
+        // Flat plateau keeps its color.
+        assert_eq!(blended.get_pixel(2, 4), tint.get_pixel(2, 4));
+        assert_ne!(tint.get_pixel(2, 4), &Rgb([0, 0, 0]));
+        // Shadowed east face is black.
+        assert_eq!(blended.get_pixel(12, 4), &Rgb([0, 0, 0]));
+        assert_ne!(tint.get_pixel(12, 4), &Rgb([0, 0, 0]));
+    }
+
+    #[test]
+    fn test_blend_relief() {
+        let color = RgbImage::from_pixel(3, 1, Rgb([200, 100, 50]));
+        let shaded = DMatrix::from_row_slice(1, 3, &[1.0, 0.5, 0.0]);
+        let full = blend_relief(&color, &shaded, 1.0);
+        assert_eq!(full.get_pixel(0, 0), &Rgb([200, 100, 50]));
+        assert_eq!(full.get_pixel(1, 0), &Rgb([100, 50, 25]));
+        assert_eq!(full.get_pixel(2, 0), &Rgb([0, 0, 0]));
+        // Half opacity halves the darkening.
+        let half = blend_relief(&color, &shaded, 0.5);
+        assert_eq!(half.get_pixel(1, 0), &Rgb([150, 75, 38]));
+        assert_eq!(half.get_pixel(2, 0), &Rgb([100, 50, 25]));
+        assert_eq!(blend_relief(&color, &shaded, 0.0), color);
+        assert_eq!(blend_relief(&color, &shaded, 7.0), full);
+    }
+}
@@ -0,0 +1,517 @@
+use crate::par::par_chunks_mut;
+use image::{ImageBuffer, Luma};
+use nalgebra::{DMatrix, Scalar};
+use nasadem::{Elev, Tile, VOID};
+use num_traits::AsPrimitive;
+
+/// Meters per degree of latitude on a sphere of the mean earth
+/// radius.
+const METERS_PER_DEG: f64 = 6_371_008.8 * std::f64::consts::PI / 180.0;
+
+/// Returns `tile`'s samples as a `(rows, cols)` matrix.
+///
+/// Matrix element `(0, 0)` is the NW corner of the tile. Void
+/// samples keep the raw [`VOID`] value, which the tinting and ocean
+/// masking routines recognize.
+pub fn tile_to_matrix<T>(tile: &Tile) -> DMatrix<T>
+where
+    T: From<Elev> + Scalar + Copy,
+{
+    tile.to_matrix_with_void(T::from(VOID))
+}
+
+/// Returns the `(east-west, north-south)` spacing, in meters, of
+/// samples `resolution` arcseconds apart at latitude `lat_deg`, for
+/// passing to [`shade`] and friends.
+///
+/// For a whole tile, pass the latitude of its center row, as
+/// [`Tile::ground_resolution_m`] does.
+#[allow(clippy::cast_possible_truncation)]
+pub fn cell_size_m(resolution: u8, lat_deg: f64) -> (f32, f32) {
+    let dy_m = f64::from(resolution) * METERS_PER_DEG / 3600.0;
+    ((dy_m * lat_deg.to_radians().cos()) as f32, dy_m as f32)
+}
+
+/// Returns a hillshade of `data`, whose samples are `cell_m`
+/// `(east-west, north-south)` meters apart.
+///
+/// Each output cell is the reflectance, in `[0, 1]`, of a lambertian
+/// surface lit by a sun at `sun_elev_rad` above the horizon and
+/// `sun_azimuth_rad` clockwise from north. The surface's gradient is
+/// found by Horn's method, weighting the cell's eight neighbors, over
+/// true ground distances, so tiles of different resolutions shade
+/// alike.
+///
+/// Elevations are multiplied by `z_factor`, a unitless vertical
+/// exaggeration, before the gradient is found; `1.0` shades the
+/// terrain as is, and larger values bring out subtle relief.
+///
+/// [`VOID`] neighbors are replaced by the cell being shaded, so
+/// voids don't cast artifacts onto the terrain around them, and void
+/// cells are shaded as flat ground.
+pub fn shade(
+    sun_elev_rad: f32,
+    sun_azimuth_rad: f32,
+    cell_m: (f32, f32),
+    z_factor: f32,
+    data: &DMatrix<f32>,
+) -> DMatrix<f32> {
+    let (rows, cols) = data.shape();
+    let sun = sun_vector(sun_elev_rad, sun_azimuth_rad);
+    DMatrix::from_fn(rows, cols, |y, x| {
+        reflectance(sun, cell_m, z_factor, data, x, y)
+    })
+}
+
+/// Azimuths, in degrees clockwise from north, of the lights GDAL's
+/// multidirectional hillshade combines: west through north.
+pub const MULTIDIRECTIONAL_AZIMUTHS_DEG: [f32; 4] = [225.0, 270.0, 315.0, 360.0];
+
+/// Returns a soft hillshade of `data`: the mean of its [`shade`]s
+/// under a sun at each of `azimuths_rad`, all at `sun_elev_rad`.
+///
+/// A single light leaves slopes facing away from it in deep shadow
+/// and flattens ridges running toward it; averaging lights from
+/// several directions, such as [`MULTIDIRECTIONAL_AZIMUTHS_DEG`],
+/// softens both. The result stays in `[0, 1]`. With no azimuths,
+/// every cell is `0`.
+pub fn shade_multidirectional(
+    sun_elev_rad: f32,
+    azimuths_rad: &[f32],
+    cell_m: (f32, f32),
+    z_factor: f32,
+    data: &DMatrix<f32>,
+) -> DMatrix<f32> {
+    let (rows, cols) = data.shape();
+    let suns = sun_vectors(sun_elev_rad, azimuths_rad);
+    DMatrix::from_fn(rows, cols, |y, x| {
+        mean_lit(&suns, gradient(cell_m, z_factor, data, x, y))
+    })
+}
+
+/// Returns a hillshade of `data`, matching [`shade`] to within
+/// floating-point tolerance, computed eight cells at a time with
+/// SIMD.
+///
+/// Vectors run north-south down each column, which is contiguous in
+/// nalgebra's column-major storage; the edge rows, any remainder too
+/// short for a vector, and columns next to a [`VOID`] are shaded one
+/// cell at a time.
+#[cfg(feature = "simd")]
+pub fn shade_simd(
+    sun_elev_rad: f32,
+    sun_azimuth_rad: f32,
+    (dx_m, dy_m): (f32, f32),
+    z_factor: f32,
+    data: &DMatrix<f32>,
+) -> DMatrix<f32> {
+    use wide::f32x8;
+    const LANES: usize = 8;
+    let (rows, cols) = data.shape();
+    let sun = sun_vector(sun_elev_rad, sun_azimuth_rad);
+    let (sun_x, sun_y, sun_z) = (
+        f32x8::splat(sun.0),
+        f32x8::splat(sun.1),
+        f32x8::splat(sun.2),
+    );
+    let (zero, two, one) = (f32x8::splat(0.0), f32x8::splat(2.0), f32x8::splat(1.0));
+    let (x_scale, y_scale) = (f32x8::splat(8.0 * dx_m), f32x8::splat(8.0 * dy_m));
+    let z_factor_x8 = f32x8::splat(z_factor);
+    let column = |x: usize| &data.as_slice()[x * rows..(x + 1) * rows];
+    let load = |col: &[f32], y: usize| {
+        f32x8::from(<[f32; LANES]>::try_from(&col[y..y + LANES]).expect("slice is LANES long"))
+    };
+    let has_void = |col: &[f32]| col.contains(&f32::from(VOID));
+    let mut shaded = DMatrix::zeros(rows, cols);
+    for x in 0..cols {
+        // Edge columns are clamped to themselves, as in `reflectance`.
+        let (west, here, east) = (
+            column(x.saturating_sub(1)),
+            column(x),
+            column((x + 1).min(cols - 1)),
+        );
+        let out = &mut shaded.as_mut_slice()[x * rows..(x + 1) * rows];
+        // Vectors start at row 1 and stop a row short of the south
+        // edge, so every north and south neighbor is in bounds. Voids
+        // are left to `reflectance`.
+        let mut y = 1;
+        let vectorize = !(has_void(west) || has_void(here) || has_void(east));
+        while vectorize && y + LANES < rows {
+            // Row 0 is north, so north is toward decreasing `y`.
+            let weigh = |col: &[f32]| load(col, y - 1) + two * load(col, y) + load(col, y + 1);
+            let dzdx = z_factor_x8 * (weigh(east) - weigh(west)) / x_scale;
+            let north = load(west, y - 1) + two * load(here, y - 1) + load(east, y - 1);
+            let south = load(west, y + 1) + two * load(here, y + 1) + load(east, y + 1);
+            let dzdy = z_factor_x8 * (north - south) / y_scale;
+            let norm = (dzdx * dzdx + dzdy * dzdy + one).sqrt();
+            let reflection = (-dzdx * sun_x - dzdy * sun_y + sun_z) / norm;
+            out[y..y + LANES].copy_from_slice(&reflection.max(zero).min(one).to_array());
+            y += LANES;
+        }
+        for y in (0..rows.min(1)).chain(y..rows) {
+            out[y] = reflectance(sun, (dx_m, dy_m), z_factor, data, x, y);
+        }
+    }
+    shaded
+}
+
+/// Returns a hillshade of `data` scaled to the full range of `Pix`.
+///
+/// Equivalent to `matrix_to_image(&shade(..))`, but shades straight
+/// into the image buffer, so no intermediate full-size matrix of
+/// shade values is allocated. Bands of rows are shaded in parallel.
+pub fn shade_to_image<Pix>(
+    sun_elev_rad: f32,
+    sun_azimuth_rad: f32,
+    cell_m: (f32, f32),
+    z_factor: f32,
+    data: &DMatrix<f32>,
+) -> ImageBuffer<Luma<Pix>, Vec<Pix>>
+where
+    Pix: image::Primitive + Send + 'static,
+    f32: AsPrimitive<Pix> + From<Pix>,
+{
+    let sun = sun_vector(sun_elev_rad, sun_azimuth_rad);
+    shade_into_image(data.shape(), |x, y| {
+        reflectance(sun, cell_m, z_factor, data, x, y)
+    })
+}
+
+/// Returns a soft hillshade of `data` scaled to the full range of
+/// `Pix`.
+///
+/// Equivalent to `matrix_to_image(&shade_multidirectional(..))`, but
+/// shades straight into the image buffer as [`shade_to_image`] does.
+pub fn shade_multidirectional_to_image<Pix>(
+    sun_elev_rad: f32,
+    azimuths_rad: &[f32],
+    cell_m: (f32, f32),
+    z_factor: f32,
+    data: &DMatrix<f32>,
+) -> ImageBuffer<Luma<Pix>, Vec<Pix>>
+where
+    Pix: image::Primitive + Send + 'static,
+    f32: AsPrimitive<Pix> + From<Pix>,
+{
+    let suns = sun_vectors(sun_elev_rad, azimuths_rad);
+    shade_into_image(data.shape(), |x, y| {
+        mean_lit(&suns, gradient(cell_m, z_factor, data, x, y))
+    })
+}
+
+/// Returns a `(rows, cols)` image of `reflectance(x, y)`, in `[0,
+/// 1]`, scaled to the full range of `Pix`, computed in parallel
+/// bands of rows.
+#[allow(clippy::cast_possible_truncation)]
+fn shade_into_image<Pix, F>(
+    (rows, cols): (usize, usize),
+    reflectance: F,
+) -> ImageBuffer<Luma<Pix>, Vec<Pix>>
+where
+    Pix: image::Primitive + Send + 'static,
+    f32: AsPrimitive<Pix> + From<Pix>,
+    F: Fn(usize, usize) -> f32 + Sync,
+{
+    let pix_max = f32::from(Pix::max_value());
+    let mut pixels = vec![Pix::zero(); rows * cols];
+    par_chunks_mut(&mut pixels, cols, |start, band| {
+        for (idx, pixel) in (start..).zip(band.iter_mut()) {
+            let (y, x) = (idx / cols, idx % cols);
+            *pixel = (reflectance(x, y) * pix_max).round().as_();
+        }
+    });
+    ImageBuffer::from_raw(cols as u32, rows as u32, pixels).expect("buffer is sized for the image")
+}
+
+/// Scales `data`, which is expected to be in `[0, 1]`, to the full
+/// range of `Pix`.
+#[allow(clippy::cast_possible_truncation)]
+pub fn matrix_to_image<Pix>(data: &DMatrix<f32>) -> ImageBuffer<Luma<Pix>, Vec<Pix>>
+where
+    Pix: image::Primitive + 'static,
+    f32: AsPrimitive<Pix> + From<Pix>,
+{
+    let (rows, cols) = data.shape();
+    let pix_max = f32::from(Pix::max_value());
+    ImageBuffer::from_fn(cols as u32, rows as u32, |x, y| {
+        let val = data[(y as usize, x as usize)].clamp(0.0, 1.0);
+        Luma([(val * pix_max).round().as_()])
+    })
+}
+
+/// Returns the unit vector pointing at the sun, where x is east, y
+/// is north, and z is up.
+fn sun_vector(sun_elev_rad: f32, sun_azimuth_rad: f32) -> (f32, f32, f32) {
+    let (az_sin, az_cos) = sun_azimuth_rad.sin_cos();
+    let (el_sin, el_cos) = sun_elev_rad.sin_cos();
+    (az_sin * el_cos, az_cos * el_cos, el_sin)
+}
+
+/// Returns the unit vectors pointing at suns at `sun_elev_rad` and
+/// each of `azimuths_rad`.
+fn sun_vectors(sun_elev_rad: f32, azimuths_rad: &[f32]) -> Vec<(f32, f32, f32)> {
+    azimuths_rad
+        .iter()
+        .map(|&azimuth_rad| sun_vector(sun_elev_rad, azimuth_rad))
+        .collect()
+}
+
+/// Returns the mean reflectance, in `[0, 1]`, of a surface with
+/// `gradient` lit by each of `suns`, or `0` if there are none.
+#[allow(clippy::cast_precision_loss)]
+fn mean_lit(suns: &[(f32, f32, f32)], gradient: (f32, f32)) -> f32 {
+    suns.iter().map(|&sun| lit(sun, gradient)).sum::<f32>() / suns.len().max(1) as f32
+}
+
+/// Returns the reflectance, in `[0, 1]`, of `data` at `(x, y)`.
+fn reflectance(
+    sun: (f32, f32, f32),
+    cell_m: (f32, f32),
+    z_factor: f32,
+    data: &DMatrix<f32>,
+    x: usize,
+    y: usize,
+) -> f32 {
+    lit(sun, gradient(cell_m, z_factor, data, x, y))
+}
+
+/// Returns the reflectance, in `[0, 1]`, of a surface with gradient
+/// `(dzdx, dzdy)` lit by `sun`.
+fn lit((sun_x, sun_y, sun_z): (f32, f32, f32), (dzdx, dzdy): (f32, f32)) -> f32 {
+    let norm = (dzdx * dzdx + dzdy * dzdy + 1.0).sqrt();
+    let reflection = (-dzdx * sun_x - dzdy * sun_y + sun_z) / norm;
+    reflection.clamp(0.0, 1.0)
+}
+
+/// Returns the `(east, north)` gradient of `data` at `(x, y)`.
+///
+/// The gradient is Horn's: each axis differences the weighted sums
+/// of the neighbors on either side, the nearer neighbor counting
+/// twice, over eight cell spacings, and is scaled by `z_factor`.
+/// A [`VOID`] cell is flat, and void neighbors are replaced by the
+/// center cell, as in [`Tile::hillshade`].
+#[allow(clippy::float_cmp)]
+fn gradient(
+    (dx_m, dy_m): (f32, f32),
+    z_factor: f32,
+    data: &DMatrix<f32>,
+    x: usize,
+    y: usize,
+) -> (f32, f32) {
+    let (rows, cols) = data.shape();
+    let void = f32::from(VOID);
+    let center = data[(y, x)];
+    if center == void {
+        return (0.0, 0.0);
+    }
+    // Edge samples are clamped to the nearest in-bounds sample.
+    let get = |x: usize, y: usize| match data[(y.min(rows - 1), x.min(cols - 1))] {
+        elev if elev == void => center,
+        elev => elev,
+    };
+    let (west, east) = (x.saturating_sub(1), x + 1);
+    // Row 0 is north, so north is toward decreasing `y`.
+    let (north, south) = (y.saturating_sub(1), y + 1);
+    let column = |x: usize| get(x, north) + 2.0 * get(x, y) + get(x, south);
+    let row = |y: usize| get(west, y) + 2.0 * get(x, y) + get(east, y);
+    let dzdx = z_factor * (column(east) - column(west)) / (8.0 * dx_m);
+    let dzdy = z_factor * (row(north) - row(south)) / (8.0 * dy_m);
+    (dzdx, dzdy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cell_size_m, matrix_to_image, shade, shade_multidirectional,
+        shade_multidirectional_to_image, shade_to_image, tile_to_matrix,
+        MULTIDIRECTIONAL_AZIMUTHS_DEG,
+    };
+    use approx::assert_relative_eq;
+    use nalgebra::DMatrix;
+    use nasadem::{Tile, VOID};
+
+    #[test]
+    fn test_tile_to_matrix() {
+        let mut path = crate::three_arcsecond_dir();
+        path.push("N44W072.hgt");
+        let tile = Tile::load(path).unwrap();
+        let matrix = tile_to_matrix::<i16>(&tile);
+        assert_eq!(matrix.shape(), (1201, 1201));
+        assert_eq!(matrix[(0, 0)], tile.get_unchecked((0, 0)));
+        assert_eq!(matrix[(1, 2)], tile.get_unchecked((2, 1)));
+
+        let mut tile = tile;
+        tile.set((2, 1), VOID).unwrap();
+        assert_eq!(tile_to_matrix::<i32>(&tile)[(1, 2)], i32::from(VOID));
+    }
+
+    #[test]
+    fn test_shade_flat() {
+        let flat = DMatrix::from_element(4, 4, 100.0);
+        let shaded = shade(30_f32.to_radians(), 0.0, (30.0, 30.0), 1.0, &flat);
+        for &val in &shaded {
+            assert_relative_eq!(val, 0.5, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn test_shade_metric_spacing() {
+        // A plane rising one meter for every three east and two north,
+        // lit from the east, shades alike at either resolution.
+        let sun_elev_rad = 45_f32.to_radians();
+        let expected = {
+            let (dzdx, dzdy) = (1.0_f32 / 3.0, 0.5_f32);
+            let (el_sin, el_cos) = sun_elev_rad.sin_cos();
+            (-dzdx * el_cos + el_sin) / (dzdx * dzdx + dzdy * dzdy + 1.0).sqrt()
+        };
+        for resolution in [1, 3] {
+            let (dx_m, dy_m) = cell_size_m(resolution, 44.5);
+            assert_relative_eq!(dx_m, dy_m * 44.5_f32.to_radians().cos());
+            let plane = DMatrix::from_fn(5, 5, |y, x| {
+                x as f32 * dx_m / 3.0 + (4 - y) as f32 * dy_m / 2.0
+            });
+            let shaded = shade(sun_elev_rad, 90_f32.to_radians(), (dx_m, dy_m), 1.0, &plane);
+            for y in 1..4 {
+                for x in 1..4 {
+                    assert_relative_eq!(shaded[(y, x)], expected, epsilon = 1e-5);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn test_shade_z_factor() {
+        let (elev, azimuth) = (45_f32.to_radians(), 315_f32.to_radians());
+        let hills = DMatrix::from_fn(6, 7, |y, x| ((x * 7 + y * 3) % 11) as f32 * 4.0);
+        // Exaggerating is scaling the elevations.
+        let doubled = hills.map(|z| z * 2.0);
+        for (exaggerated, scaled) in shade(elev, azimuth, (30.0, 30.0), 2.0, &hills)
+            .iter()
+            .zip(&shade(elev, azimuth, (30.0, 30.0), 1.0, &doubled))
+        {
+            assert_relative_eq!(exaggerated, scaled, epsilon = 1e-6);
+        }
+        // Flat ground has no relief to exaggerate.
+        let flat = DMatrix::from_element(4, 4, 100.0);
+        for &val in &shade(elev, 0.0, (30.0, 30.0), 5.0, &flat) {
+            assert_relative_eq!(val, 0.5_f32.sqrt(), epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_shade_multidirectional() {
+        // A steep ridge running east-west.
+        #[allow(clippy::cast_precision_loss)]
+        let ridge = DMatrix::from_fn(9, 5, |y, _| 400.0 - 50.0 * (y as f32 - 4.0).abs());
+        let (elev, cell_m) = (45_f32.to_radians(), (30.0, 30.0));
+        let azimuths = MULTIDIRECTIONAL_AZIMUTHS_DEG.map(f32::to_radians);
+        let soft = shade_multidirectional(elev, &azimuths, cell_m, 1.0, &ridge);
+        let mean = azimuths
+            .iter()
+            .map(|&azimuth| shade(elev, azimuth, cell_m, 1.0, &ridge))
+            .reduce(|sum, shaded| sum + shaded)
+            .unwrap()
+            / 4.0;
+        for (soft, mean) in soft.iter().zip(&mean) {
+            assert_relative_eq!(soft, mean, epsilon = 1e-6);
+            assert!((0.0..=1.0).contains(soft));
+        }
+        // Compared to the northwest sun alone, the south face comes
+        // out of shadow and the north face is toned down.
+        let northwest = shade(elev, 315_f32.to_radians(), cell_m, 1.0, &ridge);
+        assert_relative_eq!(northwest[(6, 2)], 0.0);
+        assert!(soft[(6, 2)] > 0.0);
+        assert!(soft[(2, 2)] < northwest[(2, 2)]);
+
+        assert_eq!(
+            shade_multidirectional(elev, &[], cell_m, 1.0, &ridge),
+            DMatrix::zeros(9, 5)
+        );
+
+        assert_eq!(
+            shade_multidirectional_to_image::<u8>(elev, &azimuths, cell_m, 1.0, &ridge),
+            matrix_to_image::<u8>(&soft)
+        );
+        assert_eq!(
+            shade_multidirectional_to_image::<u16>(elev, &[], cell_m, 1.0, &ridge),
+            matrix_to_image::<u16>(&DMatrix::zeros(9, 5))
+        );
+    }
+
+    #[test]
+    fn test_shade_to_image_matches_shade() {
+        let mut path = crate::three_arcsecond_dir();
+        path.push("N44W072.hgt");
+        let data = tile_to_matrix(&Tile::load(path).unwrap());
+        let cell_m = cell_size_m(3, 44.5);
+        let (elev, azimuth) = (45_f32.to_radians(), 315_f32.to_radians());
+        let shaded = shade(elev, azimuth, cell_m, 1.0, &data);
+        assert_eq!(
+            shade_to_image::<u16>(elev, azimuth, cell_m, 1.0, &data),
+            matrix_to_image::<u16>(&shaded)
+        );
+        assert_eq!(
+            shade_to_image::<u8>(elev, azimuth, cell_m, 1.0, &data),
+            matrix_to_image::<u8>(&shaded)
+        );
+        // Fewer rows than threads.
+        let ridge = DMatrix::from_fn(1, 5, |_, x| if x == 2 { 1.0 } else { 0.0 });
+        assert_eq!(
+            shade_to_image::<u8>(elev, azimuth, cell_m, 1.0, &ridge),
+            matrix_to_image::<u8>(&shade(elev, azimuth, cell_m, 1.0, &ridge))
+        );
+    }
+
+    #[test]
+    fn test_shade_void() {
+        // Flat ground with a void, which must not shade as a pit.
+        let mut flat = DMatrix::from_element(12, 5, 100.0);
+        flat[(6, 2)] = f32::from(VOID);
+        let (elev, cell_m) = (30_f32.to_radians(), (30.0, 30.0));
+        let azimuths = MULTIDIRECTIONAL_AZIMUTHS_DEG.map(f32::to_radians);
+        for shaded in [
+            shade(elev, 1.0, cell_m, 1.0, &flat),
+            shade_multidirectional(elev, &azimuths, cell_m, 1.0, &flat),
+        ] {
+            for &val in &shaded {
+                assert_relative_eq!(val, 0.5, epsilon = 1e-6);
+            }
+        }
+        assert_eq!(
+            shade_to_image::<u8>(elev, 1.0, cell_m, 1.0, &flat),
+            matrix_to_image::<u8>(&DMatrix::from_element(12, 5, 0.5))
+        );
+        #[cfg(feature = "simd")]
+        for &val in &super::shade_simd(elev, 1.0, cell_m, 1.0, &flat) {
+            assert_relative_eq!(val, 0.5, epsilon = 1e-6);
+        }
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn test_shade_simd_matches_shade() {
+        use super::shade_simd;
+        let mut path = crate::three_arcsecond_dir();
+        path.push("N44W072.hgt");
+        let data = tile_to_matrix(&Tile::load(path).unwrap());
+        let cell_m = cell_size_m(3, 44.5);
+        let (elev, azimuth) = (45_f32.to_radians(), 315_f32.to_radians());
+        // The fixture, plus shapes too short for a vector, exactly one
+        // vector, and one vector and a remainder per column.
+        for data in [
+            data,
+            DMatrix::from_fn(1, 5, |_, x| if x == 2 { 1.0 } else { 0.0 }),
+            DMatrix::from_fn(10, 3, |y, x| (x * y) as f32),
+            DMatrix::from_fn(13, 4, |y, x| (x * x + 3 * y) as f32),
+        ] {
+            let simd = shade_simd(elev, azimuth, cell_m, 2.5, &data);
+            assert_eq!(simd.shape(), data.shape());
+            for (simd, scalar) in simd.iter().zip(&shade(elev, azimuth, cell_m, 2.5, &data)) {
+                assert_relative_eq!(simd, scalar, epsilon = 1e-6);
+            }
+        }
+    }
+}
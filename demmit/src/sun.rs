@@ -0,0 +1,80 @@
+use nasadem::geo::Coord;
+use std::f64::consts::TAU;
+
+/// Returns the sun's `(azimuth, elevation)` in radians, clockwise
+/// from north and above the horizon, as seen from `coord` at
+/// `unix_time_s` seconds since the Unix epoch (UTC).
+///
+/// Uses the low-precision solar coordinates from the Astronomical
+/// Almanac, which are good to about 0.01° between 1950 and 2050;
+/// atmospheric refraction is ignored.
+pub fn sun_position(unix_time_s: f64, coord: Coord<f64>) -> (f64, f64) {
+    // Days since J2000.0.
+    let n = unix_time_s / 86_400.0 - 10_957.5;
+    let mean_lon = (280.460 + 0.985_647_4 * n).to_radians();
+    let mean_anomaly = (357.528 + 0.985_600_3 * n).to_radians();
+    let ecliptic_lon = mean_lon
+        + 1.915_f64.to_radians() * mean_anomaly.sin()
+        + 0.020_f64.to_radians() * (2.0 * mean_anomaly).sin();
+    let obliquity = (23.439 - 0.000_000_4 * n).to_radians();
+    let right_ascension = (obliquity.cos() * ecliptic_lon.sin()).atan2(ecliptic_lon.cos());
+    let declination = (obliquity.sin() * ecliptic_lon.sin()).asin();
+
+    let sidereal = (280.460_618_37 + 360.985_647_366_29 * n).to_radians() + coord.x.to_radians();
+    let hour_angle = sidereal - right_ascension;
+    let lat = coord.y.to_radians();
+    let elevation =
+        (lat.sin() * declination.sin() + lat.cos() * declination.cos() * hour_angle.cos()).asin();
+    let azimuth = (-hour_angle.sin())
+        .atan2(declination.tan() * lat.cos() - lat.sin() * hour_angle.cos())
+        .rem_euclid(TAU);
+    (azimuth, elevation)
+}
+
+/// Returns the number of days from 1970-01-01 to the given date in
+/// the proleptic Gregorian calendar.
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    // https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (i64::from(month) + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{days_from_civil, sun_position};
+    use approx::assert_abs_diff_eq;
+    use nasadem::geo::Coord;
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 1, 1), 10_957);
+        assert_eq!(days_from_civil(2024, 3, 1), 19_783);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn test_sun_position() {
+        // 2024-03-20, the March equinox, near solar noon at
+        // Greenwich: the sun is due south, 90° - latitude up.
+        let noon = (days_from_civil(2024, 3, 20) * 86_400 + 12 * 3600 + 7 * 60) as f64;
+        let (az, el) = sun_position(noon, Coord { x: 0.0, y: 44.0 });
+        assert_abs_diff_eq!(az.to_degrees(), 180.0, epsilon = 1.0);
+        assert_abs_diff_eq!(el.to_degrees(), 46.0, epsilon = 0.5);
+
+        // Seven hours later it has set in the west.
+        let (az, el) = sun_position(noon + 7.0 * 3600.0, Coord { x: 0.0, y: 44.0 });
+        assert!(
+            (270.0..300.0).contains(&az.to_degrees()),
+            "{}",
+            az.to_degrees()
+        );
+        assert!(el < 0.0);
+    }
+}
@@ -0,0 +1,524 @@
+//! End-to-end tests of the `demmit` binary.
+
+use std::{
+    path::PathBuf,
+    process::{Command, Output},
+};
+
+fn three_arcsecond_dir() -> PathBuf {
+    [
+        env!("CARGO_MANIFEST_DIR"),
+        "..",
+        "data",
+        "nasadem",
+        "3arcsecond",
+    ]
+    .iter()
+    .collect()
+}
+
+fn demmit(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_demmit"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_render_by_coord() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("mt-washington.png");
+    let tile_dir = three_arcsecond_dir();
+    let output = demmit(&[
+        "render",
+        "--coord",
+        "44.2705,-71.30325",
+        "--dir",
+        tile_dir.to_str().unwrap(),
+        out.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    let img = image::open(&out).unwrap();
+    assert_eq!((img.width(), img.height()), (1201, 1201));
+}
+
+#[test]
+fn test_render_by_coord_missing_tile() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("ocean.png");
+    let tile_dir = three_arcsecond_dir();
+    let output = demmit(&[
+        "render",
+        "--coord",
+        "-0.5,-0.5",
+        "--dir",
+        tile_dir.to_str().unwrap(),
+        out.to_str().unwrap(),
+    ]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("S01W001.hgt"), "{stderr}");
+    assert!(!out.exists());
+}
+
+#[test]
+fn test_render_bbox_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    let bbox_file = tmp.path().join("presidentials.geojson");
+    std::fs::write(
+        &bbox_file,
+        r#"{"type": "Feature", "properties": {}, "geometry": {"type": "Polygon",
+            "coordinates": [[[-71.4, 44.2], [-71.2, 44.2], [-71.2, 44.35], [-71.4, 44.35], [-71.4, 44.2]]]}}"#,
+    )
+    .unwrap();
+    let tile_dir = three_arcsecond_dir();
+    let output = demmit(&[
+        "render",
+        "--bbox-file",
+        bbox_file.to_str().unwrap(),
+        "--dir",
+        tile_dir.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    // Written next to the GeoJSON, covering 0.2° by 0.15° at 1200
+    // samples per degree.
+    let img = image::open(tmp.path().join("presidentials.png"))
+        .unwrap()
+        .into_luma16();
+    assert_eq!((img.width(), img.height()), (241, 181));
+    // Mt Washington, near 44.2705,-71.30325, is the highest point in
+    // the box and so takes the brightest pixel.
+    let (x, y, _) = img.enumerate_pixels().max_by_key(|(_, _, p)| p[0]).unwrap();
+    assert!(x.abs_diff(116) <= 2 && y.abs_diff(95) <= 2, "{x},{y}");
+}
+
+#[test]
+fn test_pyramid() {
+    let tmp = tempfile::tempdir().unwrap();
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let output = demmit(&[
+        "pyramid",
+        "--levels",
+        "3",
+        "--out-dir",
+        tmp.path().to_str().unwrap(),
+        src.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 3);
+    for (level, dim) in [(1, 601), (2, 301), (3, 151)] {
+        let img = image::open(tmp.path().join(format!("N44W072-{level}.png"))).unwrap();
+        assert_eq!((img.width(), img.height()), (dim, dim));
+    }
+}
+
+#[test]
+fn test_render_blend_multiply() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("relief.png");
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let output = demmit(&[
+        "render",
+        "--blend",
+        "multiply",
+        src.to_str().unwrap(),
+        out.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    let img = image::open(&out).unwrap();
+    assert_eq!(img.color(), image::ColorType::Rgb8);
+    assert_eq!((img.width(), img.height()), (1201, 1201));
+}
+
+#[test]
+fn test_render_blend_relief() {
+    let tmp = tempfile::tempdir().unwrap();
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let render = |opacity: &str| {
+        let out = tmp.path().join(format!("relief-{opacity}.png"));
+        let output = demmit(&[
+            "render",
+            "--blend",
+            "relief",
+            "--opacity",
+            opacity,
+            src.to_str().unwrap(),
+            out.to_str().unwrap(),
+        ]);
+        assert!(output.status.success(), "{output:?}");
+        image::open(&out).unwrap().into_rgb8()
+    };
+    let (full, none) = (render("1"), render("0"));
+    assert_eq!(full.dimensions(), (1201, 1201));
+    // Without shadows, every pixel is at least as bright.
+    assert!(full
+        .pixels()
+        .zip(none.pixels())
+        .all(|(full, none)| (0..3).all(|c| full[c] <= none[c])));
+    assert_ne!(full, none);
+}
+
+#[test]
+fn test_render_dir_dry_run() {
+    let tmp = tempfile::tempdir().unwrap();
+    let src_dir = three_arcsecond_dir().join("..").join("1arcsecond");
+    let output = demmit(&[
+        "render-dir",
+        "--verbose",
+        "--dry-run",
+        src_dir.to_str().unwrap(),
+        tmp.path().to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), 2, "{stdout}");
+    assert!(stdout.contains("N38W105.hgt"), "{stdout}");
+    assert!(stdout.contains("N44W072.hgt"), "{stdout}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("found 2 tiles"), "{stderr}");
+    assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_render_overwrite_policy() {
+    let tmp = tempfile::tempdir().unwrap();
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let out = tmp.path().join("N44W072.png");
+    std::fs::write(&out, "existing").unwrap();
+
+    let output = demmit(&[
+        "render",
+        "--overwrite-policy",
+        "skip",
+        src.to_str().unwrap(),
+        out.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), "existing");
+
+    let output = demmit(&[
+        "render",
+        "--overwrite-policy",
+        "error",
+        src.to_str().unwrap(),
+        out.to_str().unwrap(),
+    ]);
+    assert!(!output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("already exists"), "{stderr}");
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), "existing");
+
+    // Every output of `render-dir` already exists, so nothing is
+    // rendered.
+    let src_dir = three_arcsecond_dir().join("..").join("1arcsecond");
+    for stem in ["N38W105", "N44W072"] {
+        std::fs::write(tmp.path().join(format!("{stem}.png")), "existing").unwrap();
+    }
+    let output = demmit(&[
+        "render-dir",
+        "--verbose",
+        "--overwrite-policy",
+        "skip",
+        src_dir.to_str().unwrap(),
+        tmp.path().to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(stderr.matches("skipping").count(), 2, "{stderr}");
+    assert!(!stderr.contains("rendering"), "{stderr}");
+}
+
+#[test]
+fn test_render_timing() {
+    let tmp = tempfile::tempdir().unwrap();
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let out = tmp.path().join("N44W072.png");
+    let output = demmit(&[
+        "render",
+        "--hillshade",
+        "--timing",
+        src.to_str().unwrap(),
+        out.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(out.exists());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for phase in ["load", "matrix", "shade", "encode", "write"] {
+        assert!(stderr.contains(&format!("timing: {phase}: ")), "{stderr}");
+    }
+}
+
+#[test]
+fn test_render_ramp_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let tile = nasadem::Tile::load(&src).unwrap();
+    let (min, max) = (tile.min_elevation(), tile.max_elevation());
+    let ramp = tmp.path().join("gray.csv");
+    std::fs::write(&ramp, format!("{min},0,0,0\n{max},255,255,255\n")).unwrap();
+
+    let gray = tmp.path().join("gray.png");
+    let output = demmit(&[
+        "render",
+        "--depth",
+        "8",
+        src.to_str().unwrap(),
+        gray.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    let ramped = tmp.path().join("ramped.png");
+    let output = demmit(&[
+        "render",
+        "--ramp-file",
+        ramp.to_str().unwrap(),
+        src.to_str().unwrap(),
+        ramped.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+
+    // The grayscale render truncates while the ramp rounds.
+    let gray = image::open(gray).unwrap().into_luma8();
+    let ramped = image::open(ramped).unwrap().into_rgb8();
+    assert_eq!(gray.dimensions(), ramped.dimensions());
+    for (gray, ramped) in gray.pixels().zip(ramped.pixels()) {
+        let [red, green, blue] = ramped.0;
+        assert!(red == green && green == blue, "{ramped:?}");
+        assert!(red.abs_diff(gray.0[0]) <= 1, "{ramped:?} vs {gray:?}");
+    }
+}
+
+#[test]
+fn test_info() {
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let output = demmit(&["info", src.to_str().unwrap()]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("sw corner: (-72, 44)"), "{stdout}");
+    assert!(
+        stdout.contains("3 arcseconds (1201 x 1201 samples)"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("max elevation: 1908 m"), "{stdout}");
+}
+
+#[test]
+fn test_info_truncated() {
+    let tmp = tempfile::tempdir().unwrap();
+    let src = tmp.path().join("N44W072.hgt");
+    let full = std::fs::read(three_arcsecond_dir().join("N44W072.hgt")).unwrap();
+    std::fs::write(&src, &full[..1000]).unwrap();
+    let output = demmit(&["info", "--raw", src.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("sw corner: (-72, 44)"), "{stdout}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("invalid HGT file len 1000"), "{stderr}");
+}
+
+#[test]
+fn test_render_equal_ground_aspect() {
+    let tmp = tempfile::tempdir().unwrap();
+    // A flat 3-arcsecond tile at 60°N, where a degree of longitude
+    // spans half the ground of a degree of latitude.
+    let src = tmp.path().join("N60W072.hgt");
+    std::fs::write(&src, vec![0u8; 1201 * 1201 * 2]).unwrap();
+    let out = tmp.path().join("N60W072.png");
+    let output = demmit(&[
+        "render",
+        "--equal-ground-aspect",
+        src.to_str().unwrap(),
+        out.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    let img = image::open(&out).unwrap();
+    assert_eq!(img.height(), 1201);
+    // Scaled by cos(60.5°), the latitude of the tile's center.
+    assert_eq!(img.width(), 591);
+}
+
+#[test]
+fn test_render_match_photo() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("matched.png");
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let photo: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "tests",
+        "fixtures",
+        "mt-washington-photo.jpg",
+    ]
+    .iter()
+    .collect();
+    let output = demmit(&[
+        "render",
+        "--verbose",
+        "--hillshade",
+        "--match-photo",
+        photo.to_str().unwrap(),
+        src.to_str().unwrap(),
+        out.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    // Taken 2023-07-15 14:30 EDT on the summit of Mt Washington.
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("sun at azimuth 229.8°, elevation 59.4°"),
+        "{stderr}"
+    );
+    assert!(out.exists());
+
+    let no_exif = tmp.path().join("no-exif.jpg");
+    image::GrayImage::new(8, 8).save(&no_exif).unwrap();
+    let output = demmit(&[
+        "render",
+        "--hillshade",
+        "--match-photo",
+        no_exif.to_str().unwrap(),
+        src.to_str().unwrap(),
+        out.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("using --azimuth and --elevation"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_render_z_factor() {
+    let tmp = tempfile::tempdir().unwrap();
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let variance = |z_factor: &str| {
+        let out = tmp.path().join(format!("z{z_factor}.png"));
+        let output = demmit(&[
+            "render",
+            "--hillshade",
+            "--z-factor",
+            z_factor,
+            src.to_str().unwrap(),
+            out.to_str().unwrap(),
+        ]);
+        assert!(output.status.success(), "{output:?}");
+        let img = image::open(&out).unwrap().into_luma16();
+        let vals: Vec<f64> = img.pixels().map(|p| f64::from(p.0[0])).collect();
+        #[allow(clippy::cast_precision_loss)]
+        let n = vals.len() as f64;
+        let mean = vals.iter().sum::<f64>() / n;
+        vals.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n
+    };
+    // Exaggerated relief shades with more contrast.
+    assert!(variance("3") > variance("1"));
+}
+
+#[test]
+fn test_render_multidirectional() {
+    let tmp = tempfile::tempdir().unwrap();
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let render = |extra: &[&str], name: &str| {
+        let out = tmp.path().join(name);
+        let mut args = vec!["render", "--hillshade"];
+        args.extend_from_slice(extra);
+        args.extend([src.to_str().unwrap(), out.to_str().unwrap()]);
+        let output = demmit(&args);
+        assert!(output.status.success(), "{output:?}");
+        image::open(&out).unwrap().into_luma16()
+    };
+    let single = render(&[], "single.png");
+    let soft = render(&["--multidirectional"], "soft.png");
+    // `--azimuth` is ignored.
+    let turned = render(&["--multidirectional", "--azimuth", "90"], "turned.png");
+    assert_eq!(soft.dimensions(), single.dimensions());
+    assert_ne!(soft, single);
+    assert_eq!(soft, turned);
+    // Softening lifts the deepest shadows.
+    let darkest = |img: &image::ImageBuffer<image::Luma<u16>, Vec<u16>>| {
+        img.pixels().filter(|p| p.0[0] == 0).count()
+    };
+    assert!(darkest(&soft) < darkest(&single));
+}
+
+#[test]
+fn test_sample() {
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let src = src.to_str().unwrap();
+    let output = demmit(&["sample", "--coord", "44.2705,-71.30325", src]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "1903\n");
+
+    let output = demmit(&[
+        "sample",
+        "--interpolate",
+        "--coord",
+        "44.2705,-71.30325",
+        src,
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    let elev: f64 = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .unwrap();
+    assert!((1895.0..=1910.0).contains(&elev), "{elev}");
+
+    let output = demmit(&["sample", "--coord", "45.5,-71.3", src]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("is not on this tile"), "{stderr}");
+}
+
+#[test]
+fn test_render_dir_two_pass() {
+    // Adjacent 3-arcsecond tiles rising eastward one meter per sample,
+    // continuously across their shared edge.
+    let src_dir = tempfile::tempdir().unwrap();
+    for (name, offset) in [("N44W072.hgt", 0_i16), ("N44W071.hgt", 1200)] {
+        let samples: Vec<u8> = (0..1201 * 1201_i32)
+            .flat_map(|idx| (i16::try_from(idx % 1201).unwrap() + offset).to_be_bytes())
+            .collect();
+        std::fs::write(src_dir.path().join(name), samples).unwrap();
+    }
+    // Brightness of the west tile's east edge and the east tile's west
+    // edge, which sample the same ground.
+    let shared_edge = |out_dir: &std::path::Path| {
+        let west = image::open(out_dir.join("N44W072.png"))
+            .unwrap()
+            .into_luma16();
+        let east = image::open(out_dir.join("N44W071.png"))
+            .unwrap()
+            .into_luma16();
+        (west.get_pixel(1200, 600).0[0], east.get_pixel(0, 600).0[0])
+    };
+
+    let single = tempfile::tempdir().unwrap();
+    let output = demmit(&[
+        "render-dir",
+        src_dir.path().to_str().unwrap(),
+        single.path().to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    // Each tile spans its own full range.
+    assert_eq!(shared_edge(single.path()), (u16::MAX, 0));
+
+    let two_pass = tempfile::tempdir().unwrap();
+    let output = demmit(&[
+        "render-dir",
+        "--two-pass",
+        src_dir.path().to_str().unwrap(),
+        two_pass.path().to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("global extent: 0 m to 2400 m"), "{stderr}");
+    let (west, east) = shared_edge(two_pass.path());
+    assert_eq!(west, east);
+    assert_eq!(west, 32768);
+
+    let output = demmit(&[
+        "render-dir",
+        "--two-pass",
+        "--hillshade",
+        src_dir.path().to_str().unwrap(),
+        two_pass.path().to_str().unwrap(),
+    ]);
+    assert!(!output.status.success(), "{output:?}");
+}
@@ -164,8 +164,58 @@ fn walk_three_arcsecond_mmap_tile(c: &mut Criterion) {
     walk_tile(c, 1201, "Walk in-memory 3-arcsecond tile", &tile);
 }
 
+/// Compares looking up a diagonal path's samples one at a time with
+/// looking them up in one batch.
+fn get_many_vs_loop(c: &mut Criterion) {
+    let path = nw_to_se(3601);
+    for (title, tile) in [
+        ("in-memory", Tile::load(one_arcsecond_tile_path()).unwrap()),
+        ("mmap", Tile::memmap(one_arcsecond_tile_path()).unwrap()),
+    ] {
+        let mut group = c.benchmark_group(format!("Look up 1-arcsecond {title} tile path"));
+        group.bench_function("get loop", |b| {
+            b.iter(|| black_box(path.iter().map(|&xy| tile.get(xy)).collect::<Vec<_>>()));
+        });
+        group.bench_function("get_many", |b| b.iter(|| black_box(tile.get_many(&path))));
+        group.finish();
+    }
+}
+
+/// Compares loading the 3-arcsecond tile from plain, gzip, and (with
+/// the `lz4` and `zstd` features) LZ4 and Zstandard compressed files.
+fn load_compressed_tile(c: &mut Criterion) {
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    let src = three_arcsecond_tile_path();
+    let dir = tempfile::tempdir().unwrap();
+    let gz = dir.path().join("N44W072.hgt.gz");
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&std::fs::read(&src).unwrap()).unwrap();
+    std::fs::write(&gz, encoder.finish().unwrap()).unwrap();
+
+    let mut group = c.benchmark_group("Load 3-arcsecond tile");
+    group.bench_function("plain", |b| b.iter(|| black_box(Tile::load(&src).unwrap())));
+    group.bench_function("gzip", |b| b.iter(|| black_box(Tile::load(&gz).unwrap())));
+    #[cfg(feature = "lz4")]
+    {
+        Tile::load(&src).unwrap().save_lz4(dir.path()).unwrap();
+        let lz4 = dir.path().join("N44W072.hgt.lz4");
+        group.bench_function("lz4", |b| b.iter(|| black_box(Tile::load(&lz4).unwrap())));
+    }
+    #[cfg(feature = "zstd")]
+    {
+        Tile::load(&src).unwrap().save_zstd(dir.path()).unwrap();
+        let zstd = dir.path().join("N44W072.hgt.zst");
+        group.bench_function("zstd", |b| b.iter(|| black_box(Tile::load(&zstd).unwrap())));
+    }
+    group.finish();
+}
+
 criterion_group!(
     benches,
+    load_compressed_tile,
+    get_many_vs_loop,
     walk_one_arcsecond_inmem_tile,
     walk_one_arcsecond_mmap_tile,
     walk_three_arcsecond_inmem_tile,
@@ -0,0 +1,327 @@
+//! Terrain analysis products computed over a whole [`Tile`].
+
+use crate::{
+    geo::Coord,
+    tile::{box_sum, downslope_azimuth},
+    Elev, NasademError, Tile, C, VOID,
+};
+use nalgebra::{DMatrix, Scalar};
+
+/// Unit of the slopes returned by [`Tile::slope_matrix_in`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SlopeUnit {
+    /// Degrees from horizontal, `0` for flat ground and approaching
+    /// `90` for cliffs.
+    #[default]
+    Degrees,
+    /// Percent grade, the rise over run times `100`, so a 45° slope
+    /// is `100`.
+    Percent,
+}
+
+impl Tile {
+    /// Returns the Topographic Position Index (TPI) of every sample.
+    ///
+    /// TPI is a sample's elevation minus the mean elevation of its
+    /// neighbors, where neighbors are all samples within
+    /// `radius_cells` rows and columns of it (a square window,
+    /// clipped at the tile's edges). Positive values mark ridges and
+    /// peaks, negative values valleys and the foot of slopes.
+    ///
+    /// Voids are excluded from the neighborhood mean. Void samples,
+    /// and samples without any valid neighbors, are `NaN`.
+    ///
+    /// The output is a `(rows, cols)` matrix where element `(0, 0)`
+    /// is the NW corner of the tile.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn tpi(&self, radius_cells: usize) -> DMatrix<f32> {
+        let (cols, rows) = self.dimensions();
+        let sums = self.integral_image();
+        let counts = self.summed_area(|elev| i64::from(elev != VOID));
+        DMatrix::from_fn(rows, cols, |y, x| {
+            let elev = self.get_xy_unchecked((x, y));
+            if elev == VOID {
+                return f32::NAN;
+            }
+            let nw = (
+                x.saturating_sub(radius_cells),
+                y.saturating_sub(radius_cells),
+            );
+            let se = (
+                (x + radius_cells).min(cols - 1),
+                (y + radius_cells).min(rows - 1),
+            );
+            let sum = box_sum(&sums, cols, nw, se) - i64::from(elev);
+            let count = box_sum(&counts, cols, nw, se) - 1;
+            if count == 0 {
+                f32::NAN
+            } else {
+                (f64::from(elev) - sum as f64 / count as f64) as f32
+            }
+        })
+    }
+
+    /// Returns the slope of every sample in degrees, e.g. to threshold
+    /// for avalanche terrain.
+    ///
+    /// Equivalent to [`Tile::slope_matrix_in`] with
+    /// [`SlopeUnit::Degrees`].
+    pub fn slope_matrix(&self) -> DMatrix<f32> {
+        self.slope_matrix_in(SlopeUnit::Degrees)
+    }
+
+    /// Returns the slope of every sample in `unit`.
+    ///
+    /// Slopes are those of [`Sample::slope`](crate::Sample::slope):
+    /// Horn's method over the eight surrounding samples, with columns
+    /// spaced by their true ground distance at each row's latitude.
+    /// Void samples are `NaN`. The output is a `(rows, cols)` matrix
+    /// where element `(0, 0)` is the NW corner of the tile.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn slope_matrix_in(&self, unit: SlopeUnit) -> DMatrix<f32> {
+        let (cols, rows) = self.dimensions();
+        DMatrix::from_fn(rows, cols, |y, x| {
+            self.horn_gradient((x, y)).map_or(f32::NAN, |(dzdx, dzdy)| {
+                let grade = dzdx.hypot(dzdy);
+                match unit {
+                    SlopeUnit::Degrees => grade.atan().to_degrees() as f32,
+                    SlopeUnit::Percent => (grade * 100.0) as f32,
+                }
+            })
+        })
+    }
+
+    /// Returns the aspect of every sample: the compass direction its
+    /// terrain faces, in degrees clockwise from north in `[0, 360)`.
+    ///
+    /// Aspects are those of [`Sample::aspect`](crate::Sample::aspect),
+    /// from the same gradient as [`Tile::slope_matrix`]. Flat samples,
+    /// which face no direction, are `-1.0` so they can be masked, and
+    /// void samples are `NaN`. The output is a `(rows, cols)` matrix
+    /// where element `(0, 0)` is the NW corner of the tile.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn aspect_matrix(&self) -> DMatrix<f32> {
+        let (cols, rows) = self.dimensions();
+        DMatrix::from_fn(rows, cols, |y, x| {
+            self.horn_gradient((x, y)).map_or(f32::NAN, |gradient| {
+                downslope_azimuth(gradient).map_or(-1.0, |azimuth| azimuth.to_degrees() as f32)
+            })
+        })
+    }
+
+    /// Returns a hillshade of this tile with geometrically correct
+    /// slopes.
+    ///
+    /// Each output cell is the reflectance, in `[0, 1]`, of a
+    /// lambertian surface lit by a sun at `sun_elev_rad` above the
+    /// horizon and `sun_azimuth_rad` clockwise from north.
+    ///
+    /// Gradients are found by Horn's method, as for
+    /// [`Tile::slope_matrix`], and divided by the true ground spacing
+    /// along each axis. North-south spacing is constant, but
+    /// east-west spacing shrinks with the cosine of latitude, so
+    /// shading computed in sample units (as if cells were square)
+    /// exaggerates east-west slopes far from the equator.
+    ///
+    /// Void samples are `NaN`. Void neighbors are replaced by the
+    /// center sample, and off-tile neighbors by the nearest edge
+    /// sample. The output is a `(rows, cols)` matrix where element
+    /// `(0, 0)` is the NW corner of the tile.
+    pub fn hillshade(&self, sun_elev_rad: f32, sun_azimuth_rad: f32) -> DMatrix<f32> {
+        self.shade_with_spacing(sun_elev_rad, sun_azimuth_rad, |y| self.row_spacing_m(y))
+    }
+
+    /// Returns the difference of Gaussians of this tile's
+    /// elevation: the tile smoothed with a Gaussian of standard
+    /// deviation `sigma1` cells minus the tile smoothed with one of
+    /// `sigma2` cells.
+    ///
+    /// With `sigma1 < sigma2` this is a band-pass filter that keeps
+    /// features, such as ridges and valleys, whose size lies between
+    /// the two scales and suppresses both finer noise and broader
+    /// relief. A sigma of zero leaves the elevation unsmoothed.
+    ///
+    /// Voids are excluded from both smoothings and are `NaN` in the
+    /// output. The output is a `(rows, cols)` matrix where element
+    /// `(0, 0)` is the NW corner of the tile.
+    pub fn dog(&self, sigma1: f32, sigma2: f32) -> DMatrix<f32> {
+        self.gaussian_smooth(sigma1) - self.gaussian_smooth(sigma2)
+    }
+
+    /// Returns this tile's samples as a `(rows, cols)` matrix, where
+    /// element `(0, 0)` is the NW corner of the tile.
+    ///
+    /// Void samples are `T::from(0)`; use
+    /// [`Tile::to_matrix_with_void`] to mark them differently, e.g.
+    /// as `NaN`.
+    pub fn to_matrix<T>(&self) -> DMatrix<T>
+    where
+        T: From<Elev> + Scalar + Copy,
+    {
+        self.to_matrix_with_void(T::from(0))
+    }
+
+    /// Returns this tile's samples as a `(rows, cols)` matrix, where
+    /// element `(0, 0)` is the NW corner of the tile, with void
+    /// samples replaced by `void`.
+    pub fn to_matrix_with_void<T>(&self, void: T) -> DMatrix<T>
+    where
+        T: From<Elev> + Scalar + Copy,
+    {
+        let (cols, rows) = self.dimensions();
+        DMatrix::from_fn(rows, cols, |y, x| match self.get_xy_unchecked((x, y)) {
+            VOID => void,
+            elev => T::from(elev),
+        })
+    }
+
+    /// Returns this tile's elevation multiplied by `exaggeration`,
+    /// e.g. to steepen relief before shading.
+    ///
+    /// `f32` represents every whole number only up to 2^24, so beyond
+    /// that neighboring elevations start to collapse together and
+    /// gradients computed from them become noise. Rather than
+    /// silently degrade (or overflow to infinity), this fails if any
+    /// scaled elevation would exceed that magnitude.
+    ///
+    /// Void samples are `NaN`. The output is a `(rows, cols)` matrix
+    /// where element `(0, 0)` is the NW corner of the tile.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NasademError::Exaggeration`] if `exaggeration` is not
+    /// finite or scales an elevation past 2^24.
+    pub fn to_matrix_f32_checked(&self, exaggeration: f32) -> Result<DMatrix<f32>, NasademError> {
+        const MAX_EXACT: f32 = 16_777_216.0;
+        let max_abs = self.fold(0.0_f32, |max, elev| {
+            if elev == VOID {
+                max
+            } else {
+                max.max(f32::from(elev).abs())
+            }
+        });
+        if !exaggeration.is_finite() || max_abs * exaggeration.abs() > MAX_EXACT {
+            return Err(NasademError::Exaggeration(exaggeration));
+        }
+        let (cols, rows) = self.dimensions();
+        Ok(DMatrix::from_fn(rows, cols, |y, x| {
+            match self.get_xy_unchecked((x, y)) {
+                VOID => f32::NAN,
+                elev => f32::from(elev) * exaggeration,
+            }
+        }))
+    }
+
+    /// Returns the `(2 * half_size + 1)`-sample square window of
+    /// elevations centered on the sample nearest `center`, e.g. as a
+    /// feature patch for machine learning.
+    ///
+    /// Returns `None` if `center` is off this tile or the window
+    /// would extend past its edges. The output is a `(rows, cols)`
+    /// matrix where element `(0, 0)` is the window's NW corner and
+    /// element `(half_size, half_size)` is the center sample.
+    pub fn patch(&self, center: Coord<C>, half_size: usize) -> Option<DMatrix<Elev>> {
+        let (cols, rows) = self.dimensions();
+        let (x, y) = self.sample(center)?.xy();
+        let (x0, y0) = (x.checked_sub(half_size)?, y.checked_sub(half_size)?);
+        if x + half_size >= cols || y + half_size >= rows {
+            return None;
+        }
+        let size = 2 * half_size + 1;
+        Some(DMatrix::from_fn(size, size, |row, col| {
+            self.get_xy_unchecked((x0 + col, y0 + row))
+        }))
+    }
+}
+
+/// Private API
+impl Tile {
+    /// Returns this tile's elevation smoothed by a Gaussian with
+    /// standard deviation `sigma` cells, truncated at three sigma.
+    ///
+    /// The kernel is applied separably, along rows then columns, and
+    /// renormalized by the weight of the valid samples it covers, so
+    /// voids and the tile's edges don't bias the mean. Void samples
+    /// are `NaN`.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub(crate) fn gaussian_smooth(&self, sigma: f32) -> DMatrix<f32> {
+        let (cols, rows) = self.dimensions();
+        let radius = (3.0 * sigma.max(0.0)).ceil() as usize;
+        let kernel: Vec<f32> = if radius == 0 {
+            vec![1.0]
+        } else {
+            (0..=2 * radius)
+                .map(|i| {
+                    let d = i as f32 - radius as f32;
+                    (-d * d / (2.0 * sigma * sigma)).exp()
+                })
+                .collect()
+        };
+        // Convolves `src` with the kernel along each row (east-west)
+        // or along each column (north-south).
+        let convolve = |src: &DMatrix<f32>, along_rows: bool| {
+            DMatrix::from_fn(rows, cols, |y, x| {
+                let (pos, len) = if along_rows { (x, cols) } else { (y, rows) };
+                let lo = pos.saturating_sub(radius);
+                let hi = (pos + radius).min(len - 1);
+                (lo..=hi)
+                    .map(|i| {
+                        let weight = kernel[i + radius - pos];
+                        weight * if along_rows { src[(y, i)] } else { src[(i, x)] }
+                    })
+                    .sum::<f32>()
+            })
+        };
+        let valid = DMatrix::from_fn(rows, cols, |y, x| {
+            f32::from(u8::from(self.get_xy_unchecked((x, y)) != VOID))
+        });
+        let weighted = DMatrix::from_fn(rows, cols, |y, x| match self.get_xy_unchecked((x, y)) {
+            VOID => 0.0,
+            elev => f32::from(elev),
+        });
+        let sums = convolve(&convolve(&weighted, true), false);
+        let weights = convolve(&convolve(&valid, true), false);
+        DMatrix::from_fn(rows, cols, |y, x| {
+            if valid[(y, x)] == 0.0 {
+                f32::NAN
+            } else {
+                sums[(y, x)] / weights[(y, x)]
+            }
+        })
+    }
+
+    /// Returns a hillshade where row `y`'s samples are `spacing_m(y)`
+    /// `(east-west, north-south)` meters apart.
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) fn shade_with_spacing<F>(
+        &self,
+        sun_elev_rad: f32,
+        sun_azimuth_rad: f32,
+        spacing_m: F,
+    ) -> DMatrix<f32>
+    where
+        F: Fn(usize) -> (C, C),
+    {
+        let (cols, rows) = self.dimensions();
+        // Unit vector pointing at the sun, where x is east, y is
+        // north, and z is up.
+        let (sun_x, sun_y, sun_z) = {
+            let (az_sin, az_cos) = C::from(sun_azimuth_rad).sin_cos();
+            let (el_sin, el_cos) = C::from(sun_elev_rad).sin_cos();
+            (az_sin * el_cos, az_cos * el_cos, el_sin)
+        };
+        DMatrix::from_fn(rows, cols, |y, x| {
+            let (dx_m, dy_m) = spacing_m(y);
+            let Some((dzdx, dzdy)) = self.horn_gradient_with_spacing((x, y), dx_m, dy_m) else {
+                return f32::NAN;
+            };
+            let norm = (dzdx * dzdx + dzdy * dzdy + 1.0).sqrt();
+            let reflection = (-dzdx * sun_x - dzdy * sun_y + sun_z) / norm;
+            reflection.clamp(0.0, 1.0) as f32
+        })
+    }
+}
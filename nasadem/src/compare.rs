@@ -0,0 +1,76 @@
+//! Comparing a tile's samples against a reference tile.
+
+use crate::{Elev, NasademError, Tile, VOID};
+
+/// Elevation error of a tile relative to a reference tile.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ErrorStats {
+    /// Root-mean-square elevation error in meters.
+    pub rmse: f64,
+    /// Largest absolute elevation error in meters.
+    pub max_abs_error: f64,
+    /// Number of samples compared, i.e., those valid in both tiles.
+    pub count: usize,
+}
+
+impl Tile {
+    /// Returns the root-mean-square elevation error of `self`
+    /// relative to `reference`.
+    ///
+    /// See [`Tile::error_stats`].
+    pub fn rmse_against(&self, reference: &Tile) -> Result<f64, NasademError> {
+        self.error_stats(reference).map(|stats| stats.rmse)
+    }
+
+    /// Returns elevation error statistics of `self` relative to
+    /// `reference`, e.g. to validate resampling or void filling.
+    ///
+    /// Samples that are void in either tile are excluded. If no
+    /// samples are valid in both, `rmse` and `max_abs_error` are
+    /// `NaN`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NasademError::IncompatibleGrids`] unless both tiles
+    /// cover the same extent on the same sample grid.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn error_stats(&self, reference: &Tile) -> Result<ErrorStats, NasademError> {
+        let (nw, ref_nw) = (self.xy_to_geo((0, 0)), reference.xy_to_geo((0, 0)));
+        if !self.is_grid_compatible(reference)
+            || self.dimensions() != reference.dimensions()
+            || (nw.x - ref_nw.x).abs() > 1e-9
+            || (nw.y - ref_nw.y).abs() > 1e-9
+        {
+            return Err(NasademError::IncompatibleGrids);
+        }
+        let (cols, rows) = self.dimensions();
+        let (mut sum_sq, mut max_abs_error, mut count) = (0.0, 0.0_f64, 0);
+        for y in 0..rows {
+            for x in 0..cols {
+                let (elev, ref_elev): (Elev, Elev) = (
+                    self.get_xy_unchecked((x, y)),
+                    reference.get_xy_unchecked((x, y)),
+                );
+                if elev == VOID || ref_elev == VOID {
+                    continue;
+                }
+                let err = f64::from(elev) - f64::from(ref_elev);
+                sum_sq += err * err;
+                max_abs_error = max_abs_error.max(err.abs());
+                count += 1;
+            }
+        }
+        if count == 0 {
+            return Ok(ErrorStats {
+                rmse: f64::NAN,
+                max_abs_error: f64::NAN,
+                count,
+            });
+        }
+        Ok(ErrorStats {
+            rmse: (sum_sq / count as f64).sqrt(),
+            max_abs_error,
+            count,
+        })
+    }
+}
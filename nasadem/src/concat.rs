@@ -0,0 +1,93 @@
+//! Joining adjacent tiles into one.
+
+use crate::{Elev, NasademError, Tile};
+
+/// Tolerance, in degrees, when comparing sample positions of two
+/// tiles.
+const EPSILON_DEG: f64 = 1e-9;
+
+impl Tile {
+    /// Returns a new in-memory tile spanning `self` and the tile
+    /// immediately east of it.
+    ///
+    /// Adjacent tiles overlap by one column of samples; the shared
+    /// column is taken from `self`. The result has the combined
+    /// width, typically making it non-square.
+    ///
+    /// # Errors
+    ///
+    /// - [`NasademError::IncompatibleGrids`] if the tiles differ in
+    ///   resolution or sample grid.
+    /// - [`NasademError::NotAdjacent`] unless `east_neighbor`'s west
+    ///   column is `self`'s east column, spanning the same rows.
+    pub fn concat_horizontal(&self, east_neighbor: &Tile) -> Result<Tile, NasademError> {
+        if !self.is_grid_compatible(east_neighbor) {
+            return Err(NasademError::IncompatibleGrids);
+        }
+        let (cols, rows) = self.dimensions();
+        let (east_cols, east_rows) = east_neighbor.dimensions();
+        let ne = self.xy_to_geo((cols - 1, 0));
+        let east_nw = east_neighbor.xy_to_geo((0, 0));
+        if rows != east_rows
+            || (ne.x - east_nw.x).abs() > EPSILON_DEG
+            || (ne.y - east_nw.y).abs() > EPSILON_DEG
+        {
+            return Err(NasademError::NotAdjacent);
+        }
+        let out_cols = cols + east_cols - 1;
+        let mut samples: Vec<Elev> = Vec::with_capacity(out_cols * rows);
+        for y in 0..rows {
+            samples.extend((0..cols).map(|x| self.get_xy_unchecked((x, y))));
+            samples.extend((1..east_cols).map(|x| east_neighbor.get_xy_unchecked((x, y))));
+        }
+        Ok(Tile::from_samples_at(
+            self.sw_corner_center(),
+            self.resolution(),
+            (out_cols, rows),
+            samples,
+        ))
+    }
+
+    /// Returns a new in-memory tile spanning `self` and the tile
+    /// immediately south of it.
+    ///
+    /// Adjacent tiles overlap by one row of samples; the shared row
+    /// is taken from `self`. The result has the combined height,
+    /// typically making it non-square.
+    ///
+    /// # Errors
+    ///
+    /// - [`NasademError::IncompatibleGrids`] if the tiles differ in
+    ///   resolution or sample grid.
+    /// - [`NasademError::NotAdjacent`] unless `south_neighbor`'s
+    ///   north row is `self`'s south row, spanning the same columns.
+    pub fn concat_vertical(&self, south_neighbor: &Tile) -> Result<Tile, NasademError> {
+        if !self.is_grid_compatible(south_neighbor) {
+            return Err(NasademError::IncompatibleGrids);
+        }
+        let (cols, rows) = self.dimensions();
+        let (south_cols, south_rows) = south_neighbor.dimensions();
+        let sw = self.xy_to_geo((0, rows - 1));
+        let south_nw = south_neighbor.xy_to_geo((0, 0));
+        if cols != south_cols
+            || (sw.x - south_nw.x).abs() > EPSILON_DEG
+            || (sw.y - south_nw.y).abs() > EPSILON_DEG
+        {
+            return Err(NasademError::NotAdjacent);
+        }
+        let out_rows = rows + south_rows - 1;
+        let mut samples: Vec<Elev> = Vec::with_capacity(cols * out_rows);
+        for y in 0..rows {
+            samples.extend((0..cols).map(|x| self.get_xy_unchecked((x, y))));
+        }
+        for y in 1..south_rows {
+            samples.extend((0..cols).map(|x| south_neighbor.get_xy_unchecked((x, y))));
+        }
+        Ok(Tile::from_samples_at(
+            south_neighbor.sw_corner_center(),
+            self.resolution(),
+            (cols, out_rows),
+            samples,
+        ))
+    }
+}
@@ -1,3 +1,4 @@
+use crate::{geo::Coord, TileIndex, C};
 use std::{error::Error as StdError, fmt, io, path::PathBuf};
 
 #[derive(Debug)]
@@ -6,6 +7,12 @@ pub enum NasademError {
     Io(io::Error),
     HgtName(std::path::PathBuf),
     HgtLen(u64, PathBuf),
+    IncompatibleGrids,
+    NotAdjacent,
+    ReadOnly,
+    Query(TileQueryError),
+    Exaggeration(f32),
+    Decompress(io::Error),
 }
 
 impl fmt::Display for NasademError {
@@ -16,6 +23,17 @@ impl fmt::Display for NasademError {
             NasademError::HgtLen(len, path) => {
                 write!(f, "invalid HGT file len {len} for {path:?}")
             }
+            NasademError::IncompatibleGrids => {
+                write!(f, "tiles do not share resolution and sample grid")
+            }
+            NasademError::NotAdjacent => write!(f, "tiles do not share an edge"),
+            NasademError::ReadOnly => write!(f, "tile samples are read-only"),
+            NasademError::Query(err) => err.fmt(f),
+            NasademError::Exaggeration(factor) => write!(
+                f,
+                "exaggeration {factor} scales elevations beyond f32 precision"
+            ),
+            NasademError::Decompress(err) => write!(f, "failed to decompress HGT file: {err}"),
         }
     }
 }
@@ -26,12 +44,65 @@ impl From<io::Error> for NasademError {
     }
 }
 
+impl From<TileQueryError> for NasademError {
+    fn from(other: TileQueryError) -> NasademError {
+        NasademError::Query(other)
+    }
+}
+
 impl StdError for NasademError {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
-        use NasademError::{HgtLen, HgtName, Io};
+        use NasademError::{
+            Decompress, Exaggeration, HgtLen, HgtName, IncompatibleGrids, Io, NotAdjacent, Query,
+            ReadOnly,
+        };
         match self {
             Io(err) => err.source(),
-            HgtName(_) | HgtLen(_, _) => None,
+            Decompress(err) => Some(err),
+            Query(err) => Some(err),
+            HgtName(_)
+            | HgtLen(_, _)
+            | IncompatibleGrids
+            | NotAdjacent
+            | ReadOnly
+            | Exaggeration(_) => None,
         }
     }
 }
+
+/// Reasons a [`Tile::try_get`](crate::Tile::try_get) query has no
+/// elevation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TileQueryError {
+    /// A linear or (x, y) index past the end of the tile, which holds
+    /// `len` samples.
+    OutOfBounds {
+        /// The offending index.
+        index: TileIndex,
+        /// Number of samples in the tile.
+        len: usize,
+    },
+    /// A geographic coordinate not covered by the tile.
+    OffTile {
+        /// The offending coordinate.
+        coord: Coord<C>,
+    },
+    /// The sample exists but is void (no data).
+    Void,
+}
+
+impl fmt::Display for TileQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TileQueryError::OutOfBounds { index, len } => {
+                write!(f, "index {index:?} out of bounds for tile of {len} samples")
+            }
+            TileQueryError::OffTile { coord } => {
+                write!(f, "({}, {}) is not on this tile", coord.x, coord.y)
+            }
+            TileQueryError::Void => write!(f, "sample is void"),
+        }
+    }
+}
+
+impl StdError for TileQueryError {}
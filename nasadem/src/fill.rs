@@ -0,0 +1,99 @@
+//! Void filling.
+
+use crate::{store::SampleStore, Elev, Tile, C, VOID};
+
+impl Tile {
+    /// Replaces every void sample with an inverse-distance weighted
+    /// mean of the valid samples within `radius_cells` rows and
+    /// columns of it, returning the number of samples filled.
+    ///
+    /// Weights fall off with the square of the distance in cells.
+    /// Voids wider than the search radius are filled from their
+    /// edges inward over repeated passes, each pass drawing on the
+    /// samples filled by the ones before it. After the first pass,
+    /// only voids within the radius of the previous pass's fills are
+    /// revisited, so each void is interpolated about once however
+    /// wide it is. A radius of zero is treated as one.
+    ///
+    /// A memory-mapped tile is first copied into memory, since its
+    /// mapping is read-only. A tile that is entirely void, e.g. open
+    /// ocean, has nothing to interpolate from and is left untouched,
+    /// returning `None`.
+    pub fn fill_voids(&mut self, radius_cells: usize) -> Option<usize> {
+        let voids: Vec<usize> = (0..self.len())
+            .filter(|&idx| self.samples.get_linear_unchecked(idx) == VOID)
+            .collect();
+        if voids.is_empty() {
+            return Some(0);
+        }
+        if voids.len() == self.len() {
+            return None;
+        }
+        if let SampleStore::MemMap(_) = self.samples {
+            let samples = std::mem::replace(&mut self.samples, SampleStore::Tombstone(0));
+            self.samples = SampleStore::InMem(samples.into_vec().into_boxed_slice());
+        }
+        let radius = radius_cells.max(1);
+        let filled = voids.len();
+        let (cols, rows) = self.dimensions();
+        // Voids that may have a valid sample within the radius. The
+        // first pass tries every void; later ones only those beside
+        // the samples just filled.
+        let mut frontier = voids;
+        let mut queued = vec![false; self.len()];
+        while !frontier.is_empty() {
+            let fills: Vec<(usize, Elev)> = frontier
+                .iter()
+                .filter_map(|&idx| Some((idx, self.inverse_distance_mean(idx, radius)?)))
+                .collect();
+            for idx in frontier.drain(..) {
+                queued[idx] = false;
+            }
+            for &(idx, elev) in &fills {
+                self.samples
+                    .set_linear_unchecked(idx, elev)
+                    .expect("in-memory and copy-on-write stores are writable");
+            }
+            for &(idx, _) in &fills {
+                let (x, y) = (idx % cols, idx / cols);
+                for ny in y.saturating_sub(radius)..=(y + radius).min(rows - 1) {
+                    for nx in x.saturating_sub(radius)..=(x + radius).min(cols - 1) {
+                        let neighbor = ny * cols + nx;
+                        if !queued[neighbor] && self.get_xy_unchecked((nx, ny)) == VOID {
+                            queued[neighbor] = true;
+                            frontier.push(neighbor);
+                        }
+                    }
+                }
+            }
+        }
+        self.invalidate_extremes();
+        Some(filled)
+    }
+}
+
+/// Private API
+impl Tile {
+    /// Returns the inverse-distance weighted mean of the valid
+    /// samples within `radius` rows and columns of sample `idx`, or
+    /// `None` if there are none.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    fn inverse_distance_mean(&self, idx: usize, radius: usize) -> Option<Elev> {
+        let (cols, rows) = self.dimensions();
+        let (x, y) = (idx % cols, idx / cols);
+        let (mut sum, mut weights) = (0.0, 0.0);
+        for ny in y.saturating_sub(radius)..=(y + radius).min(rows - 1) {
+            for nx in x.saturating_sub(radius)..=(x + radius).min(cols - 1) {
+                let elev = self.get_xy_unchecked((nx, ny));
+                if elev == VOID {
+                    continue;
+                }
+                let (dx, dy) = (nx.abs_diff(x) as C, ny.abs_diff(y) as C);
+                let weight = 1.0 / (dx * dx + dy * dy);
+                sum += weight * C::from(elev);
+                weights += weight;
+            }
+        }
+        (weights > 0.0).then(|| (sum / weights).round() as Elev)
+    }
+}
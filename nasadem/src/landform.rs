@@ -0,0 +1,133 @@
+//! Slope-position landform classification.
+
+use crate::{Tile, C};
+use nalgebra::DMatrix;
+
+/// A slope-position landform class, as produced by
+/// [`Tile::landform_classes`].
+///
+/// The discriminants are the values stored in the output matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Landform {
+    /// Well below its surroundings.
+    Valley = 1,
+    /// Somewhat below its surroundings.
+    LowerSlope = 2,
+    /// Level with its surroundings and gently sloped.
+    Flat = 3,
+    /// Level with its surroundings but steeply sloped.
+    MiddleSlope = 4,
+    /// Somewhat above its surroundings.
+    UpperSlope = 5,
+    /// Well above its surroundings, including peaks.
+    Ridge = 6,
+}
+
+impl Landform {
+    /// Value stored for void samples.
+    pub const VOID: u8 = 0;
+
+    /// Returns the landform stored as `value`, or `None` for
+    /// [`Landform::VOID`] and unknown values.
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(Self::Valley),
+            2 => Some(Self::LowerSlope),
+            3 => Some(Self::Flat),
+            4 => Some(Self::MiddleSlope),
+            5 => Some(Self::UpperSlope),
+            6 => Some(Self::Ridge),
+            _ => None,
+        }
+    }
+}
+
+/// Parameters for [`Tile::landform_classes`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LandformParams {
+    /// Neighborhood radius, in cells, of the Topographic Position
+    /// Index (see [`Tile::tpi`]).
+    pub radius_cells: usize,
+    /// TPI, in standard deviations, beyond which a sample is a ridge
+    /// or valley.
+    pub ridge_sd: f32,
+    /// TPI, in standard deviations, beyond which a sample is an
+    /// upper or lower slope.
+    pub slope_sd: f32,
+    /// Steepest slope, in degrees, of a flat sample, as measured by
+    /// [`Sample::slope`](crate::Sample::slope).
+    pub flat_slope_deg: f32,
+}
+
+impl Default for LandformParams {
+    /// Weiss's original thresholds over a 5-cell radius.
+    fn default() -> Self {
+        Self {
+            radius_cells: 5,
+            ridge_sd: 1.0,
+            slope_sd: 0.5,
+            flat_slope_deg: 5.0,
+        }
+    }
+}
+
+impl Tile {
+    /// Returns the slope-position landform class of every sample, as
+    /// [`Landform`] discriminants.
+    ///
+    /// This is Weiss's classification: each sample's TPI is
+    /// standardized by the standard deviation of TPI across the
+    /// tile, then samples far above or below their surroundings
+    /// become ridges, upper slopes, lower slopes, or valleys, and the
+    /// rest are split by slope into flats and middle slopes.
+    ///
+    /// Void samples are [`Landform::VOID`]. The output is a `(rows,
+    /// cols)` matrix where element `(0, 0)` is the NW corner of the
+    /// tile.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn landform_classes(&self, params: &LandformParams) -> DMatrix<u8> {
+        let tpi = self.tpi(params.radius_cells);
+        let sd = {
+            let (n, sum, sum_sq) = tpi.iter().filter(|v| !v.is_nan()).fold(
+                (0_usize, 0.0, 0.0),
+                |(n, sum, sum_sq), &v| {
+                    let v = C::from(v);
+                    (n + 1, sum + v, sum_sq + v * v)
+                },
+            );
+            if n == 0 {
+                0.0
+            } else {
+                let mean = sum / n as C;
+                (sum_sq / n as C - mean * mean).max(0.0).sqrt() as f32
+            }
+        };
+        let flat_slope = C::from(params.flat_slope_deg).to_radians().tan();
+        DMatrix::from_fn(tpi.nrows(), tpi.ncols(), |y, x| {
+            let (dx_m, dy_m) = self.row_spacing_m(y);
+            let Some((dzdx, dzdy)) = self.horn_gradient_with_spacing((x, y), dx_m, dy_m) else {
+                return Landform::VOID;
+            };
+            let tpi = tpi[(y, x)];
+            let class = if tpi.is_nan() {
+                // A valid sample without valid neighbors has no
+                // position relative to them.
+                Landform::Flat
+            } else if tpi > params.ridge_sd * sd {
+                Landform::Ridge
+            } else if tpi > params.slope_sd * sd {
+                Landform::UpperSlope
+            } else if tpi < -params.ridge_sd * sd {
+                Landform::Valley
+            } else if tpi < -params.slope_sd * sd {
+                Landform::LowerSlope
+            } else if dzdx.hypot(dzdy) <= flat_slope {
+                Landform::Flat
+            } else {
+                Landform::MiddleSlope
+            };
+            class as u8
+        })
+    }
+}
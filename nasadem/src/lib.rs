@@ -1,24 +1,61 @@
 #![deny(missing_docs)]
 #![cfg_attr(not(doctest), doc = include_str!("../README.md"))]
 
+#[cfg(feature = "nalgebra")]
+pub use crate::analysis::SlopeUnit;
+#[cfg(feature = "nalgebra")]
+pub use crate::landform::{Landform, LandformParams};
+#[cfg(feature = "tar")]
+pub use crate::tar_source::TarTileSource;
+#[cfg(feature = "image")]
+pub use crate::to_image::Palette;
+#[cfg(feature = "proj")]
+pub use crate::utm::Hemisphere;
 pub use crate::{
-    error::NasademError,
+    compare::ErrorStats,
+    error::{NasademError, TileQueryError},
+    overview::Overviews,
     sample::Sample,
-    tile::{Tile, TileIndex},
+    smooth::VoidPolicy,
+    tile::{SnapMode, Tile, TileIndex},
+    tile_set::TileSet,
+    transcode::{Compression, TranscodeOptions},
+    util::{extract_resolution, filename_for, parse_sw_corner, tile_name_for},
+    visibility::STANDARD_K_FACTOR,
 };
 pub use geo;
 #[cfg(feature = "image")]
 pub use image;
+#[cfg(feature = "nalgebra")]
+pub use nalgebra;
 
+#[cfg(feature = "nalgebra")]
+mod analysis;
+mod compare;
+mod concat;
 mod error;
+mod fill;
+#[cfg(feature = "nalgebra")]
+mod landform;
+#[cfg(feature = "nalgebra")]
+mod local_grid;
+mod overview;
 mod sample;
+mod smooth;
 pub(crate) mod store;
+#[cfg(feature = "tar")]
+mod tar_source;
 #[cfg(test)]
 mod tests;
 mod tile;
+mod tile_set;
 #[cfg(feature = "image")]
 mod to_image;
+mod transcode;
 pub(crate) mod util;
+#[cfg(feature = "proj")]
+mod utm;
+mod visibility;
 
 /// Base floating point type used for all coordinates and calculations.
 ///
@@ -32,5 +69,16 @@ pub type C = f64;
 /// Bit representation of elevation samples.
 pub type Elev = i16;
 
+/// Sample value SRTM uses to mark voids (no data), e.g. radar
+/// shadows in steep terrain.
+pub const VOID: Elev = Elev::MIN;
+
 const ARCSEC_PER_DEG: C = 3600.0;
 const HALF_ARCSEC: C = 1.0 / (2.0 * 3600.0);
+
+/// Mean earth radius, in meters.
+pub(crate) const MEAN_EARTH_RADIUS_M: C = 6_371_008.8;
+
+/// Approximate ground distance of one degree of latitude, using the
+/// mean earth radius.
+pub(crate) const METERS_PER_DEG: C = MEAN_EARTH_RADIUS_M * std::f64::consts::PI / 180.0;
@@ -0,0 +1,77 @@
+//! In-memory overviews for multi-resolution access.
+
+use crate::{geo::Coord, Elev, Tile, C};
+
+/// Progressively downsampled copies of a tile, built by
+/// [`Tile::build_overviews`].
+///
+/// Level 0 is a full-resolution copy of the source and level `n` is
+/// the source [`Tile::downsample`]d by a factor of `2^n`, each sample
+/// the mean of the source block centered on it, so level `n` has
+/// `2^n` times the source's resolution. Since every level holds about
+/// a quarter of the samples of the previous one, all levels together
+/// take about 4/3 the memory of an in-memory copy of the source.
+pub struct Overviews {
+    levels: Vec<Tile>,
+}
+
+impl Overviews {
+    /// Returns the number of levels, including level 0.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Returns the tile at `level`, or `None` if there is no such
+    /// level.
+    pub fn level(&self, level: usize) -> Option<&Tile> {
+        self.levels.get(level)
+    }
+
+    /// Returns the elevation at `coord` from the level best suited to
+    /// `zoom`, or `None` if `coord` is off the tile.
+    ///
+    /// `zoom` is the number of source samples spanned by one output
+    /// pixel. The finest level that is no finer than `zoom` is used:
+    /// `zoom <= 1.0` reads level 0, `2.0..4.0` reads level 1, and so
+    /// on, clamped to the coarsest level.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn sample(&self, coord: Coord<C>, zoom: C) -> Option<Elev> {
+        let level = if zoom > 1.0 {
+            (zoom.log2().floor() as usize).min(self.levels.len() - 1)
+        } else {
+            0
+        };
+        self.levels[level].get(coord)
+    }
+}
+
+impl Tile {
+    /// Returns `levels` overviews of this tile in addition to a
+    /// full-resolution, in-memory copy of it at level 0.
+    ///
+    /// Each overview `n` is [`Tile::downsample`]d from this tile by a
+    /// factor of `2^n`, so it is the mean of the source samples it
+    /// covers rather than a mean of means. See [`Overviews`] for
+    /// memory cost.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this tile can not be halved `levels` times, i.e., if
+    /// `2^levels` does not evenly divide its sample intervals.
+    pub fn build_overviews(&self, levels: usize) -> Overviews {
+        let copy = Tile::from_samples_at(
+            self.sw_corner_center(),
+            self.resolution(),
+            self.dimensions(),
+            self.fold(Vec::with_capacity(self.len()), |mut samples, elev| {
+                samples.push(elev);
+                samples
+            }),
+        );
+        let levels = std::iter::once(copy)
+            .chain((1..=levels).map(|level| self.downsample(1 << level)))
+            .collect();
+        Overviews { levels }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     geo::{Coord, Polygon},
-    Elev, Tile, C,
+    tile::downslope_azimuth,
+    Elev, Tile, C, VOID,
 };
 
 /// A NASADEM elevation sample.
@@ -15,11 +16,21 @@ pub struct Sample<'a> {
 #[allow(clippy::must_use_candidate)]
 impl<'a> Sample<'a> {
     /// Returns sample elevation in meters.
+    ///
+    /// This is the raw value, which is [`VOID`] for samples without
+    /// data.
     #[inline]
     pub fn elevation(&self) -> Elev {
         self.tile.samples.get_linear_unchecked(self.index)
     }
 
+    /// Returns `true` if this sample has no data, i.e., its elevation
+    /// is [`VOID`].
+    #[inline]
+    pub fn is_void(&self) -> bool {
+        self.elevation() == VOID
+    }
+
     /// Returns a polygon of this samples geographic bounding box.
     #[inline]
     pub fn polygon(&self) -> Polygon {
@@ -45,6 +56,36 @@ impl<'a> Sample<'a> {
     pub fn geo(&self) -> Coord<C> {
         self.tile.xy_to_geo(self.xy())
     }
+
+    /// Returns the terrain's slope at this sample, in radians from
+    /// horizontal, or `NaN` if the sample is void.
+    ///
+    /// The gradient is found with Horn's method, a weighted central
+    /// difference over the eight surrounding samples, using the true
+    /// ground spacing of samples at this row's latitude. Neighbors
+    /// beyond the tile's edges are clamped to the nearest edge sample,
+    /// and void neighbors are replaced by this sample.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn slope(&self) -> f32 {
+        self.tile
+            .horn_gradient(self.xy())
+            .map_or(f32::NAN, |(dzdx, dzdy)| dzdx.hypot(dzdy).atan() as f32)
+    }
+
+    /// Returns the direction the terrain faces at this sample, in
+    /// radians clockwise from north in `[0, 2π)`, i.e. the compass
+    /// direction of steepest descent.
+    ///
+    /// The gradient is found as for [`Sample::slope`]. Returns `NaN`
+    /// if the sample is void or the terrain around it is perfectly
+    /// flat, where no direction is downhill.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn aspect(&self) -> f32 {
+        self.tile
+            .horn_gradient(self.xy())
+            .and_then(downslope_azimuth)
+            .map_or(f32::NAN, |azimuth| azimuth as f32)
+    }
 }
 
 impl<'a> std::cmp::PartialEq for Sample<'a> {
@@ -0,0 +1,70 @@
+//! Void-aware smoothing.
+
+use crate::{tile::box_sum, Elev, Tile, VOID};
+
+/// How [`Tile::smooth`] treats void samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoidPolicy {
+    /// Voids are excluded from every neighborhood mean and remain
+    /// void in the output.
+    Preserve,
+    /// Voids are excluded from every neighborhood mean, and a void
+    /// becomes valid if at least half of the samples in its
+    /// neighborhood are valid. Small voids are filled while large
+    /// void regions stay void.
+    InterpolateAcross,
+}
+
+impl Tile {
+    /// Returns a new in-memory tile where each sample is the mean of
+    /// the valid samples within `radius_cells` rows and columns of it
+    /// (a square box filter, clipped at the tile's edges).
+    ///
+    /// The mean is renormalized by the number of valid samples in the
+    /// window, so voids neither drag neighbors toward
+    /// [`VOID`](crate::VOID) nor punch holes in the output. Whether
+    /// void samples are filled is controlled by `policy`.
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_precision_loss
+    )]
+    pub fn smooth(&self, radius_cells: usize, policy: VoidPolicy) -> Tile {
+        let (cols, rows) = self.dimensions();
+        let sums = self.integral_image();
+        let counts = self.summed_area(|elev| i64::from(elev != VOID));
+        let mut samples = Vec::with_capacity(cols * rows);
+        for y in 0..rows {
+            for x in 0..cols {
+                let nw = (
+                    x.saturating_sub(radius_cells),
+                    y.saturating_sub(radius_cells),
+                );
+                let se = (
+                    (x + radius_cells).min(cols - 1),
+                    (y + radius_cells).min(rows - 1),
+                );
+                let count = box_sum(&counts, cols, nw, se);
+                let window = ((se.0 - nw.0 + 1) * (se.1 - nw.1 + 1)) as i64;
+                let fill = match policy {
+                    VoidPolicy::Preserve => false,
+                    VoidPolicy::InterpolateAcross => 2 * count >= window,
+                };
+                let keep = self.get_xy_unchecked((x, y)) != VOID || fill;
+                samples.push(if keep && count > 0 {
+                    let sum = box_sum(&sums, cols, nw, se);
+                    (sum as f64 / count as f64).round() as Elev
+                } else {
+                    VOID
+                });
+            }
+        }
+        Tile::from_samples_at(
+            self.sw_corner_center(),
+            self.resolution(),
+            (cols, rows),
+            samples,
+        )
+    }
+}
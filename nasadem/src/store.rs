@@ -1,10 +1,13 @@
-use crate::{util, Elev};
-use memmap2::Mmap;
+use crate::{util, Elev, NasademError, VOID};
+use memmap2::{Mmap, MmapMut};
 
 pub(crate) enum SampleStore {
     Tombstone(usize),
     InMem(Box<[Elev]>),
     MemMap(Mmap),
+    /// A private, copy-on-write mapping. Writes land in anonymous
+    /// pages and never reach the underlying file.
+    MemMapCow(MmapMut),
 }
 
 impl SampleStore {
@@ -24,32 +27,168 @@ impl SampleStore {
                 let bytes = &mut &raw.as_ref()[start..end];
                 util::parse_sample(bytes)
             }
+            Self::MemMapCow(raw) => {
+                let start = index * size_of::<Elev>();
+                util::parse_sample(&raw[start..start + size_of::<Elev>()])
+            }
         }
     }
 
-    /// Returns the lowest elevation sample in this data.
-    pub(crate) fn min(&self) -> Elev {
+    /// Overwrites the sample at `index`, or returns
+    /// [`NasademError::ReadOnly`] for a tombstone or a shared file
+    /// mapping.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub(crate) fn set_linear_unchecked(
+        &mut self,
+        index: usize,
+        elev: Elev,
+    ) -> Result<(), NasademError> {
         match self {
-            Self::Tombstone(_) => 0,
-            Self::InMem(samples) => samples.iter().min().copied().unwrap(),
-            Self::MemMap(raw) => (*raw)
-                .chunks_exact(2)
-                .map(util::parse_sample)
-                .min()
-                .unwrap(),
+            Self::InMem(samples) => samples[index] = elev,
+            Self::MemMapCow(raw) => {
+                let start = index * size_of::<Elev>();
+                raw[start..start + size_of::<Elev>()].copy_from_slice(&elev.to_be_bytes());
+            }
+            Self::Tombstone(_) | Self::MemMap(_) => return Err(NasademError::ReadOnly),
+        }
+        Ok(())
+    }
+
+    /// Returns the approximate number of bytes backing the samples.
+    ///
+    /// Mapped files count their full length even though pages
+    /// aren't resident until touched, and tombstones only count the
+    /// store itself.
+    pub(crate) fn memory_bytes(&self) -> usize {
+        match self {
+            Self::Tombstone(_) => size_of::<Self>(),
+            Self::InMem(samples) => samples.len() * size_of::<Elev>(),
+            Self::MemMap(raw) => raw.len(),
+            Self::MemMapCow(raw) => raw.len(),
         }
     }
 
-    /// Returns the highest elevation sample in this data.
-    pub(crate) fn max(&self) -> Elev {
+    /// Returns all samples, in linear order, if they're held in memory
+    /// as native `Elev`s.
+    pub(crate) fn as_slice(&self) -> Option<&[Elev]> {
         match self {
-            Self::Tombstone(_) => 0,
-            Self::InMem(samples) => samples.iter().max().copied().unwrap(),
+            Self::InMem(samples) => Some(samples),
+            Self::Tombstone(_) | Self::MemMap(_) | Self::MemMapCow(_) => None,
+        }
+    }
+
+    /// Returns all samples, in linear order, as an owned vector.
+    ///
+    /// In-memory samples are moved without copying.
+    pub(crate) fn into_vec(self) -> Vec<Elev> {
+        match self {
+            Self::Tombstone(size) => vec![0; size],
+            Self::InMem(samples) => samples.into_vec(),
+            Self::MemMap(raw) => raw.chunks_exact(2).map(util::parse_sample).collect(),
+            Self::MemMapCow(raw) => raw.chunks_exact(2).map(util::parse_sample).collect(),
+        }
+    }
+
+    /// Returns the sample at each of `indices`, matching on the store
+    /// once rather than per sample.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index is out of bounds.
+    pub(crate) fn get_many_linear<I: Gather>(
+        &self,
+        indices: impl IntoIterator<Item = I>,
+    ) -> Vec<I::Out> {
+        let sample = |raw: &[u8], index: usize| {
+            let start = index * size_of::<Elev>();
+            util::parse_sample(&raw[start..start + size_of::<Elev>()])
+        };
+        match self {
+            Self::Tombstone(size) => indices
+                .into_iter()
+                .map(|idx| {
+                    idx.gather(|index| {
+                        assert!(
+                            index < *size,
+                            "index {index} exceeds tombstone's virtual size {size}"
+                        );
+                        0
+                    })
+                })
+                .collect(),
+            Self::InMem(samples) => indices
+                .into_iter()
+                .map(|idx| idx.gather(|index| samples[index]))
+                .collect(),
+            Self::MemMap(raw) => indices
+                .into_iter()
+                .map(|idx| idx.gather(|index| sample(raw, index)))
+                .collect(),
+            Self::MemMapCow(raw) => indices
+                .into_iter()
+                .map(|idx| idx.gather(|index| sample(raw, index)))
+                .collect(),
+        }
+    }
+
+    /// Folds every sample, in linear order, into an accumulator.
+    pub(crate) fn fold<B, F>(&self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Elev) -> B,
+    {
+        match self {
+            Self::Tombstone(size) => (0..*size).fold(init, |acc, _| f(acc, 0)),
+            Self::InMem(samples) => samples.iter().fold(init, |acc, &elev| f(acc, elev)),
             Self::MemMap(raw) => (*raw)
                 .chunks_exact(2)
-                .map(util::parse_sample)
-                .max()
-                .unwrap(),
+                .fold(init, |acc, bytes| f(acc, util::parse_sample(bytes))),
+            Self::MemMapCow(raw) => (*raw)
+                .chunks_exact(2)
+                .fold(init, |acc, bytes| f(acc, util::parse_sample(bytes))),
         }
     }
+
+    /// Returns the lowest and highest non-void elevation samples in
+    /// this data, in a single pass, or `(VOID, VOID)` if every sample
+    /// is void.
+    pub(crate) fn range(&self) -> (Elev, Elev) {
+        if let Self::Tombstone(_) = self {
+            return (0, 0);
+        }
+        self.fold(None, |acc, elev| match (acc, elev) {
+            (acc, VOID) => acc,
+            (None, elev) => Some((elev, elev)),
+            (Some((lo, hi)), elev) => Some((lo.min(elev), hi.max(elev))),
+        })
+        .unwrap_or((VOID, VOID))
+    }
+}
+
+/// A linear index, or possibly none, for
+/// [`SampleStore::get_many_linear`].
+pub(crate) trait Gather {
+    /// The sample, or possibly none.
+    type Out;
+
+    /// Returns the sample `read` gives for this index.
+    fn gather(self, read: impl Fn(usize) -> Elev) -> Self::Out;
+}
+
+impl Gather for usize {
+    type Out = Elev;
+
+    fn gather(self, read: impl Fn(usize) -> Elev) -> Elev {
+        read(self)
+    }
+}
+
+impl Gather for Option<usize> {
+    type Out = Option<Elev>;
+
+    fn gather(self, read: impl Fn(usize) -> Elev) -> Option<Elev> {
+        self.map(read)
+    }
 }
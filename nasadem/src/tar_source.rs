@@ -0,0 +1,101 @@
+//! Serving tiles out of a tar archive of `.hgt` files.
+
+use crate::{geo::Coord, util, Elev, NasademError, Tile};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+/// A tar archive of `.hgt` files, loaded one tile at a time.
+///
+/// Tar has no table of contents, so [`TarTileSource::open`] walks
+/// every member header once and records where each tile's samples
+/// start. Loading a tile then seeks straight to its data instead of
+/// extracting it or rescanning the archive.
+pub struct TarTileSource {
+    /// Path to the archive.
+    path: PathBuf,
+
+    /// Offset and length in bytes of each tile's data in the archive,
+    /// keyed by SW corner.
+    index: HashMap<Coord<i16>, (u64, u64)>,
+}
+
+impl TarTileSource {
+    /// Opens and indexes the tar archive at `path`.
+    ///
+    /// Members are indexed by the SW corner parsed from their file
+    /// name, ignoring any leading directories. Members whose names
+    /// are not valid `.hgt` names are skipped. The archive must not
+    /// be compressed.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, NasademError> {
+        let mut archive = tar::Archive::new(BufReader::new(File::open(&path)?));
+        let mut index = HashMap::new();
+        for entry in archive.entries()? {
+            let entry = entry?;
+            let member = entry.path()?;
+            let is_hgt = member
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("hgt"));
+            if !entry.header().entry_type().is_file() || !is_hgt {
+                continue;
+            }
+            if let Ok(sw_corner) = util::parse_sw_corner(&member) {
+                index.insert(sw_corner, (entry.raw_file_position(), entry.size()));
+            }
+        }
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            index,
+        })
+    }
+
+    /// Returns the number of tiles in the archive.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Returns `true` if the archive contains no tiles.
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Returns `true` if the archive contains the tile whose SW
+    /// corner is `sw_corner`.
+    pub fn contains(&self, sw_corner: Coord<i16>) -> bool {
+        self.index.contains_key(&sw_corner)
+    }
+
+    /// Reads the tile whose SW corner is `sw_corner` into memory.
+    ///
+    /// Returns [`NasademError::Io`] with [`io::ErrorKind::NotFound`]
+    /// if the archive has no such tile, the same as [`Tile::load`]
+    /// for a missing file, and [`NasademError::HgtLen`] if the
+    /// member's length does not match a supported tile (see
+    /// [`extract_resolution`](crate::extract_resolution)).
+    pub fn tile(&self, sw_corner: Coord<i16>) -> Result<Tile, NasademError> {
+        let &(offset, len) = self.index.get(&sw_corner).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "{} is not in {}",
+                    util::filename_for(sw_corner),
+                    self.path.display()
+                ),
+            )
+        })?;
+        let (resolution, dimensions) = util::resolution_for_len(len).ok_or_else(|| {
+            NasademError::HgtLen(len, self.path.join(util::filename_for(sw_corner)))
+        })?;
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut raw = vec![0; dimensions.0 * dimensions.1 * size_of::<Elev>()];
+        file.read_exact(&mut raw)?;
+        let samples = raw.chunks_exact(2).map(util::parse_sample).collect();
+        Ok(Tile::from_samples(
+            sw_corner, resolution, dimensions, samples,
+        ))
+    }
+}
@@ -1,2 +1,5 @@
+#![allow(clippy::cast_precision_loss, clippy::float_cmp)]
+
 mod one_arcsecond;
+mod synthetic;
 mod three_arcsecond;
@@ -1,4 +1,4 @@
-use crate::{geo::Coord, util, Tile};
+use crate::{geo::Coord, util, NasademError, Tile, TileQueryError, TileSet};
 use std::{fs::File, io::BufReader, path::PathBuf};
 
 fn one_arcsecond_dir() -> PathBuf {
@@ -122,3 +122,177 @@ fn test_tile_index_conversions() {
         }
     }
 }
+
+#[test]
+fn test_is_grid_compatible() {
+    let one_arcsecond = {
+        let mut path = one_arcsecond_dir();
+        path.push("N44W072.hgt");
+        Tile::memmap(path).unwrap()
+    };
+    let three_arcsecond = {
+        let mut path = one_arcsecond_dir();
+        path.pop();
+        path.push("3arcsecond");
+        path.push("N44W072.hgt");
+        Tile::memmap(path).unwrap()
+    };
+    let east_neighbor = Tile::tombstone(Coord { x: -71, y: 44 }, 1);
+    let far_away = {
+        let mut path = one_arcsecond_dir();
+        path.push("N38W105.hgt");
+        Tile::memmap(path).unwrap()
+    };
+
+    assert!(one_arcsecond.is_grid_compatible(&one_arcsecond));
+    assert!(one_arcsecond.is_grid_compatible(&east_neighbor));
+    assert!(east_neighbor.is_grid_compatible(&one_arcsecond));
+    assert!(one_arcsecond.is_grid_compatible(&far_away));
+    assert!(!one_arcsecond.is_grid_compatible(&three_arcsecond));
+    assert!(!three_arcsecond.is_grid_compatible(&east_neighbor));
+}
+
+#[test]
+fn test_invalid_len_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("N44W072.hgt");
+    let len = 3601 * 3601 * 2 + 1;
+    File::create(&path).unwrap().set_len(len).unwrap();
+    for res in [Tile::load(&path), Tile::memmap(&path)] {
+        match res {
+            Err(NasademError::HgtLen(err_len, err_path)) => {
+                assert_eq!(err_len, len);
+                assert_eq!(err_path, path);
+            }
+            other => panic!("expected HgtLen error, got {other:?}"),
+        }
+    }
+
+    assert_eq!(util::square_dim_for_len(3601 * 3601 * 2), Some(3601));
+    assert_eq!(util::square_dim_for_len(3601 * 3601 * 2 + 1), None);
+    assert_eq!(util::square_dim_for_len(3601 * 3600 * 2), None);
+    assert_eq!(util::square_dim_for_len(0), None);
+
+    let len_for = |dim: u64| dim * dim * 2;
+    assert_eq!(
+        util::resolution_for_len(len_for(3601)),
+        Some((1, (3601, 3601)))
+    );
+    assert_eq!(
+        util::resolution_for_len(len_for(1801)),
+        Some((2, (1801, 1801)))
+    );
+    assert_eq!(
+        util::resolution_for_len(len_for(1201)),
+        Some((3, (1201, 1201)))
+    );
+    assert_eq!(
+        util::resolution_for_len(len_for(121)),
+        Some((30, (121, 121)))
+    );
+    // Half-arcsecond grids can't be represented.
+    assert_eq!(util::resolution_for_len(len_for(7201)), None);
+    // 3599 intervals don't divide a degree.
+    assert_eq!(util::resolution_for_len(len_for(3600)), None);
+    // 3600 arcseconds per sample overflows.
+    assert_eq!(util::resolution_for_len(len_for(2)), None);
+    assert_eq!(util::resolution_for_len(len_for(1)), None);
+}
+
+#[test]
+fn test_load_two_arcsecond() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("N44W072.hgt");
+    let samples: Vec<u8> = (0..1801 * 1801_i32)
+        .flat_map(|idx| i16::try_from(idx % 1801).unwrap().to_be_bytes())
+        .collect();
+    std::fs::write(&path, samples).unwrap();
+    assert_eq!(util::extract_resolution(&path).unwrap(), (2, (1801, 1801)));
+    for tile in [Tile::load(&path).unwrap(), Tile::memmap(&path).unwrap()] {
+        assert_eq!(tile.resolution(), 2);
+        assert_eq!(tile.dimensions(), (1801, 1801));
+        // Elevation is the column, so it grows one meter every 2
+        // arcseconds east.
+        assert_eq!(tile.get(Coord { x: -72.0, y: 44.5 }), Some(0));
+        assert_eq!(tile.get(Coord { x: -71.5, y: 44.5 }), Some(900));
+        assert_eq!(tile.get(Coord { x: -71.0, y: 44.5 }), Some(1800));
+    }
+}
+
+#[test]
+fn test_tombstone_resolutions() {
+    for (resolution, dim) in [(1, 3601), (2, 1801), (3, 1201), (30, 121)] {
+        let tombstone = Tile::tombstone(Coord { x: -72, y: 44 }, resolution);
+        assert_eq!(tombstone.resolution(), resolution);
+        assert_eq!(tombstone.dimensions(), (dim, dim));
+        assert_eq!(tombstone.get(Coord { x: -71.5, y: 44.5 }), Some(0));
+    }
+}
+
+#[test]
+#[should_panic(expected = "does not evenly divide")]
+fn test_tombstone_rejects_uneven_resolution() {
+    let _ = Tile::tombstone(Coord { x: -72, y: 44 }, 7);
+}
+
+#[test]
+fn test_memory_bytes() {
+    let mut path = one_arcsecond_dir();
+    path.push("N44W072.hgt");
+    let tile = Tile::load(path).unwrap();
+    // 3601 × 3601 samples at two bytes each, ~25.9 MB.
+    assert_eq!(tile.memory_bytes(), 25_934_402);
+    let tombstone = Tile::tombstone(Coord { x: -72, y: 44 }, 1);
+    assert!(tombstone.memory_bytes() < 64);
+}
+
+#[test]
+fn test_ground_resolution_m() {
+    let tile = Tile::memmap(one_arcsecond_dir().join("N44W072.hgt")).unwrap();
+    let (dx_m, dy_m) = tile.ground_resolution_m();
+    // Center latitude is 44.5°N.
+    assert!((dx_m - 22.0).abs() < 0.1, "{dx_m}");
+    assert!((dy_m - 30.9).abs() < 0.1, "{dy_m}");
+}
+
+#[test]
+fn test_tile_set_open_dir() {
+    let dir = one_arcsecond_dir();
+    let set = TileSet::open_dir(&dir).unwrap().with_cache_capacity(1);
+    // README.md isn't a tile.
+    assert_eq!(set.len(), 2);
+    assert!(set.cached().is_empty());
+    let bounds = set.bounds().unwrap();
+    assert!(bounds.min().x < -105.0 && bounds.max().x > -71.0);
+
+    let n44w072 = Tile::memmap(dir.join("N44W072.hgt")).unwrap();
+    let n38w105 = Tile::memmap(dir.join("N38W105.hgt")).unwrap();
+    let (nh, co) = (Coord { x: -71.3, y: 44.27 }, Coord { x: -104.5, y: 38.5 });
+    assert_eq!(set.get(nh), n44w072.get(nh));
+    assert_eq!(set.cached(), vec![Coord { x: -72, y: 44 }]);
+    assert_eq!(set.try_get(co).unwrap(), n38w105.get(co).unwrap());
+    // Capacity 1 evicts the first tile.
+    assert_eq!(set.cached(), vec![Coord { x: -105, y: 38 }]);
+    assert_eq!(set.get(nh), n44w072.get(nh));
+    assert_eq!(set.cached(), vec![Coord { x: -72, y: 44 }]);
+    // Edge samples cover just past the tile's degree.
+    assert!(set.get(Coord { x: -71.0, y: 45.0 }).is_some());
+
+    let missing = Coord { x: -71.5, y: 45.5 };
+    assert_eq!(set.get(missing), None);
+    match set.try_get(missing) {
+        Err(NasademError::Io(err)) => {
+            assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+            assert!(err.to_string().contains("N45W072.hgt"), "{err}");
+        }
+        other => panic!("expected a missing file, got {other:?}"),
+    }
+    assert!(matches!(
+        set.try_get(Coord { x: -71.5, y: 95.0 }),
+        Err(NasademError::Query(TileQueryError::OffTile { .. }))
+    ));
+
+    let set = TileSet::open_dir(&dir).unwrap().with_gap_elevation(0);
+    assert_eq!(set.try_get(missing).unwrap(), 0);
+    assert!(TileSet::open_dir(dir.join("nonexistent")).is_err());
+}
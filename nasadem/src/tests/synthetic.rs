@@ -0,0 +1,1398 @@
+//! Tests against small, procedurally generated tiles.
+
+use crate::{geo::Coord, Elev, Tile};
+
+/// Side length, in samples, of the synthetic tiles.
+const DIM: usize = 61;
+
+/// Returns a 3-arcsecond tile with a paraboloid hill rising 500 m
+/// above a flat 100 m plain.
+///
+/// The summit is the center sample, `(30, 30)`, and the hill's base
+/// is a circle of radius 20 samples around it.
+pub(crate) fn dome() -> Tile {
+    const BASE: f64 = 100.0;
+    const HEIGHT: f64 = 500.0;
+    const RADIUS: f64 = 20.0;
+    let center = (DIM / 2) as f64;
+    let samples = (0..DIM * DIM)
+        .map(|idx| {
+            let x = (idx % DIM) as f64 - center;
+            let y = (idx / DIM) as f64 - center;
+            let r = x.hypot(y) / RADIUS;
+            #[allow(clippy::cast_possible_truncation)]
+            let elev = (BASE + HEIGHT * (1.0 - r * r).max(0.0)).round() as Elev;
+            elev
+        })
+        .collect();
+    Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples)
+}
+
+/// Returns a 3-arcsecond tile with a square pyramid rising 20 m per
+/// sample toward its summit, the center sample, `(30, 30)`, from a flat
+/// 100 m plain 20 samples out.
+///
+/// Its four faces each have a constant slope, the north and south
+/// faces steeper than the east and west, whose samples are closer
+/// together.
+#[cfg(feature = "nalgebra")]
+pub(crate) fn pyramid() -> Tile {
+    let center = DIM / 2;
+    let samples = (0..DIM * DIM)
+        .map(|idx| {
+            let r = (idx % DIM)
+                .abs_diff(center)
+                .max((idx / DIM).abs_diff(center));
+            Elev::try_from(100 + 20 * 20_usize.saturating_sub(r)).unwrap()
+        })
+        .collect();
+    Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples)
+}
+
+/// Returns a 3-arcsecond tile rising 10 m per sample eastward, from
+/// 0 m along its west edge to 600 m along its east edge.
+pub(crate) fn east_ramp() -> Tile {
+    let samples = (0..DIM * DIM)
+        .map(|idx| Elev::try_from(idx % DIM * 10).unwrap())
+        .collect();
+    Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples)
+}
+
+/// Returns a 3-arcsecond tile that is a flat 100 m everywhere.
+pub(crate) fn flat() -> Tile {
+    Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), vec![100; DIM * DIM])
+}
+
+#[test]
+fn test_dome() {
+    let tile = dome();
+    assert_eq!(tile.dimensions(), (DIM, DIM));
+    assert_eq!(tile.get((30, 30)), Some(600));
+    assert_eq!(tile.get((0, 0)), Some(100));
+    assert_eq!(tile.max_elevation(), 600);
+    assert_eq!(tile.min_elevation(), 100);
+}
+
+#[cfg(feature = "nalgebra")]
+#[test]
+fn test_tpi() {
+    let tile = dome();
+    let tpi = tile.tpi(5);
+    assert_eq!(tpi.shape(), (DIM, DIM));
+    // Summit stands well above its neighbors.
+    assert!(tpi[(30, 30)] > 20.0);
+    // The foot of the hill sits below the slope rising beside it.
+    assert!(tpi[(30, 51)] < 0.0);
+    assert!(tpi[(51, 30)] < 0.0);
+    // Flat plain far from the hill.
+    assert_eq!(tpi[(0, 0)], 0.0);
+}
+
+#[cfg(feature = "nalgebra")]
+#[test]
+fn test_tpi_excludes_voids() {
+    let mut samples = vec![10; 9];
+    samples[0] = crate::VOID;
+    samples[8] = crate::VOID;
+    let tile = Tile::from_samples(Coord { x: 0, y: 0 }, 3, (3, 3), samples);
+    let tpi = tile.tpi(1);
+    assert!(tpi[(0, 0)].is_nan());
+    assert_eq!(tpi[(1, 1)], 0.0);
+    assert_eq!(tpi[(0, 1)], 0.0);
+}
+
+#[test]
+fn test_integral_image() {
+    let tile = dome();
+    let table = tile.integral_image();
+    assert_eq!(table.len(), (DIM + 1) * (DIM + 1));
+    let total = tile.fold(0, |sum, elev| sum + i64::from(elev));
+    assert_eq!(table.last(), Some(&total));
+    assert_eq!(crate::tile::box_sum(&table, DIM, (30, 30), (30, 30)), 600);
+}
+
+#[test]
+fn test_downsample_matches_naive_block_average() {
+    let (mut samples, ..) = dome().into_raw();
+    samples[DIM * 29 + 31] = crate::VOID;
+    let tile = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples);
+
+    for factor in [1, 2, 3, 4, 5] {
+        let small = tile.downsample(factor);
+        let out_dim = (DIM - 1) / factor + 1;
+        assert_eq!(small.dimensions(), (out_dim, out_dim));
+        assert_eq!(usize::from(small.resolution()), 3 * factor);
+        let block = |i: usize| {
+            let center = i * factor;
+            center.saturating_sub(factor / 2)..=(center + factor / 2).min(DIM - 1)
+        };
+        for y in 0..out_dim {
+            for x in 0..out_dim {
+                let (mut sum, mut count) = (0.0, 0.0);
+                for by in block(y) {
+                    for bx in block(x) {
+                        let elev = tile.get((bx, by)).unwrap();
+                        if elev != crate::VOID {
+                            sum += f64::from(elev);
+                            count += 1.0;
+                        }
+                    }
+                }
+                #[allow(clippy::cast_possible_truncation)]
+                let expected = if count == 0.0 {
+                    crate::VOID
+                } else {
+                    (sum / count).round() as Elev
+                };
+                assert_eq!(
+                    small.get((x, y)),
+                    Some(expected),
+                    "factor {factor} ({x}, {y})"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "does not evenly divide")]
+fn test_downsample_rejects_uneven_factor() {
+    let _ = dome().downsample(7);
+}
+
+#[test]
+fn test_rmse_against() {
+    let tile = dome();
+    let stats = tile.error_stats(&tile).unwrap();
+    assert_eq!(stats.rmse, 0.0);
+    assert_eq!(stats.max_abs_error, 0.0);
+    assert_eq!(stats.count, DIM * DIM);
+
+    // Shift the dome one sample east, voiding the vacated column.
+    let (samples, ..) = dome().into_raw();
+    let shifted = (0..DIM * DIM)
+        .map(|idx| {
+            if idx % DIM == 0 {
+                crate::VOID
+            } else {
+                samples[idx - 1]
+            }
+        })
+        .collect();
+    let shifted = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), shifted);
+    let stats = shifted.error_stats(&tile).unwrap();
+    assert!(stats.rmse > 0.0);
+    assert!(stats.max_abs_error >= stats.rmse);
+    assert_eq!(stats.count, DIM * (DIM - 1));
+    assert_eq!(shifted.rmse_against(&tile).unwrap(), stats.rmse);
+
+    let coarse = tile.downsample(2);
+    assert!(matches!(
+        coarse.rmse_against(&tile),
+        Err(crate::NasademError::IncompatibleGrids)
+    ));
+    let elsewhere = Tile::from_samples(Coord { x: -71, y: 44 }, 3, (DIM, DIM), samples);
+    assert!(elsewhere.rmse_against(&tile).is_err());
+}
+
+#[test]
+fn test_count_below_above() {
+    let samples = vec![crate::VOID, 1, 2, 3, 4, 5, 6, 7, 8];
+    let tile = Tile::from_samples(Coord { x: 0, y: 0 }, 3, (3, 3), samples);
+    assert_eq!(tile.count_below(4), 3);
+    assert_eq!(tile.count_above(4), 4);
+    assert_eq!(tile.count_below(Elev::MAX), 8);
+    assert_eq!(tile.count_above(Elev::MIN), 8);
+
+    // Everything outside the dome's base circle is 100 m plain.
+    let tile = dome();
+    let plain = tile.count_below(101);
+    assert_eq!(plain + tile.count_above(100), DIM * DIM);
+    assert_eq!(tile.count_above(599), 1);
+}
+
+#[test]
+fn test_smooth_void_policy() {
+    use crate::{VoidPolicy, VOID};
+    // A single void in a flat 10 m field, and a 5x5 block of voids.
+    let mut samples = vec![10; DIM * DIM];
+    samples[DIM * 5 + 5] = VOID;
+    for y in 30..35 {
+        for x in 30..35 {
+            samples[DIM * y + x] = VOID;
+        }
+    }
+    let tile = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples);
+
+    let preserved = tile.smooth(1, VoidPolicy::Preserve);
+    assert_eq!(preserved.get((5, 5)), Some(VOID));
+    // Neighbors of voids are not dragged toward VOID.
+    assert_eq!(preserved.get((4, 5)), Some(10));
+    assert_eq!(preserved.get((29, 29)), Some(10));
+
+    let filled = tile.smooth(1, VoidPolicy::InterpolateAcross);
+    assert_eq!(filled.get((5, 5)), Some(10));
+    // The block's edge has enough valid neighbors to fill, but its
+    // interior does not.
+    assert_eq!(filled.get((30, 30)), Some(10));
+    assert_eq!(filled.get((32, 32)), Some(VOID));
+
+    // Smoothing keeps the grid.
+    assert!(filled.is_grid_compatible(&tile));
+    assert_eq!(filled.dimensions(), tile.dimensions());
+    assert_eq!(
+        dome()
+            .smooth(0, VoidPolicy::Preserve)
+            .rmse_against(&dome())
+            .unwrap(),
+        0.0
+    );
+}
+
+#[test]
+fn test_stride() {
+    // Four columns by three rows, numbered in row-major order.
+    let (cols, rows) = (4, 3);
+    let samples: Vec<Elev> = (0..12).collect();
+    let tile = Tile::from_samples(Coord { x: 0, y: 0 }, 3, (cols, rows), samples.clone());
+    assert_eq!(tile.stride(), cols);
+    for y in 0..rows {
+        for x in 0..cols {
+            let linear = y * tile.stride() + x;
+            assert_eq!(Some(samples[linear]), tile.get((x, y)));
+            assert_eq!(tile.get(linear), tile.get((x, y)));
+        }
+    }
+    assert_eq!(dome().stride(), DIM);
+}
+
+#[cfg(feature = "nalgebra")]
+#[test]
+fn test_hillshade_latitude_correction() {
+    // Ramps rising 10 m per sample to the east and to the north, at
+    // 60°N where east-west spacing is half the north-south spacing.
+    let ramp = |east: bool| {
+        let samples = (0..DIM * DIM)
+            .map(|idx| {
+                let (x, y) = (idx % DIM, idx / DIM);
+                let steps = if east { x } else { DIM - 1 - y };
+                #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+                let elev = 10 * steps as Elev;
+                elev
+            })
+            .collect();
+        Tile::from_samples(Coord { x: 10, y: 60 }, 3, (DIM, DIM), samples)
+    };
+    let (east_ramp, north_ramp) = (ramp(true), ramp(false));
+    let overhead = 90_f32.to_radians();
+    let center = (DIM / 2, DIM / 2);
+
+    // Overhead reflectance is 1 / sqrt(1 + slope²), so brighter means
+    // flatter.
+    let corrected = east_ramp.hillshade(overhead, 0.0)[center];
+    let in_samples = east_ramp.shade_with_spacing(overhead, 0.0, |_| (1.0, 1.0))[center];
+    assert!((corrected - in_samples).abs() > 0.1);
+    assert!(corrected > in_samples);
+
+    // Uncorrected, the two ramps look equally steep. Corrected, the
+    // east ramp is steeper since its samples are closer together.
+    let north_in_samples = north_ramp.shade_with_spacing(overhead, 0.0, |_| (1.0, 1.0))[center];
+    assert!((north_in_samples - in_samples).abs() < 1e-6);
+    assert!(north_ramp.hillshade(overhead, 0.0)[center] > corrected);
+
+    // Flat ground is lit as expected everywhere.
+    let flat = Tile::from_samples(Coord { x: 10, y: 60 }, 3, (3, 3), vec![50; 9]);
+    let shaded = flat.hillshade(30_f32.to_radians(), 1.0);
+    assert!(shaded.iter().all(|&v| (v - 0.5).abs() < 1e-6));
+}
+
+#[test]
+fn test_try_get() {
+    use crate::{TileIndex, TileQueryError, VOID};
+    let (mut samples, ..) = dome().into_raw();
+    samples[0] = VOID;
+    let tile = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples);
+
+    assert_eq!(tile.try_get((30, 30)), Ok(600));
+    assert_eq!(tile.try_get((0, 0)), Err(TileQueryError::Void));
+    assert_eq!(tile.try_get(0), Err(TileQueryError::Void));
+
+    let off_tile = Coord { x: -71.5, y: 44.1 };
+    assert_eq!(
+        tile.try_get(off_tile),
+        Err(TileQueryError::OffTile { coord: off_tile })
+    );
+    assert_eq!(
+        tile.try_get((DIM, 0)),
+        Err(TileQueryError::OutOfBounds {
+            index: TileIndex::XY((DIM, 0)),
+            len: DIM * DIM,
+        })
+    );
+    assert_eq!(
+        tile.try_get(DIM * DIM),
+        Err(TileQueryError::OutOfBounds {
+            index: TileIndex::Linear(DIM * DIM),
+            len: DIM * DIM,
+        })
+    );
+    assert_eq!(
+        TileQueryError::OffTile { coord: off_tile }.to_string(),
+        "(-71.5, 44.1) is not on this tile"
+    );
+}
+
+#[test]
+fn test_nearest_sample() {
+    let tile = dome();
+    let summit = tile.sample((30, 30)).unwrap().geo();
+    // 0.4" east and 0.3" north of the summit's center.
+    let query = Coord {
+        x: summit.x + 0.4 / 3600.0,
+        y: summit.y + 0.3 / 3600.0,
+    };
+    let (sample, dist) = tile.nearest_sample(query).unwrap();
+    assert_eq!(sample.xy(), (30, 30));
+    assert_eq!(sample.elevation(), 600);
+    // Longitude spacing shrinks by cos(44°), so this is ~12.8 m
+    // rather than the ~15.4 m a flat arcsecond grid would give.
+    assert!((12.5..13.2).contains(&dist), "{dist}");
+
+    let (_, dist) = tile.nearest_sample(summit).unwrap();
+    assert!(dist < 1e-6, "{dist}");
+
+    assert!(tile.nearest_sample(Coord { x: -71.5, y: 44.1 }).is_none());
+}
+
+#[test]
+fn test_set() {
+    use crate::{NasademError, TileIndex, TileQueryError};
+    let mut tile = dome();
+    assert_eq!((tile.min_elevation(), tile.max_elevation()), (100, 600));
+
+    tile.set((3, 4), 750).unwrap();
+    assert_eq!(tile.get((3, 4)), Some(750));
+    assert_eq!((tile.min_elevation(), tile.max_elevation()), (100, 750));
+
+    tile.set(0, -20).unwrap();
+    assert_eq!(tile.get(0), Some(-20));
+    assert_eq!((tile.min_elevation(), tile.max_elevation()), (-20, 750));
+
+    assert!(matches!(
+        tile.set((DIM, 0), 0),
+        Err(NasademError::Query(TileQueryError::OutOfBounds {
+            index: TileIndex::XY((DIM, 0)),
+            ..
+        }))
+    ));
+    assert!(matches!(
+        Tile::tombstone(Coord { x: -72, y: 44 }, 3).set(0, 1),
+        Err(NasademError::ReadOnly)
+    ));
+}
+
+#[test]
+fn test_voids() {
+    use crate::VOID;
+    let mut tile = dome();
+    tile.set((30, 30), VOID).unwrap();
+    tile.set((0, 0), VOID).unwrap();
+    // Voids are skipped, so the summit's neighbors are now highest.
+    assert_eq!(tile.min_elevation(), 100);
+    assert!(tile.max_elevation() < 600);
+
+    // The raw accessors still return the sentinel.
+    assert_eq!(tile.get((30, 30)), Some(VOID));
+    assert_eq!(tile.get_checked((30, 30)), None);
+    assert_eq!(tile.get_checked((31, 30)), tile.get((31, 30)));
+    assert_eq!(tile.get_checked((DIM, 0)), None);
+    assert!(tile.sample((0, 0)).unwrap().is_void());
+    assert_eq!(tile.sample((0, 0)).unwrap().elevation(), VOID);
+    assert!(!tile.sample((1, 0)).unwrap().is_void());
+
+    let all_void = Tile::from_samples(Coord { x: 0, y: 0 }, 3, (2, 2), vec![VOID; 4]);
+    assert_eq!(all_void.min_elevation(), VOID);
+    assert_eq!(all_void.max_elevation(), VOID);
+}
+
+#[test]
+fn test_valid_centroid() {
+    use crate::VOID;
+    let tile = dome();
+    let center = tile.sample((30, 30)).unwrap().geo();
+    let centroid = tile.valid_centroid(false).unwrap();
+    assert!((centroid.x - center.x).abs() < 1e-9);
+    assert!((centroid.y - center.y).abs() < 1e-9);
+    // Cells shrink toward the pole, so weighting by area pulls the
+    // centroid south.
+    let weighted = tile.valid_centroid(true).unwrap();
+    assert!((weighted.x - center.x).abs() < 1e-9);
+    assert!(weighted.y < centroid.y);
+
+    // Voiding the western half shifts the centroid east, to the
+    // middle of the remaining columns 30..61.
+    let (samples, cols, rows, res, sw_corner) = dome().into_raw();
+    let samples = samples
+        .into_iter()
+        .enumerate()
+        .map(|(i, elev)| if i % cols < 30 { VOID } else { elev })
+        .collect();
+    let tile = Tile::from_samples(sw_corner, res, (cols, rows), samples);
+    let centroid = tile.valid_centroid(false).unwrap();
+    let expected = tile.sample((45, 30)).unwrap().geo();
+    assert!(centroid.x > center.x);
+    assert!((centroid.x - expected.x).abs() < 1e-9);
+    assert!((centroid.y - center.y).abs() < 1e-9);
+
+    let all_void = Tile::from_samples(Coord { x: 0, y: 0 }, 3, (2, 2), vec![VOID; 4]);
+    assert_eq!(all_void.valid_centroid(false), None);
+}
+
+#[test]
+fn test_build_overviews() {
+    let tile = dome();
+    let overviews = tile.build_overviews(2);
+    assert_eq!(overviews.len(), 3);
+
+    let full = overviews.level(0).unwrap();
+    assert_eq!(full.dimensions(), tile.dimensions());
+    assert_eq!(full.resolution(), tile.resolution());
+    assert!(tile
+        .iter()
+        .all(|s| full.get(s.index()) == Some(s.elevation())));
+
+    let coarsest = overviews.level(2).unwrap();
+    assert_eq!(coarsest.dimensions(), (16, 16));
+    assert_eq!(coarsest.resolution(), 12);
+    assert!(overviews.level(3).is_none());
+
+    let summit = tile.sample((30, 30)).unwrap().geo();
+    assert_eq!(overviews.sample(summit, 1.0), Some(600));
+    assert_eq!(
+        overviews.sample(summit, 2.5),
+        overviews.level(1).unwrap().get(summit)
+    );
+    assert_eq!(overviews.sample(summit, 100.0), coarsest.get(summit));
+    assert!(overviews.sample(summit, 100.0).unwrap() < 600);
+}
+
+#[cfg(feature = "nalgebra")]
+#[test]
+fn test_dog() {
+    // Ripples running north-south with a 16-cell wavelength.
+    const WAVELENGTH: f64 = 16.0;
+    let samples = (0..DIM * DIM)
+        .map(|idx| {
+            let x = (idx % DIM) as f64;
+            #[allow(clippy::cast_possible_truncation)]
+            let elev =
+                (1000.0 + 100.0 * (std::f64::consts::TAU * x / WAVELENGTH).sin()).round() as Elev;
+            elev
+        })
+        .collect();
+    let ripples = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples);
+
+    // The fine Gaussian passes the ripples while the coarse one
+    // flattens them, so the DoG recovers most of their amplitude.
+    let dog = ripples.dog(1.0, 8.0);
+    let interior = dog.view((24, 24), (13, 13));
+    let peak = interior.iter().fold(0.0_f32, |max, v| max.max(v.abs()));
+    assert!(peak > 80.0, "{peak}");
+
+    // Both Gaussians flatten ripples much finer than either sigma.
+    let dog = ripples.dog(8.0, 16.0);
+    let interior = dog.view((24, 24), (13, 13));
+    let peak = interior.iter().fold(0.0_f32, |max, v| max.max(v.abs()));
+    assert!(peak < 5.0, "{peak}");
+
+    let flat = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), vec![250; DIM * DIM]);
+    assert!(flat.dog(1.0, 4.0).iter().all(|v| v.abs() < 1e-3));
+}
+
+#[cfg(feature = "nalgebra")]
+#[test]
+fn test_to_matrix() {
+    use crate::VOID;
+    let mut tile = dome();
+    let matrix = tile.to_matrix::<f64>();
+    assert_eq!(matrix.shape(), (DIM, DIM));
+    assert_eq!(matrix[(0, 0)], f64::from(tile.get((0, 0)).unwrap()));
+    assert_eq!(matrix[(30, 30)], 600.0);
+
+    tile.set((0, 0), VOID).unwrap();
+    assert_eq!(tile.to_matrix::<i32>()[(0, 0)], 0);
+    let matrix = tile.to_matrix_with_void(f64::NAN);
+    assert!(matrix[(0, 0)].is_nan());
+    assert_eq!(matrix[(0, 1)], 100.0);
+}
+
+#[cfg(feature = "nalgebra")]
+#[test]
+fn test_to_matrix_f32_checked() {
+    use crate::{NasademError, VOID};
+    let (mut samples, ..) = dome().into_raw();
+    samples[0] = VOID;
+    let tile = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples);
+
+    let scaled = tile.to_matrix_f32_checked(2.5).unwrap();
+    assert_eq!(scaled.shape(), (DIM, DIM));
+    assert_eq!(scaled[(30, 30)], 1500.0);
+    assert!(scaled[(0, 0)].is_nan());
+
+    // 600 m * 1e5 is far past 2^24.
+    assert!(matches!(
+        tile.to_matrix_f32_checked(1e5),
+        Err(NasademError::Exaggeration(factor)) if factor == 1e5
+    ));
+    assert!(tile.to_matrix_f32_checked(f32::INFINITY).is_err());
+    assert!(tile.to_matrix_f32_checked(f32::NAN).is_err());
+}
+
+#[test]
+fn test_get_bilinear() {
+    use approx::assert_relative_eq;
+    let tile = dome();
+    let center = |xy| tile.sample(xy).unwrap().geo();
+    let elev = |xy| f64::from(tile.get(xy).unwrap());
+
+    // Sample centers interpolate to themselves.
+    for xy in [(30, 30), (0, 0), (DIM - 1, DIM - 1), (17, 42)] {
+        assert_relative_eq!(
+            tile.get_bilinear(center(xy)).unwrap(),
+            elev(xy),
+            epsilon = 1e-6
+        );
+    }
+
+    // Halfway between two samples is their mean.
+    let (a, b) = (center((10, 30)), center((11, 30)));
+    let mid = Coord {
+        x: (a.x + b.x) / 2.0,
+        y: a.y,
+    };
+    assert_relative_eq!(
+        tile.get_bilinear(mid).unwrap(),
+        (elev((10, 30)) + elev((11, 30))) / 2.0,
+        epsilon = 1e-6
+    );
+
+    assert_eq!(tile.get_bilinear(Coord { x: -71.5, y: 44.1 }), None);
+}
+
+#[cfg(feature = "nalgebra")]
+#[test]
+fn test_landform_classes() {
+    use crate::{Landform, LandformParams};
+    let tile = dome();
+    let classes = tile.landform_classes(&LandformParams::default());
+    assert_eq!(classes.shape(), (DIM, DIM));
+    let class = |y, x| Landform::from_u8(classes[(y, x)]);
+    assert_eq!(class(30, 30), Some(Landform::Ridge));
+    assert_eq!(class(0, 0), Some(Landform::Flat));
+    assert_eq!(class(DIM - 1, 5), Some(Landform::Flat));
+    // The foot of the hill sits below the slope rising beside it.
+    assert!(matches!(
+        class(30, 51),
+        Some(Landform::Valley | Landform::LowerSlope)
+    ));
+
+    let mut samples = vec![10; 9];
+    samples[4] = crate::VOID;
+    let tile = Tile::from_samples(Coord { x: 0, y: 0 }, 3, (3, 3), samples);
+    let classes = tile.landform_classes(&LandformParams::default());
+    assert_eq!(classes[(1, 1)], Landform::VOID);
+    assert_eq!(Landform::from_u8(classes[(0, 0)]), Some(Landform::Flat));
+}
+
+#[cfg(feature = "nalgebra")]
+#[test]
+fn test_patch() {
+    let tile = dome();
+    let summit = tile.sample((30, 30)).unwrap().geo();
+    let patch = tile.patch(summit, 2).unwrap();
+    assert_eq!(patch.shape(), (5, 5));
+    assert_eq!(patch[(2, 2)], 600);
+    for row in 0..5 {
+        for col in 0..5 {
+            assert_eq!(Some(patch[(row, col)]), tile.get((28 + col, 28 + row)));
+        }
+    }
+    // The dome is symmetric, so opposite neighbors match.
+    assert_eq!(patch[(0, 2)], patch[(4, 2)]);
+    assert_eq!(patch[(2, 0)], patch[(2, 4)]);
+    assert!(patch[(1, 2)] < 600);
+
+    // A window touching the edges fits, one past them doesn't.
+    assert!(tile.patch(summit, 30).is_some());
+    assert!(tile.patch(summit, 31).is_none());
+    let corner = tile.sample((0, 0)).unwrap().geo();
+    assert_eq!(tile.patch(corner, 0).unwrap()[(0, 0)], 100);
+    assert!(tile.patch(corner, 1).is_none());
+    assert!(tile.patch(Coord { x: -71.5, y: 44.1 }, 0).is_none());
+}
+
+#[test]
+fn test_fill_voids() {
+    use crate::VOID;
+    let mut tile = dome();
+    // A lone void at the summit and a 9-sample wide void on the
+    // plain, wider than the search radius.
+    tile.set((30, 30), VOID).unwrap();
+    for y in 0..9 {
+        for x in 0..9 {
+            tile.set((x, y), VOID).unwrap();
+        }
+    }
+    assert_eq!(tile.min_elevation(), 100);
+    assert_eq!(tile.fill_voids(2), Some(82));
+    assert_eq!(tile.count_below(Elev::MIN + 1), 0);
+    // The summit is filled from the slope around it, which falls
+    // away from the true 600 m summit.
+    let summit = tile.get((30, 30)).unwrap();
+    assert!((590..600).contains(&summit), "{summit}");
+    assert_eq!(tile.get((0, 0)), Some(100));
+    assert_eq!(tile.get((4, 4)), Some(100));
+    assert_eq!(tile.fill_voids(2), Some(0));
+
+    let mut ocean = Tile::from_samples(Coord { x: 0, y: 0 }, 3, (2, 2), vec![VOID; 4]);
+    assert_eq!(ocean.fill_voids(2), None);
+    assert_eq!(ocean.get(0), Some(VOID));
+}
+
+#[test]
+fn test_fill_wide_void() {
+    use crate::VOID;
+    // Void the whole hill, 51 samples across, leaving a 5-sample
+    // ring of 100 m plain to fill it from one cell at a time.
+    let mut tile = dome();
+    for y in 5..DIM - 5 {
+        for x in 5..DIM - 5 {
+            tile.set((x, y), VOID).unwrap();
+        }
+    }
+    assert_eq!(tile.fill_voids(1), Some(51 * 51));
+    assert_eq!(tile.count_below(Elev::MIN + 1), 0);
+    assert_eq!(tile.min_elevation(), 100);
+    assert_eq!(tile.max_elevation(), 100);
+}
+
+#[test]
+fn test_tile_name_for() {
+    use crate::tile_name_for;
+    let name = |x, y| tile_name_for(Coord { x, y });
+    assert_eq!(name(-71.3, 44.27), "N44W072.hgt");
+    assert_eq!(name(151.2, -33.9), "S34E151.hgt");
+    assert_eq!(name(-43.2, -22.9), "S23W044.hgt");
+    assert_eq!(name(139.7, 35.7), "N35E139.hgt");
+    assert_eq!(name(-0.5, -0.5), "S01W001.hgt");
+    assert_eq!(name(0.5, 0.5), "N00E000.hgt");
+    assert_eq!(name(0.0, 0.0), "N00E000.hgt");
+    // Integer coordinates are tile SW corners.
+    assert_eq!(name(-72.0, 44.0), "N44W072.hgt");
+}
+
+#[test]
+fn test_elevation_range_in() {
+    use crate::geo::Rect;
+    let mut tile = dome();
+    let geo = |x, y| tile.sample((x, y)).unwrap().geo();
+    let rect = |nw: Coord<f64>, se: Coord<f64>| Rect::new(nw, se);
+
+    // The plain in the NW corner is flat.
+    let plain = rect(geo(0, 0), geo(5, 5));
+    assert_eq!(tile.elevation_range_in(plain), Some((100, 100)));
+
+    // A block over the summit spans the extremes of just that block.
+    let summit = rect(geo(25, 25), geo(35, 35));
+    let block: Vec<Elev> = (25..=35)
+        .flat_map(|y| (25..=35).map(move |x| (x, y)))
+        .map(|xy| tile.get(xy).unwrap())
+        .collect();
+    let expected = (*block.iter().min().unwrap(), *block.iter().max().unwrap());
+    assert_eq!(expected.1, 600);
+    assert!(expected.0 > 100);
+    assert_eq!(tile.elevation_range_in(summit), Some(expected));
+
+    // A single sample, and a window overhanging the tile's edge.
+    assert_eq!(
+        tile.elevation_range_in(rect(geo(30, 30), geo(30, 30))),
+        Some((600, 600))
+    );
+    let overhang = Rect::new(Coord { x: -72.1, y: 43.9 }, geo(3, DIM - 4));
+    assert_eq!(tile.elevation_range_in(overhang), Some((100, 100)));
+
+    // Between samples, off the tile, or all void.
+    let res = 3.0 / 3600.0;
+    let gap = Rect::new(
+        Coord {
+            x: geo(1, 1).x + res / 4.0,
+            y: geo(1, 1).y,
+        },
+        Coord {
+            x: geo(1, 1).x + res * 3.0 / 4.0,
+            y: geo(1, 2).y,
+        },
+    );
+    assert_eq!(tile.elevation_range_in(gap), None);
+    let off = Rect::new(Coord { x: -73.0, y: 44.0 }, Coord { x: -72.5, y: 44.5 });
+    assert_eq!(tile.elevation_range_in(off), None);
+    let corner = rect(geo(0, 0), geo(0, 0));
+    tile.set((0, 0), crate::VOID).unwrap();
+    assert_eq!(tile.elevation_range_in(corner), None);
+}
+
+#[test]
+fn test_linear_to_xy_non_square() {
+    let (cols, rows) = (5, 3);
+    let samples = (0..cols * rows)
+        .map(|i| Elev::try_from(i).unwrap())
+        .collect();
+    let tile = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (cols, rows), samples);
+    assert_eq!(tile.linear_to_xy(4), (4, 0));
+    assert_eq!(tile.linear_to_xy(5), (0, 1));
+    assert_eq!(tile.linear_to_xy(14), (4, 2));
+    for idx in 0..cols * rows {
+        let xy = tile.linear_to_xy(idx);
+        assert_eq!(tile.xy_to_linear(xy), idx);
+        assert_eq!(tile.get(xy), tile.get(idx));
+    }
+}
+
+#[test]
+fn test_extremes_cached_at_elev_max() {
+    use crate::store::SampleStore;
+    let mut samples = vec![Elev::MAX; 4];
+    samples[0] = Elev::MIN + 1;
+    let mut tile = Tile::from_samples(Coord { x: 0, y: 0 }, 3, (2, 2), samples);
+    assert_eq!(tile.max_elevation(), Elev::MAX);
+    assert_eq!(tile.min_elevation(), Elev::MIN + 1);
+
+    // Change the samples behind the cache's back; a rescan would see
+    // the new values.
+    let SampleStore::InMem(store) = &mut tile.samples else {
+        panic!("synthetic tiles are in memory");
+    };
+    store.fill(7);
+    assert_eq!(tile.max_elevation(), Elev::MAX);
+    assert_eq!(tile.min_elevation(), Elev::MIN + 1);
+
+    // Setting a sample invalidates the cache.
+    tile.set((0, 0), 7).unwrap();
+    assert_eq!(tile.max_elevation(), 7);
+    assert_eq!(tile.min_elevation(), 7);
+}
+
+#[test]
+fn test_elevation_range_caches_both() {
+    use crate::{store::SampleStore, VOID};
+    let samples = vec![100, VOID, 600, 250];
+    let mut tile = Tile::from_samples(Coord { x: 0, y: 0 }, 3, (2, 2), samples);
+    assert_eq!(tile.elevation_range(), (100, 600));
+
+    // One pass filled both caches, so neither method rescans.
+    let SampleStore::InMem(store) = &mut tile.samples else {
+        panic!("synthetic tiles are in memory");
+    };
+    store.fill(7);
+    assert_eq!((tile.min_elevation(), tile.max_elevation()), (100, 600));
+
+    let all_void = Tile::from_samples(Coord { x: 0, y: 0 }, 3, (2, 2), vec![VOID; 4]);
+    assert_eq!(all_void.elevation_range(), (VOID, VOID));
+}
+
+#[test]
+fn test_rows_and_cols() {
+    let samples = (0..12).collect();
+    let tile = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (4, 3), samples);
+    let indices = |samples: &mut dyn Iterator<Item = crate::Sample<'_>>| {
+        samples.map(|sample| sample.index()).collect::<Vec<_>>()
+    };
+    assert_eq!(
+        indices(&mut tile.rows().flatten()),
+        indices(&mut tile.iter())
+    );
+    assert_eq!(tile.rows().count(), 3);
+    let row: Vec<Elev> = tile.rows().nth(1).unwrap().map(|s| s.elevation()).collect();
+    assert_eq!(row, [4, 5, 6, 7]);
+
+    assert_eq!(tile.cols().count(), 4);
+    let col: Vec<_> = tile.cols().nth(2).unwrap().map(|s| s.xy()).collect();
+    assert_eq!(col, [(2, 0), (2, 1), (2, 2)]);
+    let col: Vec<Elev> = tile.cols().nth(2).unwrap().map(|s| s.elevation()).collect();
+    assert_eq!(col, [2, 6, 10]);
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn test_par_iter() {
+    use crate::VOID;
+    use rayon::iter::ParallelIterator;
+    let (mut samples, ..) = dome().into_raw();
+    samples[0] = VOID;
+    let tile = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples.clone());
+    let indices: Vec<_> = tile.par_iter().map(|sample| sample.index()).collect();
+    assert_eq!(indices, (0..DIM * DIM).collect::<Vec<_>>());
+    let elevations: Vec<_> = tile.par_iter().map(|sample| sample.elevation()).collect();
+    assert_eq!(elevations, samples);
+
+    let range = tile.par_elevation_range();
+    let fresh = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples);
+    assert_eq!(range, fresh.elevation_range());
+    assert_ne!(range.0, VOID);
+    assert_eq!(tile.elevation_range(), range);
+
+    let void = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (2, 2), vec![VOID; 4]);
+    assert_eq!(void.par_elevation_range(), (VOID, VOID));
+    let tombstone = Tile::tombstone(Coord { x: -72, y: 44 }, 3);
+    assert_eq!(tombstone.par_elevation_range(), (0, 0));
+}
+
+#[test]
+fn test_is_flat() {
+    use crate::VOID;
+    let constant = |elev| {
+        Tile::from_samples(
+            Coord { x: -72, y: 44 },
+            3,
+            (DIM, DIM),
+            vec![elev; DIM * DIM],
+        )
+    };
+    assert!(Tile::tombstone(Coord { x: -72, y: 44 }, 3).is_flat());
+    assert!(constant(250).is_flat());
+    assert!(constant(VOID).is_flat());
+    assert!(!dome().is_flat());
+
+    // Voids don't break an otherwise constant tile's flatness.
+    let (mut samples, ..) = constant(250).into_raw();
+    samples[DIM + 1] = VOID;
+    assert!(Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples).is_flat());
+}
+
+#[test]
+fn test_crop() {
+    use crate::geo::Rect;
+    let tile = dome();
+    let geo = |x, y| tile.sample((x, y)).unwrap().geo();
+
+    // A block over the summit keeps its samples and grid.
+    let crop = tile.crop(Rect::new(geo(25, 25), geo(35, 32))).unwrap();
+    assert_eq!(crop.dimensions(), (11, 8));
+    assert_eq!(crop.resolution(), tile.resolution());
+    assert_eq!(crop.get((5, 5)), Some(600));
+    assert_eq!(
+        crop.elevation_range(),
+        tile.elevation_range_in(Rect::new(geo(25, 25), geo(35, 32)))
+            .unwrap()
+    );
+    for (x, y) in [(25, 25), (35, 25), (25, 32), (35, 32), (30, 30)] {
+        assert_eq!(crop.get(geo(x, y)), tile.get((x, y)));
+        let offset = crop.sample((x - 25, y - 25)).unwrap().geo() - geo(x, y);
+        assert!(offset.x.abs() < 1e-9 && offset.y.abs() < 1e-9, "{offset:?}");
+    }
+    assert_eq!(crop.get(geo(24, 25)), None);
+    assert_eq!(crop.get(geo(25, 33)), None);
+
+    // Overhangs clamp to the tile's edges.
+    let crop = tile
+        .crop(Rect::new(Coord { x: -72.1, y: 43.9 }, geo(3, DIM - 4)))
+        .unwrap();
+    assert_eq!(crop.dimensions(), (4, 4));
+    assert_eq!(crop.get(geo(0, DIM - 1)), Some(100));
+
+    // Between samples or off the tile.
+    let res = 3.0 / 3600.0;
+    let between = Coord {
+        x: geo(10, 10).x + res / 3.0,
+        y: geo(10, 10).y,
+    };
+    assert!(tile
+        .crop(Rect::new(
+            between,
+            Coord {
+                x: between.x + res / 3.0,
+                ..between
+            }
+        ))
+        .is_none());
+    assert!(tile
+        .crop(Rect::new(
+            Coord { x: -71.0, y: 44.0 },
+            Coord { x: -70.0, y: 45.0 }
+        ))
+        .is_none());
+}
+
+#[test]
+fn test_crop_keeps_georeference() {
+    use crate::{geo::Rect, TileSet, VoidPolicy};
+    let tile = dome();
+    let geo = |x, y| tile.sample((x, y)).unwrap().geo();
+
+    // 13 x 9 samples around the summit, nowhere near a whole degree.
+    let crop = tile.crop(Rect::new(geo(24, 26), geo(36, 34))).unwrap();
+    assert_eq!(crop.dimensions(), (13, 9));
+
+    // Every tile rebuilt from the crop stays where the crop is.
+    let summit = geo(30, 30);
+    let coarse = crop.downsample(2);
+    assert_eq!(coarse.dimensions(), (7, 5));
+    assert_eq!(coarse.sample(summit).unwrap().xy(), (3, 2));
+    for sample in coarse.iter() {
+        let (x, y) = sample.xy();
+        let offset = sample.geo() - crop.sample((2 * x, 2 * y)).unwrap().geo();
+        assert!(offset.x.abs() < 1e-9 && offset.y.abs() < 1e-9, "{offset:?}");
+    }
+    assert_eq!(crop.resample(6).get(summit), coarse.get(summit));
+    assert_eq!(crop.smooth(0, VoidPolicy::Preserve).get(summit), Some(600));
+    assert_eq!(
+        crop.build_overviews(0).level(0).unwrap().get(summit),
+        Some(600)
+    );
+
+    let mut set = TileSet::new();
+    set.insert(crop);
+    assert_eq!(set.get(summit), Some(600));
+}
+
+#[test]
+fn test_resample() {
+    use crate::VOID;
+    let mut tile = dome();
+
+    // A multiple of the resolution averages blocks, as downsample.
+    let coarse = tile.resample(9);
+    assert_eq!((coarse.resolution(), coarse.dimensions()), (9, (21, 21)));
+    assert!(coarse
+        .iter()
+        .map(|s| s.elevation())
+        .eq(tile.downsample(3).iter().map(|s| s.elevation())));
+
+    // Other targets interpolate, keeping shared samples exact.
+    let fine = tile.resample(1);
+    assert_eq!((fine.resolution(), fine.dimensions()), (1, (181, 181)));
+    for (x, y) in [(0, 0), (30, 30), (60, 60), (12, 47)] {
+        assert_eq!(fine.get((3 * x, 3 * y)), tile.get((x, y)));
+    }
+    let between = (f64::from(tile.get((30, 30)).unwrap()) * 2.0
+        + f64::from(tile.get((31, 30)).unwrap()))
+        / 3.0;
+    #[allow(clippy::cast_possible_truncation)]
+    let between = between.round() as Elev;
+    assert_eq!(fine.get((91, 90)), Some(between));
+    let coarse = tile.resample(4);
+    assert_eq!((coarse.resolution(), coarse.dimensions()), (4, (46, 46)));
+    assert_eq!(coarse.get((3, 3)), tile.get((4, 4)));
+
+    // Voids spread to the interpolated samples around them.
+    tile.set((30, 30), VOID).unwrap();
+    let fine = tile.resample(1);
+    assert_eq!(fine.get((90, 90)), Some(VOID));
+    assert_eq!(fine.get((92, 92)), Some(VOID));
+    // The next source sample east is exact, so untouched.
+    assert_eq!(fine.get((93, 90)), tile.get((31, 30)));
+}
+
+#[test]
+#[should_panic(expected = "does not evenly divide")]
+fn test_resample_misaligned() {
+    // 180 arcseconds across isn't a whole number of 7s.
+    let _ = dome().resample(7);
+}
+
+#[test]
+fn test_tile_set() {
+    use crate::TileSet;
+    // Two full 3-arcsecond tiles side by side, leaving N44W070 a gap.
+    let flat =
+        |x, elev| Tile::from_samples(Coord { x, y: 44 }, 3, (1201, 1201), vec![elev; 1201 * 1201]);
+    let set: TileSet = [flat(-72, 100), flat(-71, 200)].into_iter().collect();
+    assert_eq!(set.len(), 2);
+    assert!(set.tile(Coord { x: -71, y: 44 }).is_some());
+
+    let get = |x, y| set.get(Coord { x, y });
+    assert_eq!(get(-71.5, 44.5), Some(100));
+    assert_eq!(get(-70.5, 44.5), Some(200));
+    // The shared edge belongs to the tile east of it.
+    assert_eq!(get(-71.0, 44.5), Some(200));
+    // Within half a sample east of the last tile, its edge covers.
+    let half_sample = 1.5 / 3600.0;
+    assert_eq!(get(-70.0 + half_sample / 2.0, 44.5), Some(200));
+    assert_eq!(get(-70.0 + half_sample * 2.0, 44.5), None);
+    assert_eq!(get(-69.5, 44.5), None);
+    assert_eq!(get(-71.5, 45.5), None);
+
+    let bounds = set.bounds().unwrap();
+    assert!((bounds.min().x - (-72.0 - half_sample)).abs() < 1e-9);
+    assert!((bounds.max().x - (-70.0 + half_sample)).abs() < 1e-9);
+    assert!((bounds.min().y - (44.0 - half_sample)).abs() < 1e-9);
+    assert!((bounds.max().y - (45.0 + half_sample)).abs() < 1e-9);
+
+    let set = set.with_gap_elevation(0);
+    assert_eq!(set.get(Coord { x: -69.5, y: 44.5 }), Some(0));
+    assert_eq!(set.get(Coord { x: -71.5, y: 44.5 }), Some(100));
+    assert!(TileSet::new().bounds().is_none());
+}
+
+#[test]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn test_tile_set_profile() {
+    use crate::{
+        geo::{HaversineDistance, HaversineIntermediate, Point},
+        TileSet,
+    };
+    // Elevation rises one meter per sample eastward, continuously
+    // across the two tiles, leaving N44W070 a gap.
+    let ramp = |x: i16, offset: i16| {
+        let samples = (0..1201 * 1201)
+            .map(|idx: i32| i16::try_from(idx % 1201).unwrap() + offset)
+            .collect();
+        Tile::from_samples(Coord { x, y: 44 }, 3, (1201, 1201), samples)
+    };
+    let set: TileSet = [ramp(-72, 0), ramp(-71, 1200)].into_iter().collect();
+
+    let (start, end) = (Coord { x: -71.9, y: 44.1 }, Coord { x: -69.8, y: 44.9 });
+    let distance_m = Point::from(start).haversine_distance(&Point::from(end));
+    let profile = set.profile(start, end, 1000.0);
+    assert_eq!(profile.len(), (distance_m / 1000.0).ceil() as usize + 1);
+    assert_eq!(profile[0].0, 0.0);
+    assert_eq!(profile[1].0, 1000.0);
+    assert_eq!(profile.last().unwrap().0, distance_m);
+
+    let mut gaps = 0;
+    for &(along_m, elev) in &profile {
+        let point =
+            Point::from(start).haversine_intermediate(&Point::from(end), along_m / distance_m);
+        if point.x() > -70.0 {
+            assert_eq!(elev, None, "{point:?}");
+            gaps += 1;
+        } else {
+            // Interpolated, not snapped to the nearest sample.
+            let expected = (point.x() + 72.0) * 1200.0;
+            assert!(
+                (f64::from(elev.unwrap()) - expected).abs() <= 0.5,
+                "{point:?}"
+            );
+        }
+    }
+    assert!(gaps > 0);
+
+    assert_eq!(set.profile(start, start, 10.0), vec![(0.0, Some(120))]);
+}
+
+#[test]
+fn test_line_of_sight() {
+    // A flat 3-arcsecond tile at 100 m with a 50 m wall along the
+    // 44.5°N row, and a void at 44.6°N.
+    let mut samples = vec![100; 1201 * 1201];
+    samples[600 * 1201..601 * 1201].fill(150);
+    samples[480 * 1201 + 600] = crate::VOID;
+    let tile = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (1201, 1201), samples);
+    let (south, north) = (Coord { x: -71.5, y: 44.2 }, Coord { x: -71.5, y: 44.8 });
+
+    assert!(!tile.line_of_sight((south, 10.0), (north, 10.0)));
+    // The wall is midway, so the line clears it at 50 m above each
+    // end's terrain.
+    assert!(!tile.line_of_sight((south, 49.0), (north, 49.0)));
+    assert!(tile.line_of_sight((south, 51.0), (north, 51.0)));
+    assert!(tile.line_of_sight((south, 2.0), (south, 2.0)));
+    // Along the wall's south side nothing intervenes, and the void
+    // doesn't obstruct.
+    let (west, east) = (Coord { x: -71.9, y: 44.6 }, Coord { x: -71.1, y: 44.6 });
+    assert!(tile.line_of_sight((west, 0.0), (east, 0.0)));
+    // An endpoint off the tile has unknown terrain.
+    assert!(!tile.line_of_sight((south, 1000.0), (Coord { x: -70.5, y: 44.5 }, 1000.0)));
+
+    // Over these ~64 km the earth bulges about 60 m at the midpoint.
+    assert!(tile.line_of_sight((west, 10.0), (east, 10.0)));
+    assert!(!tile.line_of_sight_curved((west, 10.0), (east, 10.0)));
+    assert!(tile.line_of_sight_curved((west, 70.0), (east, 70.0)));
+}
+
+#[test]
+#[cfg(feature = "image")]
+#[allow(clippy::cast_possible_truncation)]
+fn test_to_normal_map() {
+    let map = flat().to_normal_map();
+    assert_eq!(map.dimensions(), (DIM as u32, DIM as u32));
+    assert!(map.pixels().all(|px| px.0 == [128, 128, 255]));
+
+    // Rising eastward, the normals tilt west.
+    let ramp = east_ramp();
+    let map = ramp.to_normal_map();
+    let (dx_m, _) = ramp.ground_resolution_m();
+    let nx = -10.0 / dx_m / ((10.0 / dx_m).powi(2) + 1.0).sqrt();
+    let [r, g, b] = map.get_pixel(DIM as u32 / 2, DIM as u32 / 2).0;
+    assert!(
+        (f64::from(r) - (nx + 1.0) / 2.0 * 255.0).abs() <= 1.0,
+        "{r}"
+    );
+    assert!(r < 128);
+    assert_eq!(g, 128);
+    assert!(b < 255);
+}
+
+#[test]
+#[cfg(feature = "image")]
+fn test_to_image_flat() {
+    let tombstone = Tile::tombstone(Coord { x: -72, y: 44 }, 3);
+    let img = tombstone.to_image::<u16>();
+    assert_eq!(img.dimensions(), (1201, 1201));
+    assert!(img.pixels().all(|px| px.0 == [0]));
+    assert!(flat().to_image::<u8>().pixels().all(|px| px.0 == [0]));
+}
+
+#[test]
+#[cfg(feature = "image")]
+fn test_to_image_ranged() {
+    let ramp = east_ramp();
+    assert_eq!(ramp.to_image::<u16>(), ramp.to_image_ranged::<u16>(0, 600));
+
+    let img = ramp.to_image_ranged::<u8>(100, 350);
+    let column = |x: u32| img.get_pixel(x, 30).0[0];
+    assert_eq!(column(0), 0);
+    assert_eq!(column(10), 0);
+    assert_eq!(column(20), 102);
+    assert_eq!(column(35), 255);
+    assert_eq!(column(60), 255);
+    assert!(ramp
+        .to_image_ranged::<u8>(300, 300)
+        .pixels()
+        .all(|px| px.0 == [0]));
+}
+
+#[test]
+#[cfg(feature = "image")]
+#[allow(clippy::cast_possible_truncation)]
+fn test_to_color_image() {
+    use crate::{Palette, VOID};
+    use image::Rgb;
+    let (mut samples, ..) = east_ramp().into_raw();
+    samples[DIM + 1] = VOID;
+    let ramp = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples);
+
+    let gray =
+        Palette::new([(1.0, [255, 255, 255]), (0.0, [0, 0, 0])]).with_void_color([255, 0, 0]);
+    let img = ramp.to_color_image(&gray);
+    assert_eq!(img.dimensions(), (DIM as u32, DIM as u32));
+    assert_eq!(img.get_pixel(0, 0), &Rgb([0, 0, 0]));
+    assert_eq!(img.get_pixel(30, 0), &Rgb([128, 128, 128]));
+    assert_eq!(img.get_pixel(60, 0), &Rgb([255, 255, 255]));
+    assert_eq!(img.get_pixel(1, 1), &Rgb([255, 0, 0]));
+
+    let img = ramp.to_color_image(&Palette::default());
+    assert_eq!(img.get_pixel(0, 0), &Rgb([0x4a, 0x7c, 0x3e]));
+    assert_eq!(img.get_pixel(60, 0), &Rgb([0xff, 0xff, 0xff]));
+    assert_eq!(img.get_pixel(1, 1), &Rgb([0, 0, 0]));
+
+    // Flat tiles take the lowest stop's color.
+    let tombstone = Tile::tombstone(Coord { x: -72, y: 44 }, 3);
+    assert!(tombstone
+        .to_color_image(&gray)
+        .pixels()
+        .all(|px| px == &Rgb([0, 0, 0])));
+}
+
+#[test]
+fn test_fresnel_clearance() {
+    use crate::geo::{HaversineDistance, Point};
+    let flat = Tile::from_samples(
+        Coord { x: -72, y: 44 },
+        3,
+        (1201, 1201),
+        vec![100; 1201 * 1201],
+    );
+    let (west, east) = (Coord { x: -71.9, y: 44.6 }, Coord { x: -71.1, y: 44.6 });
+    let clearance = flat.fresnel_clearance((west, 10.0), (east, 10.0), 900e6);
+    // One step per column crossed, excluding the endpoints.
+    assert_eq!(clearance.len(), 959);
+    assert!(clearance.iter().all(|c| c.is_finite() && *c < 10.0));
+
+    // The zone is widest midway, where its radius is
+    // sqrt(λ * d / 4) for a path of length d.
+    let distance_m = Point::from(west).haversine_distance(&Point::from(east));
+    let radius_m = (299_792_458.0 / 900e6 * distance_m / 4.0).sqrt();
+    assert!((clearance[479] - (10.0 - radius_m)).abs() < 1e-6);
+    assert!(clearance[479] < 0.0);
+    assert!(clearance[0] > 0.0 && clearance[958] > 0.0);
+
+    // Higher frequencies have narrower zones.
+    let clearance_5ghz = flat.fresnel_clearance((west, 10.0), (east, 10.0), 5.8e9);
+    assert!(clearance_5ghz[479] > clearance[479]);
+
+    let off_tile = Coord { x: -70.5, y: 44.6 };
+    assert!(flat
+        .fresnel_clearance((west, 10.0), (off_tile, 10.0), 900e6)
+        .is_empty());
+}
+
+#[test]
+#[cfg(feature = "nalgebra")]
+fn test_viewshed() {
+    use crate::VOID;
+    // Flat at 100 m with a 50 m wall along column 40.
+    let mut samples = vec![100; DIM * DIM];
+    for row in samples.chunks_mut(DIM) {
+        row[40] = 150;
+    }
+    samples[30 * DIM + 20] = VOID;
+    let tile = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples);
+
+    let view = tile.viewshed((30, 30), 2.0, 20);
+    assert_eq!(view.shape(), (41, 41));
+    // Window element (r, c) is sample (10 + c, 10 + r).
+    assert!(view[(20, 20)]);
+    assert!(view[(20, 29)]);
+    // The wall is seen, the ground just behind it isn't.
+    assert!(view[(20, 30)]);
+    assert!(!view[(20, 31)]);
+    assert!(!view[(25, 34)]);
+    // Voids are hidden but don't block.
+    assert!(!view[(20, 10)]);
+    assert!(view[(20, 9)]);
+    // Corners are beyond the radius.
+    assert!(!view[(0, 0)]);
+    assert!(view[(0, 20)]);
+
+    // The window clips at the tile's edges.
+    let view = tile.viewshed((0, 0), 2.0, 20);
+    assert_eq!(view.shape(), (21, 21));
+    assert!(view[(0, 0)] && view[(0, 20)] && view[(20, 0)]);
+    let view = tile.viewshed((DIM - 1, 5), 0.0, 100);
+    assert_eq!(view.shape(), (DIM, DIM));
+    assert!(view[(5, DIM - 1)]);
+
+    // The observer's own sample is visible even if void.
+    let view = tile.viewshed((20, 30), 2.0, 5);
+    assert_eq!(view.iter().filter(|&&v| v).count(), 1);
+    assert!(view[(5, 5)]);
+}
+
+#[test]
+fn test_sample_slope_and_aspect() {
+    use std::f32::consts::{FRAC_PI_2, PI};
+    let ramp = east_ramp();
+    let sample = ramp.sample((30, 30)).unwrap();
+    // Samples are ~66 m apart east-west at this latitude, and ~93 m
+    // north-south.
+    let (dx_m, dy_m) = ramp.ground_resolution_m();
+    assert!((f64::from(sample.slope()) - (10.0 / dx_m).atan()).abs() < 1e-6);
+    // It faces downhill, west.
+    assert!((sample.aspect() - 3.0 * FRAC_PI_2).abs() < 1e-6);
+
+    // Rising 10 m per sample northward, i.e. toward row 0.
+    let samples = (0..DIM * DIM)
+        .map(|idx| Elev::try_from((DIM - 1 - idx / DIM) * 10).unwrap())
+        .collect();
+    let north_ramp = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples);
+    let sample = north_ramp.sample((30, 30)).unwrap();
+    assert!((f64::from(sample.slope()) - (10.0 / dy_m).atan()).abs() < 1e-6);
+    assert!((sample.aspect() - PI).abs() < 1e-6);
+    // Edge neighbors are clamped rather than read out of bounds.
+    for xy in [(0, 0), (DIM - 1, DIM - 1)] {
+        let corner = north_ramp.sample(xy).unwrap();
+        assert!(corner.slope() > 0.0);
+        assert!((corner.aspect() - PI).abs() < 1e-6);
+    }
+
+    let flat = flat();
+    let sample = flat.sample((30, 30)).unwrap();
+    assert_eq!(sample.slope(), 0.0);
+    assert!(sample.aspect().is_nan());
+
+    let (mut samples, ..) = flat.into_raw();
+    samples[30 * DIM + 30] = crate::VOID;
+    let voided = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples);
+    assert!(voided.sample((30, 30)).unwrap().slope().is_nan());
+    // A void neighbor is replaced by the center, so stays flat.
+    assert_eq!(voided.sample((31, 30)).unwrap().slope(), 0.0);
+}
+
+#[test]
+#[cfg(feature = "nalgebra")]
+#[allow(clippy::cast_sign_loss)]
+fn test_slope_matrix() {
+    use crate::SlopeUnit;
+    let tile = pyramid();
+    let degrees = tile.slope_matrix();
+    assert_eq!(degrees.shape(), (DIM, DIM));
+    // Samples whose whole 3x3 neighborhood lies on one face, keyed
+    // by the face's offset from the summit.
+    let face = |dx: isize, dy: isize| {
+        (3..18).flat_map(move |along: isize| {
+            (-(along - 2)..=along - 2).map(move |across| {
+                let (x, y) = if dx == 0 {
+                    (across, along * dy)
+                } else {
+                    (along * dx, across)
+                };
+                ((30 + y) as usize, (30 + x) as usize)
+            })
+        })
+    };
+    let (_, dy_m) = tile.ground_resolution_m();
+    for (dx, dy) in [(0, -1), (1, 0), (0, 1), (-1, 0)] {
+        let slopes: Vec<f32> = face(dx, dy).map(|rc| degrees[rc]).collect();
+        let (lo, hi) = slopes
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(lo, hi), &s| (lo.min(s), hi.max(s)));
+        assert!(hi - lo < 0.01, "face {:?}: {lo} to {hi}", (dx, dy));
+        if dx == 0 {
+            let expected = (20.0 / dy_m).atan().to_degrees();
+            assert!(
+                (f64::from(lo) - expected).abs() < 1e-4,
+                "{lo} vs {expected}"
+            );
+        } else {
+            // East-west samples are closer together, so steeper.
+            assert!(f64::from(lo) > (20.0 / dy_m).atan().to_degrees() + 1.0);
+        }
+    }
+    // The plain is flat.
+    assert_eq!(degrees[(0, 0)], 0.0);
+    let sample = tile.sample((30, 20)).unwrap();
+    assert!((degrees[(20, 30)] - sample.slope().to_degrees()).abs() < 1e-4);
+
+    let percent = tile.slope_matrix_in(SlopeUnit::Percent);
+    assert!((f64::from(percent[(20, 30)]) - 2000.0 / dy_m).abs() < 1e-3);
+
+    let (mut samples, ..) = tile.into_raw();
+    samples[0] = crate::VOID;
+    let voided = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples);
+    assert!(voided.slope_matrix()[(0, 0)].is_nan());
+}
+
+#[test]
+#[cfg(feature = "nalgebra")]
+fn test_aspect_matrix() {
+    let tile = pyramid();
+    let aspect = tile.aspect_matrix();
+    assert_eq!(aspect.shape(), (DIM, DIM));
+    // Each face's midline, as (row, col), and the way it faces.
+    for ((row, col), expected) in [
+        ((20, 30), 0.0),
+        ((30, 40), 90.0),
+        ((40, 30), 180.0),
+        ((30, 20), 270.0),
+    ] {
+        assert!(
+            (aspect[(row, col)] - expected).abs() < 1e-3,
+            "{:?}",
+            (row, col)
+        );
+        let sample = tile.sample((col, row)).unwrap();
+        assert!((aspect[(row, col)] - sample.aspect().to_degrees()).abs() < 1e-3);
+    }
+    // Off the midline the face still faces the same way.
+    assert!((aspect[(15, 25)] - 0.0).abs() < 1e-3);
+    // The plain is flat.
+    assert_eq!(aspect[(0, 0)], -1.0);
+    assert!(aspect
+        .iter()
+        .all(|&a| a == -1.0 || (0.0..360.0).contains(&a)));
+
+    let (mut samples, ..) = tile.into_raw();
+    samples[0] = crate::VOID;
+    let voided = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (DIM, DIM), samples);
+    assert!(voided.aspect_matrix()[(0, 0)].is_nan());
+}
@@ -1,6 +1,6 @@
 use crate::{
     geo::{geometry::LineString, Coord, Polygon},
-    util, Tile,
+    util, NasademError, SnapMode, Tile,
 };
 use std::{fs::File, io::BufReader, path::PathBuf};
 
@@ -125,8 +125,22 @@ fn test_xy_to_polygon() {
     let mut path = three_arcsecond_dir();
     path.push("N44W072.hgt");
     let parsed_tile = Tile::load(&path).unwrap();
+    // (0, 0) is the NW corner.
     assert_eq!(
         parsed_tile.xy_to_polygon((0, 0)),
+        Polygon::new(
+            LineString::from(vec![
+                (-72.000_416_666_666_67, 44.999_583_333_333_334),
+                (-71.999_583_333_333_33, 44.999_583_333_333_334),
+                (-71.999_583_333_333_33, 45.000_416_666_666_666),
+                (-72.000_416_666_666_67, 45.000_416_666_666_666),
+                (-72.000_416_666_666_67, 44.999_583_333_333_334),
+            ]),
+            vec![],
+        )
+    );
+    assert_eq!(
+        parsed_tile.xy_to_polygon((0, 1200)),
         Polygon::new(
             LineString::from(vec![
                 (-72.000_416_666_666_67, 43.999_583_333_333_334),
@@ -139,3 +153,861 @@ fn test_xy_to_polygon() {
         )
     );
 }
+
+#[test]
+fn test_sample() {
+    let mut path = three_arcsecond_dir();
+    path.push("N44W072.hgt");
+    let tile = Tile::load(&path).unwrap();
+
+    for linear_idx in [0, 1, 1200, 1201, 720_600, tile.len() - 1] {
+        let sample = tile.sample(linear_idx).unwrap();
+        assert_eq!(Some(sample.elevation()), tile.get(linear_idx));
+        assert_eq!(sample.index(), linear_idx);
+        assert_eq!(
+            tile.sample_unchecked(linear_idx).elevation(),
+            sample.elevation()
+        );
+    }
+
+    let sample = tile.sample((1200, 3)).unwrap();
+    assert_eq!(sample.xy(), (1200, 3));
+    assert_eq!(Some(sample.elevation()), tile.get((1200, 3)));
+
+    let geo = tile.xy_to_geo((7, 11));
+    assert_eq!(tile.sample(geo).unwrap().xy(), (7, 11));
+
+    assert!(tile.sample(tile.len()).is_none());
+    assert!(tile.sample((1201, 0)).is_none());
+    assert!(tile.sample((0, 1201)).is_none());
+    assert!(tile.sample(Coord { x: -71.5, y: 45.1 }).is_none());
+}
+
+#[test]
+fn test_fold() {
+    let mut path = three_arcsecond_dir();
+    path.push("N44W072.hgt");
+    let expected: i64 = Tile::load(&path)
+        .unwrap()
+        .iter()
+        .map(|sample| i64::from(sample.elevation()))
+        .sum();
+    for tile in [Tile::load(&path).unwrap(), Tile::memmap(&path).unwrap()] {
+        let sum = tile.fold(0_i64, |acc, elev| acc + i64::from(elev));
+        assert_eq!(sum, expected);
+    }
+
+    let tombstone = Tile::tombstone(Coord { x: -72, y: 44 }, 3);
+    assert_eq!(tombstone.fold(0, |n, _| n + 1), tombstone.len());
+}
+
+#[test]
+fn test_get_many() {
+    let mut path = three_arcsecond_dir();
+    path.push("N44W072.hgt");
+    let xys = [(0, 0), (2, 1), (1200, 1200), (1201, 0), (600, 7)];
+    let coords = [
+        Coord {
+            x: -71.303_25,
+            y: 44.270_5,
+        },
+        Coord { x: -73.0, y: 44.5 },
+    ];
+    for tile in [
+        Tile::load(&path).unwrap(),
+        Tile::memmap(&path).unwrap(),
+        Tile::memmap_cow(&path).unwrap(),
+        Tile::tombstone(Coord { x: -72, y: 44 }, 3),
+    ] {
+        let expected: Vec<_> = xys.iter().map(|&xy| tile.get(xy)).collect();
+        assert_eq!(tile.get_many(&xys), expected);
+        assert_eq!(expected[3], None);
+        let expected: Vec<_> = coords.iter().map(|&coord| tile.get(coord)).collect();
+        assert_eq!(tile.get_many(&coords), expected);
+        assert_eq!(tile.get_many(&[5, 1201 * 1201]), [tile.get(5), None]);
+
+        let inside = [(0, 0), (2, 1), (1200, 1200), (600, 7)];
+        let expected: Vec<_> = inside.iter().map(|&xy| tile.get_unchecked(xy)).collect();
+        assert_eq!(tile.get_many_unchecked(&inside), expected);
+        assert_eq!(
+            tile.get_many_unchecked(&coords[..1]),
+            [tile.get_unchecked(coords[0])]
+        );
+    }
+}
+
+#[test]
+fn test_as_slice() {
+    let mut path = three_arcsecond_dir();
+    path.push("N44W072.hgt");
+    let tile = Tile::load(&path).unwrap();
+    let slice = tile.as_slice().unwrap();
+    assert_eq!(slice.len(), 1201 * 1201);
+    assert_eq!(slice[1201 + 2], tile.get_unchecked((2, 1)));
+    assert!(tile
+        .iter()
+        .all(|sample| slice[sample.index()] == sample.elevation()));
+
+    assert!(Tile::memmap(&path).unwrap().as_slice().is_none());
+    assert!(Tile::memmap_cow(&path).unwrap().as_slice().is_none());
+    assert!(Tile::tombstone(Coord { x: -72, y: 44 }, 3)
+        .as_slice()
+        .is_none());
+}
+
+#[test]
+fn test_into_raw() {
+    let mut path = three_arcsecond_dir();
+    path.push("N44W072.hgt");
+    let expected: Vec<_> = Tile::load(&path)
+        .unwrap()
+        .iter()
+        .map(|sample| sample.elevation())
+        .collect();
+
+    for tile in [Tile::load(&path).unwrap(), Tile::memmap(&path).unwrap()] {
+        let polygon = tile.polygon();
+        let (samples, cols, rows, resolution, sw_corner) = tile.into_raw();
+        assert_eq!(samples, expected);
+        assert_eq!((cols, rows), (1201, 1201));
+        assert_eq!(resolution, 3);
+        assert_eq!(sw_corner, Coord { x: -72, y: 44 });
+
+        let roundtrip = Tile::from_samples(sw_corner, resolution, (cols, rows), samples);
+        assert_eq!(roundtrip.dimensions(), (cols, rows));
+        assert_eq!(roundtrip.resolution(), resolution);
+        assert_eq!(roundtrip.polygon(), polygon);
+        assert_eq!(roundtrip.into_raw().0, expected);
+    }
+
+    let (samples, ..) = Tile::tombstone(Coord { x: -72, y: 44 }, 3).into_raw();
+    assert_eq!(samples, vec![0; 1201 * 1201]);
+}
+
+#[test]
+fn test_save() {
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let original = std::fs::read(&src).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+
+    // Tiles are loaded by name, so round trip through directories.
+    let (first, second) = (dir.path().join("first"), dir.path().join("second"));
+    std::fs::create_dir(&first).unwrap();
+    std::fs::create_dir(&second).unwrap();
+    let saved = first.join("N44W072.hgt");
+    Tile::load(&src).unwrap().save(&saved).unwrap();
+    assert_eq!(std::fs::read(&saved).unwrap(), original);
+    // Saving into a directory names the file after the tile.
+    Tile::load(&saved).unwrap().save(&second).unwrap();
+    assert_eq!(std::fs::read(second.join("N44W072.hgt")).unwrap(), original);
+    let from_memmap = dir.path().join("from-memmap.hgt");
+    Tile::memmap(&saved).unwrap().save(&from_memmap).unwrap();
+    assert_eq!(std::fs::read(&from_memmap).unwrap(), original);
+
+    // A downsampled tile still spans a degree, at 6 arcseconds.
+    let third = dir.path().join("third");
+    std::fs::create_dir(&third).unwrap();
+    let downsampled = Tile::load(&src).unwrap().downsample(2);
+    downsampled.save(&third).unwrap();
+    let reloaded = Tile::load(third.join("N44W072.hgt")).unwrap();
+    assert_eq!(reloaded.resolution(), 6);
+    assert_eq!(reloaded.into_raw().0, downsampled.into_raw().0);
+
+    let partial = Tile::from_samples(Coord { x: -72, y: 44 }, 3, (10, 10), vec![0; 100]);
+    let path = dir.path().join("partial.hgt");
+    assert!(matches!(
+        partial.save(&path),
+        Err(NasademError::HgtLen(_, err_path)) if err_path == path
+    ));
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_load_gzip() {
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("N44W072.hgt.gz");
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    encoder.write_all(&std::fs::read(&src).unwrap()).unwrap();
+    std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+
+    let plain = Tile::load(&src).unwrap();
+    assert_eq!(util::extract_resolution(&path).unwrap(), (3, (1201, 1201)));
+    for tile in [Tile::load(&path).unwrap(), Tile::memmap(&path).unwrap()] {
+        assert!(tile.row_slice(0).is_some(), "gzip tiles are in memory");
+        assert_eq!(tile.resolution(), 3);
+        assert_eq!(tile.polygon(), plain.polygon());
+        assert!(tile
+            .iter()
+            .map(|s| s.elevation())
+            .eq(plain.iter().map(|s| s.elevation())));
+    }
+
+    let corrupt = dir.path().join("N45W072.hgt.gz");
+    std::fs::write(&corrupt, b"not gzip").unwrap();
+    assert!(matches!(
+        Tile::load(&corrupt),
+        Err(NasademError::Decompress(_))
+    ));
+}
+
+#[cfg(feature = "zip")]
+#[test]
+fn test_load_zip() {
+    use std::io::Write;
+    use zip::{write::FileOptions, ZipWriter};
+
+    let write_zip = |path: &std::path::Path, entries: &[(&str, &[u8])]| {
+        let mut zip = ZipWriter::new(File::create(path).unwrap());
+        for (name, data) in entries {
+            zip.start_file(*name, FileOptions::default()).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap();
+    };
+
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let dir = tempfile::tempdir().unwrap();
+    // Named as NASA Earthdata names them; the tile name comes from
+    // the entry.
+    let path = dir.path().join("NASADEM_HGT_x.zip");
+    write_zip(
+        &path,
+        &[
+            ("README.txt", b"not a tile"),
+            ("n44w072.hgt", &std::fs::read(&src).unwrap()),
+        ],
+    );
+
+    let plain = Tile::load(&src).unwrap();
+    for tile in [Tile::load_zip(&path).unwrap(), Tile::load(&path).unwrap()] {
+        assert!(tile.row_slice(0).is_some(), "zip tiles are in memory");
+        assert_eq!(tile.resolution(), 3);
+        assert_eq!(tile.polygon(), plain.polygon());
+        assert!(tile
+            .iter()
+            .map(|s| s.elevation())
+            .eq(plain.iter().map(|s| s.elevation())));
+    }
+
+    let no_hgt = dir.path().join("no_hgt.zip");
+    write_zip(&no_hgt, &[("README.txt", b"not a tile")]);
+    assert!(matches!(
+        Tile::load_zip(&no_hgt),
+        Err(NasademError::HgtName(err_path)) if err_path == no_hgt
+    ));
+
+    let corrupt = dir.path().join("corrupt.zip");
+    std::fs::write(&corrupt, b"not a zip").unwrap();
+    assert!(matches!(
+        Tile::load_zip(&corrupt),
+        Err(NasademError::Decompress(_))
+    ));
+}
+
+#[cfg(feature = "lz4")]
+#[test]
+fn test_lz4_round_trip() {
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let original = Tile::load(&src).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    original.save_lz4(dir.path()).unwrap();
+    let path = dir.path().join("N44W072.hgt.lz4");
+    assert!(std::fs::metadata(&path).unwrap().len() < std::fs::metadata(&src).unwrap().len());
+
+    for tile in [
+        Tile::load_lz4(&path).unwrap(),
+        Tile::load(&path).unwrap(),
+        Tile::memmap(&path).unwrap(),
+    ] {
+        assert!(tile.row_slice(0).is_some(), "lz4 tiles are in memory");
+        assert_eq!(tile.resolution(), 3);
+        assert_eq!(tile.polygon(), original.polygon());
+        assert!(tile
+            .iter()
+            .map(|s| s.elevation())
+            .eq(original.iter().map(|s| s.elevation())));
+    }
+
+    let corrupt = dir.path().join("N45W072.hgt.lz4");
+    std::fs::write(&corrupt, b"not lz4").unwrap();
+    assert!(matches!(
+        Tile::load_lz4(&corrupt),
+        Err(NasademError::Decompress(_))
+    ));
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_zstd_round_trip() {
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let original = Tile::load(&src).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    original.save_zstd(dir.path()).unwrap();
+    let path = dir.path().join("N44W072.hgt.zst");
+    assert!(std::fs::metadata(&path).unwrap().len() < std::fs::metadata(&src).unwrap().len());
+
+    for tile in [
+        Tile::load_zstd(&path).unwrap(),
+        Tile::load(&path).unwrap(),
+        Tile::memmap(&path).unwrap(),
+    ] {
+        assert!(tile.row_slice(0).is_some(), "zstd tiles are in memory");
+        assert_eq!(tile.resolution(), 3);
+        assert_eq!(tile.polygon(), original.polygon());
+        assert!(tile
+            .iter()
+            .map(|s| s.elevation())
+            .eq(original.iter().map(|s| s.elevation())));
+    }
+
+    let corrupt = dir.path().join("N45W072.hgt.zst");
+    std::fs::write(&corrupt, b"not zstd").unwrap();
+    assert!(matches!(
+        Tile::load_zstd(&corrupt),
+        Err(NasademError::Decompress(_))
+    ));
+}
+
+#[test]
+fn test_get_xy_perimeter() {
+    let tile = Tile::load(three_arcsecond_dir().join("N44W072.hgt")).unwrap();
+    let (cols, rows) = tile.dimensions();
+    let (east, south) = (cols - 1, rows - 1);
+    let perimeter = (0..cols)
+        .flat_map(|x| [(x, 0), (x, south)])
+        .chain((0..rows).flat_map(|y| [(0, y), (east, y)]));
+    for (x, y) in perimeter {
+        assert_eq!(
+            tile.get((x, y)),
+            tile.get(y * cols + x),
+            "({x}, {y}) is on the tile"
+        );
+    }
+    // Just past the east and south edges, including where `x * y`
+    // is small.
+    for (x, y) in [(cols, 0), (0, rows), (cols, 1), (1, rows), (cols, rows)] {
+        assert_eq!(tile.get((x, y)), None, "({x}, {y}) is off the tile");
+    }
+}
+
+#[test]
+fn test_row_slice() {
+    let mut path = three_arcsecond_dir();
+    path.push("N44W072.hgt");
+    let tile = Tile::load(&path).unwrap();
+    let (cols, rows) = tile.dimensions();
+
+    let nw_row = tile.row_slice(0).unwrap();
+    assert_eq!(nw_row.len(), cols);
+    for (x, &elev) in nw_row.iter().enumerate() {
+        assert_eq!(elev, tile.get_unchecked((x, 0)));
+    }
+    let south_row = tile.row_slice(rows - 1).unwrap();
+    assert_eq!(south_row[7], tile.get_unchecked((7, rows - 1)));
+    assert!(tile.row_slice(rows).is_none());
+
+    assert!(Tile::memmap(&path).unwrap().row_slice(0).is_none());
+    assert!(Tile::tombstone(Coord { x: -72, y: 44 }, 3)
+        .row_slice(0)
+        .is_none());
+}
+
+#[cfg(feature = "proj")]
+#[test]
+fn test_get_utm() {
+    use crate::Hemisphere;
+    let mut path = three_arcsecond_dir();
+    path.push("N44W072.hgt");
+    let tile = Tile::load(path).unwrap();
+    // Mt Washington summit, zone 19T.
+    let mt_washington = Coord {
+        x: -71.30325,
+        y: 44.2705,
+    };
+    let (easting, northing) = (316_178.68, 4_904_497.21);
+    assert_eq!(
+        tile.get_utm(19, Hemisphere::North, easting, northing),
+        tile.get(mt_washington)
+    );
+    assert!(tile.get(mt_washington).is_some());
+    // Same easting and northing in the southern hemisphere is
+    // nowhere near this tile.
+    assert_eq!(tile.get_utm(19, Hemisphere::South, easting, northing), None);
+    assert_eq!(tile.get_utm(0, Hemisphere::North, easting, northing), None);
+}
+
+#[cfg(feature = "nalgebra")]
+#[test]
+fn test_to_local_meter_grid() {
+    use crate::METERS_PER_DEG;
+    let mut path = three_arcsecond_dir();
+    path.push("N44W072.hgt");
+    let tile = Tile::load(path).unwrap();
+    let spacing_m = 250.0;
+    let (grid, origin) = tile.to_local_meter_grid(spacing_m);
+    assert_eq!(origin, Coord { x: -71.5, y: 44.5 });
+
+    let (rows, cols) = grid.shape();
+    let expected_rows = METERS_PER_DEG / spacing_m;
+    let expected_cols = expected_rows * 44.5_f64.to_radians().cos();
+    assert!((rows as f64 - expected_rows).abs() <= 1.0, "{rows}");
+    assert!((cols as f64 - expected_cols).abs() <= 1.0, "{cols}");
+    assert_eq!(grid[(rows / 2, cols / 2)], tile.get(origin).unwrap());
+    assert!(grid.iter().all(|&elev| elev != crate::VOID));
+}
+
+#[test]
+fn test_geo_to_xy_half_sample_ties() {
+    let mut path = three_arcsecond_dir();
+    path.push("N44W072.hgt");
+    let tile = Tile::load(path).unwrap();
+    let deg_per_sample = 3.0 / 3600.0;
+    // Exactly between columns 10 and 11 and between rows 20 and 21
+    // snaps east and south.
+    let tie = Coord {
+        x: -72.0 + 10.5 * deg_per_sample,
+        y: 45.0 - 20.5 * deg_per_sample,
+    };
+    assert_eq!(tile.geo_to_xy(tie), (11, 21));
+    assert_eq!(tile.get(tie), tile.get((11, 21)));
+    // Just shy of the tie snaps to the nearer sample.
+    let near = Coord {
+        x: tie.x - 1e-6,
+        y: tie.y + 1e-6,
+    };
+    assert_eq!(tile.geo_to_xy(near), (10, 20));
+    // Half a sample outside the west and north edges snaps onto the
+    // tile, while half a sample outside the east and south edges
+    // falls off it.
+    let nw_tie = Coord {
+        x: -72.0 - 0.5 * deg_per_sample,
+        y: 45.0 + 0.5 * deg_per_sample,
+    };
+    assert_eq!(tile.geo_to_xy(nw_tie), (0, 0));
+    assert_eq!(tile.get(nw_tie), tile.get((0, 0)));
+    let se_tie = Coord {
+        x: -71.0 + 0.5 * deg_per_sample,
+        y: 44.0 - 0.5 * deg_per_sample,
+    };
+    assert_eq!(tile.geo_to_xy(se_tie), (1201, 1201));
+    assert_eq!(tile.get(se_tie), None);
+}
+
+#[test]
+fn test_geo_to_xy_frac() {
+    let mut path = three_arcsecond_dir();
+    path.push("N44W072.hgt");
+    let tile = Tile::load(path).unwrap();
+    let deg_per_sample = 3.0 / 3600.0;
+    let (x, y) = tile.geo_to_xy_frac(Coord {
+        x: -72.0 + 10.25 * deg_per_sample,
+        y: 45.0 - 20.75 * deg_per_sample,
+    });
+    assert!((x - 10.25).abs() < 1e-9, "{x}");
+    assert!((y - 20.75).abs() < 1e-9, "{y}");
+    assert_eq!(tile.geo_to_xy_frac(Coord { x: -72.0, y: 45.0 }), (0.0, 0.0));
+    let (x, y) = tile.geo_to_xy_frac(Coord { x: -71.0, y: 44.0 });
+    assert!((x - 1200.0).abs() < 1e-9 && (y - 1200.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_get_geo_interpolated() {
+    let mut path = three_arcsecond_dir();
+    path.push("N44W072.hgt");
+    let tile = Tile::load(path).unwrap();
+    let deg_per_sample = 3.0 / 3600.0;
+    let elev = |xy| f64::from(tile.get(xy).unwrap());
+
+    // A quarter of the way from column 10 to 11 in row 20.
+    let between = Coord {
+        x: -72.0 + 10.25 * deg_per_sample,
+        y: 45.0 - 20.0 * deg_per_sample,
+    };
+    let expected = 0.75 * elev((10, 20)) + 0.25 * elev((11, 20));
+    let interpolated = tile.get_geo_interpolated(between).unwrap();
+    assert!((interpolated - expected).abs() < 1e-6, "{interpolated}");
+
+    // The last row and column, and the SE corner, have no samples
+    // past them to interpolate toward.
+    let se = Coord { x: -71.0, y: 44.0 };
+    let interpolated = tile.get_geo_interpolated(se).unwrap();
+    assert!((interpolated - elev((1200, 1200))).abs() < 1e-6);
+    let east_edge = Coord {
+        x: -71.0,
+        y: 45.0 - 20.5 * deg_per_sample,
+    };
+    let expected = (elev((1200, 20)) + elev((1200, 21))) / 2.0;
+    let interpolated = tile.get_geo_interpolated(east_edge).unwrap();
+    assert!((interpolated - expected).abs() < 1e-6, "{interpolated}");
+
+    assert_eq!(tile.get_geo_interpolated(Coord { x: -70.9, y: 44.5 }), None);
+    assert_eq!(tile.get_geo_interpolated(Coord { x: -71.5, y: 43.9 }), None);
+}
+
+#[test]
+fn test_geo_to_xy_mode() {
+    let mut path = three_arcsecond_dir();
+    path.push("N44W072.hgt");
+    let tile = Tile::load(path).unwrap();
+    let deg_per_sample = 3.0 / 3600.0;
+    // 70% of the way from column 10 to 11 and from row 20 to 21.
+    let between = Coord {
+        x: -72.0 + 10.7 * deg_per_sample,
+        y: 45.0 - 20.7 * deg_per_sample,
+    };
+    assert_eq!(
+        tile.geo_to_xy_mode(between, SnapMode::Nearest),
+        Some((11, 21))
+    );
+    assert_eq!(
+        tile.geo_to_xy_mode(between, SnapMode::Floor),
+        Some((10, 20))
+    );
+    assert_eq!(tile.geo_to_xy_mode(between, SnapMode::Ceil), Some((11, 21)));
+    // A sample center snaps to itself in every mode.
+    let center = tile.sample((10, 20)).unwrap().geo();
+    for mode in [SnapMode::Nearest, SnapMode::Floor, SnapMode::Ceil] {
+        assert_eq!(tile.geo_to_xy_mode(center, mode), Some((10, 20)));
+    }
+    // Just outside the NW corner floors off the tile but ceils onto
+    // it.
+    let nw = Coord {
+        x: -72.0 - 0.2 * deg_per_sample,
+        y: 45.0 + 0.2 * deg_per_sample,
+    };
+    assert_eq!(tile.geo_to_xy_mode(nw, SnapMode::Floor), None);
+    assert_eq!(tile.geo_to_xy_mode(nw, SnapMode::Ceil), Some((0, 0)));
+}
+
+#[test]
+fn test_concat() {
+    use crate::{Elev, NasademError};
+    let mut path = three_arcsecond_dir();
+    path.push("N44W072.hgt");
+    let tile = Tile::load(path).unwrap();
+    let dim = 1201;
+
+    // Synthetic neighbors whose shared edge matches the fixture.
+    let east = Tile::from_samples(
+        Coord { x: -71, y: 44 },
+        3,
+        (dim, dim),
+        (0..dim * dim)
+            .map(|idx| {
+                let (x, y) = (idx % dim, idx / dim);
+                if x == 0 {
+                    tile.get((dim - 1, y)).unwrap()
+                } else {
+                    Elev::try_from(x).unwrap()
+                }
+            })
+            .collect(),
+    );
+    let south = Tile::from_samples(
+        Coord { x: -72, y: 43 },
+        3,
+        (dim, dim),
+        (0..dim * dim)
+            .map(|idx| {
+                let (x, y) = (idx % dim, idx / dim);
+                if y == 0 {
+                    tile.get((x, dim - 1)).unwrap()
+                } else {
+                    -Elev::try_from(y).unwrap()
+                }
+            })
+            .collect(),
+    );
+
+    let wide = tile.concat_horizontal(&east).unwrap();
+    assert_eq!(wide.dimensions(), (2 * dim - 1, dim));
+    assert_eq!(wide.get((100, 200)), tile.get((100, 200)));
+    assert_eq!(wide.get((dim - 1 + 5, 7)), east.get((5, 7)));
+    assert_eq!(wide.get((dim - 1 + 5, 7)), Some(5));
+    let mt_washington = Coord {
+        x: -71.30325,
+        y: 44.2705,
+    };
+    assert_eq!(wide.get(mt_washington), tile.get(mt_washington));
+    let east_coord = Coord { x: -70.5, y: 44.5 };
+    assert_eq!(wide.get(east_coord), east.get(east_coord));
+
+    let tall = tile.concat_vertical(&south).unwrap();
+    assert_eq!(tall.dimensions(), (dim, 2 * dim - 1));
+    assert_eq!(tall.get((100, 200)), tile.get((100, 200)));
+    assert_eq!(tall.get((7, dim - 1 + 5)), Some(-5));
+    assert_eq!(tall.get(mt_washington), tile.get(mt_washington));
+    let south_coord = Coord { x: -71.5, y: 43.5 };
+    assert_eq!(tall.get(south_coord), south.get(south_coord));
+
+    assert!(matches!(
+        tile.concat_horizontal(&tile),
+        Err(NasademError::NotAdjacent)
+    ));
+    assert!(matches!(
+        east.concat_horizontal(&tile),
+        Err(NasademError::NotAdjacent)
+    ));
+    assert!(matches!(
+        tile.concat_vertical(&east),
+        Err(NasademError::NotAdjacent)
+    ));
+    let coarse = tile.downsample(2);
+    assert!(matches!(
+        tile.concat_horizontal(&coarse),
+        Err(NasademError::IncompatibleGrids)
+    ));
+}
+
+#[test]
+fn test_memmap_cow_set() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("N44W072.hgt");
+    std::fs::copy(three_arcsecond_dir().join("N44W072.hgt"), &path).unwrap();
+    let original = std::fs::read(&path).unwrap();
+
+    let mut tile = Tile::memmap_cow(&path).unwrap();
+    let before = tile.get((600, 600)).unwrap();
+    assert_eq!(tile.max_elevation(), 1908);
+    tile.set((600, 600), 9000).unwrap();
+    assert_eq!(tile.get((600, 600)), Some(9000));
+    assert_eq!(tile.max_elevation(), 9000);
+    assert_ne!(before, 9000);
+
+    drop(tile);
+    assert_eq!(std::fs::read(&path).unwrap(), original);
+    assert_eq!(Tile::memmap(&path).unwrap().get((600, 600)), Some(before));
+}
+
+#[test]
+fn test_memmap_set_is_read_only() {
+    let mut tile = Tile::memmap(three_arcsecond_dir().join("N44W072.hgt")).unwrap();
+    assert!(matches!(tile.set(0, 0), Err(NasademError::ReadOnly)));
+}
+
+#[test]
+fn test_memmap_fill_voids() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("N44W072.hgt");
+    let mut raw = 100_i16.to_be_bytes().repeat(1201 * 1201);
+    raw[..2].copy_from_slice(&crate::VOID.to_be_bytes());
+    std::fs::write(&path, &raw).unwrap();
+
+    let mut tile = Tile::memmap(&path).unwrap();
+    assert_eq!(tile.fill_voids(1), Some(1));
+    assert_eq!(tile.get(0), Some(100));
+    // The tile was copied into memory, leaving the file untouched.
+    assert_eq!(std::fs::read(&path).unwrap(), raw);
+}
+
+#[test]
+fn test_row_latitude() {
+    use approx::assert_relative_eq;
+    let tile = Tile::memmap(three_arcsecond_dir().join("N44W072.hgt")).unwrap();
+    let (_, rows) = tile.dimensions();
+    assert_relative_eq!(tile.row_latitude(0), 45.0);
+    assert_relative_eq!(tile.row_latitude(rows / 2), 44.5);
+    assert_relative_eq!(tile.row_latitude(rows - 1), 44.0);
+    assert_relative_eq!(tile.row_latitude(7), tile.sample((123, 7)).unwrap().geo().y);
+}
+
+#[cfg(feature = "tar")]
+#[test]
+fn test_tar_tile_source() {
+    use crate::TarTileSource;
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let tmp = tempfile::tempdir().unwrap();
+    let tar_path = tmp.path().join("tiles.tar");
+    {
+        let mut builder = tar::Builder::new(File::create(&tar_path).unwrap());
+        builder
+            .append_path_with_name(
+                three_arcsecond_dir()
+                    .join("..")
+                    .join("1arcsecond")
+                    .join("README.md"),
+                "nasadem/README.md",
+            )
+            .unwrap();
+        builder
+            .append_path_with_name(&src, "nasadem/N44W072.hgt")
+            .unwrap();
+        builder.finish().unwrap();
+    }
+
+    let tiles = TarTileSource::open(&tar_path).unwrap();
+    assert_eq!(tiles.len(), 1);
+    assert!(tiles.contains(Coord { x: -72, y: 44 }));
+
+    let from_tar = tiles.tile(Coord { x: -72, y: 44 }).unwrap();
+    let standalone = Tile::load(&src).unwrap();
+    assert_eq!(from_tar.dimensions(), standalone.dimensions());
+    assert_eq!(from_tar.resolution(), standalone.resolution());
+    assert_eq!(from_tar.into_raw(), standalone.into_raw());
+
+    assert!(matches!(
+        tiles.tile(Coord { x: 0, y: 0 }),
+        Err(NasademError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound
+    ));
+}
+
+#[test]
+fn test_crop_memmap() {
+    use crate::geo::Rect;
+    let path = three_arcsecond_dir().join("N44W072.hgt");
+    let tile = Tile::memmap(&path).unwrap();
+    // Around Mt Washington.
+    let bbox = Rect::new(Coord { x: -71.4, y: 44.2 }, Coord { x: -71.2, y: 44.35 });
+    let crop = tile.crop(bbox).unwrap();
+    assert_eq!(crop.dimensions(), (241, 181));
+    assert!(!crop.is_tombstone());
+    assert_eq!(Some(crop.elevation_range()), tile.elevation_range_in(bbox));
+    for sample in crop.iter() {
+        assert_eq!(Some(sample.elevation()), tile.get(sample.geo()));
+    }
+}
+
+#[test]
+fn test_extents() {
+    use approx::assert_relative_eq;
+    use geo::BoundingRect;
+    let tile = Tile::load(three_arcsecond_dir().join("N44W072.hgt")).unwrap();
+
+    // Sample centers span the whole degree exactly.
+    let centers = tile.extent_centers();
+    assert_relative_eq!(centers.min().x, -72.0, epsilon = 1e-9);
+    assert_relative_eq!(centers.min().y, 44.0, epsilon = 1e-9);
+    assert_relative_eq!(centers.max().x, -71.0, epsilon = 1e-9);
+    assert_relative_eq!(centers.max().y, 45.0, epsilon = 1e-9);
+
+    // Pixel areas reach half a sample further on every side.
+    let half_sample = 1.5 / 3600.0;
+    let pixels = tile.extent_pixels();
+    for padding in [centers.min() - pixels.min(), pixels.max() - centers.max()] {
+        assert_relative_eq!(padding.x, half_sample, epsilon = 1e-12);
+        assert_relative_eq!(padding.y, half_sample, epsilon = 1e-12);
+    }
+
+    assert_eq!(tile.polygon().bounding_rect(), Some(pixels));
+}
+
+#[test]
+fn test_transcode() {
+    use crate::{TranscodeOptions, VOID};
+    let src = three_arcsecond_dir().join("N44W072.hgt");
+    let dir = tempfile::tempdir().unwrap();
+
+    // The defaults copy the tile unchanged.
+    let copy = dir.path().join("N44W072.hgt");
+    Tile::transcode(&src, &copy, TranscodeOptions::default()).unwrap();
+    assert_eq!(std::fs::read(&copy).unwrap(), std::fs::read(&src).unwrap());
+
+    // Punch a void, then fill it and downsample to 6 arcseconds.
+    let mut voided = Tile::load(&src).unwrap();
+    voided.set((600, 600), VOID).unwrap();
+    let voided_path = dir.path().join("voided").join("N44W072.hgt");
+    std::fs::create_dir(voided_path.parent().unwrap()).unwrap();
+    voided.save(&voided_path).unwrap();
+    let out_dir = dir.path().join("out");
+    std::fs::create_dir(&out_dir).unwrap();
+    let options = TranscodeOptions {
+        fill_voids: Some(2),
+        downsample: Some(2),
+        ..TranscodeOptions::default()
+    };
+    Tile::transcode(&voided_path, &out_dir, options).unwrap();
+    let tile = Tile::load(out_dir.join("N44W072.hgt")).unwrap();
+    assert_eq!((tile.resolution(), tile.dimensions()), (6, (601, 601)));
+    assert_eq!(tile.fold(0, |n, elev| n + usize::from(elev == VOID)), 0);
+    voided.fill_voids(2);
+    assert!(tile
+        .iter()
+        .map(|s| s.elevation())
+        .eq(voided.downsample(2).iter().map(|s| s.elevation())));
+
+    // A plain tile to a downsampled compressed one.
+    #[cfg(feature = "zstd")]
+    {
+        use crate::Compression;
+        let options = TranscodeOptions {
+            downsample: Some(2),
+            compression: Compression::Zstd,
+            ..TranscodeOptions::default()
+        };
+        Tile::transcode(&src, dir.path(), options).unwrap();
+        let path = dir.path().join("N44W072.hgt.zst");
+        let tile = Tile::load(&path).unwrap();
+        assert_eq!((tile.resolution(), tile.dimensions()), (6, (601, 601)));
+        assert!(tile.iter().map(|s| s.elevation()).eq(Tile::load(&src)
+            .unwrap()
+            .downsample(2)
+            .iter()
+            .map(|s| s.elevation())));
+    }
+
+    #[cfg(feature = "lz4")]
+    {
+        use crate::Compression;
+        let options = TranscodeOptions {
+            downsample: Some(2),
+            compression: Compression::Lz4,
+            ..TranscodeOptions::default()
+        };
+        Tile::transcode(&src, dir.path(), options).unwrap();
+        let path = dir.path().join("N44W072.hgt.lz4");
+        let tile = Tile::load(&path).unwrap();
+        assert_eq!((tile.resolution(), tile.dimensions()), (6, (601, 601)));
+        assert!(tile.iter().map(|s| s.elevation()).eq(Tile::load(&src)
+            .unwrap()
+            .downsample(2)
+            .iter()
+            .map(|s| s.elevation())));
+    }
+}
+
+#[test]
+fn test_iter_cells_in() {
+    use geo::{Contains, Rect};
+    let tile = Tile::memmap(three_arcsecond_dir().join("N44W072.hgt")).unwrap();
+    let rect = Rect::new(
+        Coord {
+            x: -71.31,
+            y: 44.26,
+        },
+        Coord { x: -71.3, y: 44.28 },
+    );
+
+    let (nw_x, nw_y) = tile
+        .geo_to_xy_mode(
+            Coord {
+                x: -71.31,
+                y: 44.28,
+            },
+            SnapMode::Ceil,
+        )
+        .unwrap();
+    let nw = tile.sample((nw_x, nw_y)).unwrap();
+    let mut cells = tile.iter_cells_in(rect);
+    assert_eq!(cells.next(), Some((nw.polygon(), nw.elevation())));
+
+    // 0.01° by 0.02° at 1200 samples per degree, with both edges
+    // lying on samples.
+    let (se_x, se_y) = tile
+        .geo_to_xy_mode(Coord { x: -71.3, y: 44.26 }, SnapMode::Floor)
+        .unwrap();
+    assert_eq!((se_x - nw_x + 1, se_y - nw_y + 1), (13, 25));
+    assert_eq!(tile.iter_cells_in(rect).count(), 13 * 25);
+    let block = (nw_y..=se_y).flat_map(|y| (nw_x..=se_x).map(move |x| (x, y)));
+    for ((polygon, elev), xy) in tile.iter_cells_in(rect).zip(block) {
+        let sample = tile.sample(xy).unwrap();
+        assert!(polygon.contains(&sample.geo()));
+        assert_eq!(elev, sample.elevation());
+    }
+
+    let off_tile = Rect::new(Coord { x: -70.5, y: 44.5 }, Coord { x: -70.4, y: 44.6 });
+    assert_eq!(tile.iter_cells_in(off_tile).count(), 0);
+}
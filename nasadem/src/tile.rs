@@ -1,17 +1,25 @@
 use crate::{
-    geo::{polygon, Coord, Polygon},
+    geo::{polygon, Coord, Polygon, Rect},
     store::SampleStore,
-    util, Elev, NasademError, Sample, ARCSEC_PER_DEG, C, HALF_ARCSEC,
+    util, Elev, NasademError, Sample, TileQueryError, ARCSEC_PER_DEG, C, HALF_ARCSEC,
+    METERS_PER_DEG, VOID,
 };
-use memmap2::Mmap;
+use flate2::read::GzDecoder;
+use memmap2::{Mmap, MmapOptions};
+#[cfg(feature = "rayon")]
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 use std::{
     fmt,
     fs::File,
-    io::BufReader,
+    io::{BufReader, Read},
     path::Path,
-    sync::atomic::{AtomicI16, Ordering},
+    sync::OnceLock,
 };
 
+/// Distance, in samples, within which a coordinate is considered to
+/// lie exactly halfway between two samples.
+const TIE_EPSILON: C = 1e-9;
+
 /// A NASADEM tile.
 pub struct Tile {
     /// Southwest corner of the tile.
@@ -30,11 +38,9 @@ pub struct Tile {
     /// Number of (rows, columns) in this tile.
     dimensions: (usize, usize),
 
-    /// Lowest elevation sample in this tile.
-    min_elevation: AtomicI16,
-
-    /// Highest elevation sample in this tile.
-    max_elevation: AtomicI16,
+    /// Lowest and highest elevation samples in this tile, computed
+    /// together on first use.
+    elevation_range: OnceLock<(Elev, Elev)>,
 
     /// Elevation samples.
     pub(crate) samples: SampleStore,
@@ -42,7 +48,16 @@ pub struct Tile {
 
 impl Tile {
     /// Returns a Tile read into memory from the file at `path`.
+    ///
+    /// A path with a `.gz` extension is decompressed as gzip, and the
+    /// tile's resolution inferred from the decompressed sample count.
+    /// With the `zip`, `lz4`, and `zstd` features, paths with `.zip`,
+    /// `.lz4`, and `.zst` extensions are read with [`Tile::load_zip`],
+    /// [`Tile::load_lz4`], and [`Tile::load_zstd`].
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, NasademError> {
+        if let Some(tile) = Self::load_compressed(&path) {
+            return tile;
+        }
         let (resolution, dimensions @ (cols, rows)) = util::extract_resolution(&path)?;
         let sw_corner_center = {
             let Coord { x, y } = util::parse_sw_corner(&path)?;
@@ -72,65 +87,177 @@ impl Tile {
             SampleStore::InMem(sample_store.into_boxed_slice())
         };
 
-        let min_elevation = Elev::MAX.into();
-        let max_elevation = Elev::MAX.into();
+        let elevation_range = OnceLock::new();
 
         Ok(Self {
             sw_corner_center,
             ne_corner_center,
             resolution,
             dimensions,
-            min_elevation,
-            max_elevation,
+            elevation_range,
             samples,
         })
     }
 
-    /// Returns a Tile using the memory-mapped file as storage.
-    pub fn memmap<P: AsRef<Path>>(path: P) -> Result<Self, NasademError> {
-        let (resolution, dimensions) = util::extract_resolution(&path)?;
-        let sw_corner_center = {
-            let Coord { x, y } = util::parse_sw_corner(&path)?;
-            Coord {
-                x: C::from(x),
-                y: C::from(y),
-            }
-        };
+    /// Returns the tile in the compressed file at `path`, or `None` if
+    /// its extension isn't that of a supported compression format.
+    fn load_compressed<P: AsRef<Path>>(path: P) -> Option<Result<Self, NasademError>> {
+        if util::is_gzip(&path) {
+            return Some(Self::load_gzip(path));
+        }
+        #[cfg(feature = "zip")]
+        if util::has_extension(&path, "zip") {
+            return Some(Self::load_zip(path));
+        }
+        #[cfg(feature = "lz4")]
+        if util::has_extension(&path, "lz4") {
+            return Some(Self::load_lz4(path));
+        }
+        #[cfg(feature = "zstd")]
+        if util::has_extension(&path, "zst") {
+            return Some(Self::load_zstd(path));
+        }
+        None
+    }
 
-        #[allow(clippy::cast_precision_loss)]
-        let ne_corner_center = Coord {
-            y: sw_corner_center.y as C + 1.0,
-            x: sw_corner_center.x as C + 1.0,
-        };
+    /// Returns an in-memory tile from decompressed `.hgt` bytes,
+    /// inferring its resolution from their length.
+    ///
+    /// `path` names the source in [`NasademError::HgtLen`] errors.
+    fn from_hgt_bytes(
+        sw_corner: Coord<i16>,
+        raw: &[u8],
+        path: &Path,
+    ) -> Result<Self, NasademError> {
+        let (resolution, dimensions) = util::resolution_for_len(raw.len() as u64)
+            .ok_or_else(|| NasademError::HgtLen(raw.len() as u64, path.to_path_buf()))?;
+        let samples = raw.chunks_exact(2).map(util::parse_sample).collect();
+        Ok(Self::from_samples(
+            sw_corner, resolution, dimensions, samples,
+        ))
+    }
 
-        let samples = {
-            let file = File::open(path)?;
-            let mmap = unsafe { Mmap::map(&file)? };
-            SampleStore::MemMap(mmap)
-        };
+    /// Returns a Tile decompressed into memory from the gzip file at
+    /// `path`.
+    fn load_gzip<P: AsRef<Path>>(path: P) -> Result<Self, NasademError> {
+        let sw_corner = util::parse_sw_corner(&path)?;
+        let mut raw = Vec::new();
+        GzDecoder::new(BufReader::new(File::open(&path)?))
+            .read_to_end(&mut raw)
+            .map_err(NasademError::Decompress)?;
+        Self::from_hgt_bytes(sw_corner, &raw, path.as_ref())
+    }
 
-        let min_elevation = Elev::MAX.into();
-        let max_elevation = Elev::MAX.into();
+    /// Returns a Tile decompressed into memory from the LZ4 frame
+    /// file at `path`, e.g. `N44W072.hgt.lz4` as written by
+    /// [`Tile::save_lz4`].
+    ///
+    /// LZ4 decompresses several times faster than gzip, at the cost
+    /// of larger files. The tile's resolution is inferred from the
+    /// decompressed sample count.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NasademError::Decompress`] if the file isn't a valid
+    /// LZ4 frame.
+    #[cfg(feature = "lz4")]
+    pub fn load_lz4<P: AsRef<Path>>(path: P) -> Result<Self, NasademError> {
+        let sw_corner = util::parse_sw_corner(&path)?;
+        let mut raw = Vec::new();
+        lz4_flex::frame::FrameDecoder::new(BufReader::new(File::open(&path)?))
+            .read_to_end(&mut raw)
+            .map_err(NasademError::Decompress)?;
+        Self::from_hgt_bytes(sw_corner, &raw, path.as_ref())
+    }
 
-        Ok(Self {
-            sw_corner_center,
-            ne_corner_center,
-            resolution,
-            dimensions,
-            min_elevation,
-            max_elevation,
-            samples,
-        })
+    /// Returns a Tile decompressed into memory from the Zstandard
+    /// file at `path`, e.g. `N44W072.hgt.zst` as written by
+    /// [`Tile::save_zstd`].
+    ///
+    /// Zstandard files are about as small as gzip's and decompress
+    /// several times faster. The tile's resolution is inferred from
+    /// the decompressed sample count.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NasademError::Decompress`] if the file isn't valid
+    /// Zstandard.
+    #[cfg(feature = "zstd")]
+    pub fn load_zstd<P: AsRef<Path>>(path: P) -> Result<Self, NasademError> {
+        let sw_corner = util::parse_sw_corner(&path)?;
+        let raw = zstd::stream::decode_all(BufReader::new(File::open(&path)?))
+            .map_err(NasademError::Decompress)?;
+        Self::from_hgt_bytes(sw_corner, &raw, path.as_ref())
+    }
+
+    /// Returns a Tile read into memory from the first `.hgt` entry of
+    /// the zip archive at `path`, as distributed by USGS and NASA
+    /// Earthdata.
+    ///
+    /// The SW corner is parsed from the entry's name, not the
+    /// archive's, as archives are often named differently, e.g.
+    /// `NASADEM_HGT_n44w072.zip`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NasademError::HgtName`] if the archive has no `.hgt`
+    /// entry or its name is malformed, and
+    /// [`NasademError::Decompress`] if the archive is corrupt.
+    #[cfg(feature = "zip")]
+    pub fn load_zip<P: AsRef<Path>>(path: P) -> Result<Self, NasademError> {
+        let path = path.as_ref();
+        let mut archive = zip::ZipArchive::new(BufReader::new(File::open(path)?))
+            .map_err(|err| NasademError::Decompress(err.into()))?;
+        let entry_name = archive
+            .file_names()
+            .find(|name| util::has_extension(name, "hgt"))
+            .map(str::to_owned)
+            .ok_or_else(|| NasademError::HgtName(path.to_path_buf()))?;
+        let sw_corner = util::parse_sw_corner(&entry_name)?;
+        let mut entry = archive
+            .by_name(&entry_name)
+            .map_err(|err| NasademError::Decompress(err.into()))?;
+        #[allow(clippy::cast_possible_truncation)]
+        let mut raw = Vec::with_capacity(entry.size() as usize);
+        entry
+            .read_to_end(&mut raw)
+            .map_err(NasademError::Decompress)?;
+        Self::from_hgt_bytes(sw_corner, &raw, &path.join(&entry_name))
+    }
+
+    /// Returns a Tile using the memory-mapped file as storage.
+    ///
+    /// Compressed files, e.g. `.hgt.gz`, can't be mapped, and are
+    /// instead [loaded](Self::load) into memory.
+    pub fn memmap<P: AsRef<Path>>(path: P) -> Result<Self, NasademError> {
+        Self::map_file(path, false)
+    }
+
+    /// Returns a Tile using a private, copy-on-write memory mapping
+    /// of the file as storage.
+    ///
+    /// Like [`Tile::memmap`], samples are paged in from the file on
+    /// demand, but this tile can also be edited with [`Tile::set`].
+    /// Edited pages are copied into anonymous memory; the file
+    /// itself is never modified. As with [`Tile::memmap`],
+    /// compressed files are loaded into memory instead.
+    pub fn memmap_cow<P: AsRef<Path>>(path: P) -> Result<Self, NasademError> {
+        Self::map_file(path, true)
     }
 
     /// Returns a virtual tile that always with no elevation.
     ///
     /// A tombstone is handy when dealing with voids in SRTM coverage,
     /// e.g. oceans.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `arcsec_per_sample` does not evenly divide a degree,
+    /// the same resolutions [`Tile::load`] accepts.
     pub fn tombstone(sw_corner: Coord<i16>, arcsec_per_sample: u8) -> Self {
         assert!(
-            arcsec_per_sample == 1 || arcsec_per_sample == 3,
-            "only resolution of 1 or 3 arcsecs per sample"
+            arcsec_per_sample != 0 && 3600 % u16::from(arcsec_per_sample) == 0,
+            "{arcsec_per_sample} arcsecs per sample does not evenly divide a degree"
         );
         let sw_corner_center = Coord {
             x: C::from(sw_corner.x),
@@ -148,25 +275,235 @@ impl Tile {
         };
 
         let samples = SampleStore::Tombstone(dim * dim);
-        let min_elevation = 0.into();
-        let max_elevation = 0.into();
+        let elevation_range = OnceLock::from((0, 0));
 
         Self {
             sw_corner_center,
             ne_corner_center,
             resolution,
             dimensions,
-            min_elevation,
-            max_elevation,
+            elevation_range,
+            samples,
+        }
+    }
+
+    /// Returns an in-memory tile built from raw elevation samples.
+    ///
+    /// `samples` are in row-major order starting from the NW corner,
+    /// and `dimensions` is the tile's `(cols, rows)`. Unlike tiles
+    /// loaded from disk, the tile need not span a full degree; its
+    /// extent is derived from `dimensions` and `arcsec_per_sample`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples.len()` does not equal `cols * rows`.
+    pub fn from_samples(
+        sw_corner: Coord<i16>,
+        arcsec_per_sample: u8,
+        dimensions: (usize, usize),
+        samples: Vec<Elev>,
+    ) -> Self {
+        let sw_corner_center = Coord {
+            x: C::from(sw_corner.x),
+            y: C::from(sw_corner.y),
+        };
+        Self::from_samples_at(sw_corner_center, arcsec_per_sample, dimensions, samples)
+    }
+
+    /// Returns an in-memory tile as [`Tile::from_samples`] does, but
+    /// with its SW sample centered on `sw_corner_center`, which need
+    /// not lie on a whole degree, e.g. for a [`Tile::crop`]ped tile.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples.len()` does not equal `cols * rows`.
+    pub(crate) fn from_samples_at(
+        sw_corner_center: Coord<C>,
+        arcsec_per_sample: u8,
+        dimensions: (usize, usize),
+        samples: Vec<Elev>,
+    ) -> Self {
+        let (cols, rows) = dimensions;
+        assert_eq!(
+            samples.len(),
+            cols * rows,
+            "sample count does not match dimensions {dimensions:?}"
+        );
+
+        let deg_per_sample = C::from(arcsec_per_sample) / ARCSEC_PER_DEG;
+        #[allow(clippy::cast_precision_loss)]
+        let ne_corner_center = Coord {
+            y: sw_corner_center.y + (rows - 1) as C * deg_per_sample,
+            x: sw_corner_center.x + (cols - 1) as C * deg_per_sample,
+        };
+
+        let samples = SampleStore::InMem(samples.into_boxed_slice());
+        let elevation_range = OnceLock::new();
+
+        Self {
+            sw_corner_center,
+            ne_corner_center,
+            resolution: arcsec_per_sample,
+            dimensions,
+            elevation_range,
             samples,
         }
     }
 
+    /// Returns this tile's samples without copying, if it holds them
+    /// in memory.
+    ///
+    /// Samples are in row-major order starting from the NW corner:
+    /// the sample at `(x, y)`, `x` columns east and `y` rows south of
+    /// the NW corner, is at index `y * cols + x`, matching
+    /// [`Sample::index`] and [`Tile::from_samples`].
+    ///
+    /// Returns `None` for memory-mapped tiles, whose samples are
+    /// big-endian bytes in the file, and tombstones, which have no
+    /// samples; read those with [`Tile::fold`] or
+    /// [`Tile::into_raw`] instead.
+    pub fn as_slice(&self) -> Option<&[Elev]> {
+        self.samples.as_slice()
+    }
+
+    /// Consumes the tile, returning its samples, columns, rows,
+    /// resolution in arcseconds per sample, and SW corner.
+    ///
+    /// Samples are in row-major order starting from the NW corner,
+    /// exactly as expected by [`Tile::from_samples`]. Memory-mapped
+    /// and tombstone tiles are materialized into a new vector, while
+    /// in-memory tiles hand over their samples without copying.
+    ///
+    /// The SW corner is the whole degree holding the SW sample, so a
+    /// tile whose samples aren't aligned to whole degrees, e.g. one
+    /// from [`Tile::crop`], is rebuilt at that degree instead.
+    pub fn into_raw(self) -> (Vec<Elev>, usize, usize, u8, Coord<i16>) {
+        let (cols, rows) = self.dimensions;
+        let sw_corner = self.sw_corner();
+        (
+            self.samples.into_vec(),
+            cols,
+            rows,
+            self.resolution,
+            sw_corner,
+        )
+    }
+
+    /// Writes this tile to `path` as an `.hgt` file of big-endian
+    /// samples in row-major order from the NW corner, as read by
+    /// [`Tile::load`].
+    ///
+    /// If `path` is a directory, the file is written inside it and
+    /// named after the tile's SW corner (see [`filename_for`]), so
+    /// it can be loaded back by name.
+    ///
+    /// [`filename_for`]: crate::filename_for
+    /// [`extract_resolution`]: crate::extract_resolution
+    ///
+    /// # Errors
+    ///
+    /// - [`NasademError::HgtLen`] if this tile isn't a full-degree
+    ///   tile at a resolution [`extract_resolution`] recognizes, and
+    ///   so could not be loaded back.
+    /// - [`NasademError::Io`] if the file can't be written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), NasademError> {
+        let path = self.save_path(path.as_ref(), "");
+        let bytes = self.to_hgt_bytes(&path)?;
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// Writes this tile to `path` as an LZ4 frame compressed `.hgt`
+    /// file, as read by [`Tile::load_lz4`].
+    ///
+    /// As with [`Tile::save`], if `path` is a directory the file is
+    /// written inside it, here named e.g. `N44W072.hgt.lz4`.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Tile::save`].
+    #[cfg(feature = "lz4")]
+    pub fn save_lz4<P: AsRef<Path>>(&self, path: P) -> Result<(), NasademError> {
+        use std::io::Write;
+        let path = self.save_path(path.as_ref(), ".lz4");
+        let bytes = self.to_hgt_bytes(&path)?;
+        let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+        encoder.write_all(&bytes)?;
+        std::fs::write(path, encoder.finish().map_err(std::io::Error::from)?)?;
+        Ok(())
+    }
+
+    /// Writes this tile to `path` as a Zstandard compressed `.hgt`
+    /// file, as read by [`Tile::load_zstd`].
+    ///
+    /// As with [`Tile::save`], if `path` is a directory the file is
+    /// written inside it, here named e.g. `N44W072.hgt.zst`.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Tile::save`].
+    #[cfg(feature = "zstd")]
+    pub fn save_zstd<P: AsRef<Path>>(&self, path: P) -> Result<(), NasademError> {
+        let path = self.save_path(path.as_ref(), ".zst");
+        let bytes = self.to_hgt_bytes(&path)?;
+        std::fs::write(path, zstd::stream::encode_all(bytes.as_slice(), 0)?)?;
+        Ok(())
+    }
+
+    /// Returns `path`, or if it is a directory, the file within it
+    /// named after this tile's SW corner plus `suffix`.
+    fn save_path(&self, path: &Path, suffix: &str) -> std::path::PathBuf {
+        if path.is_dir() {
+            path.join(util::filename_for(self.sw_corner()) + suffix)
+        } else {
+            path.to_path_buf()
+        }
+    }
+
+    /// Returns this tile's samples as the contents of an `.hgt` file,
+    /// or [`NasademError::HgtLen`] naming `path` if they couldn't be
+    /// loaded back as this tile.
+    fn to_hgt_bytes(&self, path: &Path) -> Result<Vec<u8>, NasademError> {
+        let len = self.len() * size_of::<Elev>();
+        if util::resolution_for_len(len as u64) != Some((self.resolution, self.dimensions)) {
+            return Err(NasademError::HgtLen(len as u64, path.to_path_buf()));
+        }
+        let mut bytes = Vec::with_capacity(len);
+        self.fold((), |(), elev| bytes.extend_from_slice(&elev.to_be_bytes()));
+        Ok(bytes)
+    }
+
     /// Returns this tile's (x, y) dimensions.
     pub fn dimensions(&self) -> (usize, usize) {
         self.dimensions
     }
 
+    /// Returns the number of samples per row, i.e., the number of
+    /// columns.
+    ///
+    /// Samples are stored in row-major order starting from the NW
+    /// corner, so the linear index of column `x` in row `y` is `y *
+    /// stride + x`, as accepted by [`Tile::get`] and returned by
+    /// [`Sample::index`].
+    pub fn stride(&self) -> usize {
+        self.dimensions.0
+    }
+
+    /// Returns the latitude of the sample centers in row `y`.
+    ///
+    /// Row 0 lies on the tile's north edge and the last row on its
+    /// south edge.
+    pub fn row_latitude(&self, y: usize) -> C {
+        self.xy_to_geo((0, y)).y
+    }
+
+    /// Returns the `(east-west, north-south)` ground distance, in
+    /// meters, between adjacent samples in row `y`.
+    pub(crate) fn row_spacing_m(&self, y: usize) -> (C, C) {
+        let dy_m = C::from(self.resolution) * METERS_PER_DEG / ARCSEC_PER_DEG;
+        (dy_m * self.row_latitude(y).to_radians().cos(), dy_m)
+    }
+
     /// Returns the number of samples in this tile.
     #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
@@ -174,32 +511,70 @@ impl Tile {
         x * y
     }
 
-    /// Returns the lowest elevation sample in this tile.
+    /// Returns `true` if this is a [`Tile::tombstone`] standing in
+    /// for a tile without data.
+    pub fn is_tombstone(&self) -> bool {
+        matches!(self.samples, SampleStore::Tombstone(_))
+    }
+
+    /// Returns the raster coordinates, where (0, 0) is the NW corner,
+    /// of the sample `coord` snaps to under `mode`, or `None` if that
+    /// sample is off this tile.
+    ///
+    /// [`SnapMode::Nearest`] is what geographic indexing, e.g.
+    /// [`Tile::get`], uses.
+    pub fn geo_to_xy_mode(&self, coord: Coord<C>, mode: SnapMode) -> Option<(usize, usize)> {
+        let (x, y) = self.snap_geo_to_xy(coord, mode);
+        let (cols, rows) = self.dimensions();
+        let (x, y) = (usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+        (x < cols && y < rows).then_some((x, y))
+    }
+
+    /// Returns the approximate number of bytes this tile's samples
+    /// occupy, e.g. to bound a tile cache by memory rather than by
+    /// tile count.
+    ///
+    /// In-memory tiles report two bytes per sample. Memory-mapped
+    /// tiles report the length of the mapping, though its pages
+    /// aren't resident until touched. Tombstones report a small
+    /// constant.
+    pub fn memory_bytes(&self) -> usize {
+        self.samples.memory_bytes()
+    }
+
+    /// Returns the lowest elevation sample in this tile, ignoring
+    /// voids, or [`VOID`] if every sample is void.
     pub fn min_elevation(&self) -> Elev {
-        let mut min_elevation = self.min_elevation.load(Ordering::Relaxed);
-        // This block can race (not data-race), but it's fine because
-        // it's unlikely to happen very often if at all, and min elev
-        // is min elev. The worst that can happen is the same value is
-        // stored more than once, but atomically.
-        if min_elevation == Elev::MAX {
-            min_elevation = self.samples.min();
-            self.min_elevation.store(min_elevation, Ordering::SeqCst);
-        };
-        min_elevation
+        self.elevation_range().0
     }
 
-    /// Returns the highest elevation sample in this tile.
+    /// Returns the highest elevation sample in this tile, ignoring
+    /// voids, or [`VOID`] if every sample is void.
     pub fn max_elevation(&self) -> Elev {
-        let mut max_elevation = self.max_elevation.load(Ordering::Relaxed);
-        if max_elevation == Elev::MAX {
-            // This block can race (not data-race), but it's fine because
-            // it's unlikely to happen very often if at all, and max elev
-            // is max elev. The worst that can happen is the same value is
-            // stored more than once, but atomically.
-            max_elevation = self.samples.max();
-            self.max_elevation.store(max_elevation, Ordering::SeqCst);
-        };
-        max_elevation
+        self.elevation_range().1
+    }
+
+    /// Returns the lowest and highest elevation samples in this tile,
+    /// ignoring voids, or `(VOID, VOID)` if every sample is void.
+    ///
+    /// Both are found in a single pass over the samples on first use
+    /// and cached, so later calls to this method,
+    /// [`min_elevation`](Self::min_elevation), or
+    /// [`max_elevation`](Self::max_elevation) are free.
+    pub fn elevation_range(&self) -> (Elev, Elev) {
+        *self.elevation_range.get_or_init(|| self.samples.range())
+    }
+
+    /// Returns `true` if every sample in this tile that isn't void has
+    /// the same elevation, as with tombstones and many ocean or ice
+    /// tiles, which callers may then skip or replace.
+    ///
+    /// Tiles that are entirely void are flat. This is answered from
+    /// [`elevation_range`](Self::elevation_range), so costs one pass
+    /// over the samples at most.
+    pub fn is_flat(&self) -> bool {
+        let (lo, hi) = self.elevation_range();
+        lo == hi
     }
 
     /// Returns this tile's resolution in arcseconds per sample.
@@ -207,18 +582,443 @@ impl Tile {
         self.resolution
     }
 
+    /// Returns the approximate `(east-west, north-south)` spacing of
+    /// samples, in meters, along the tile's middle row, e.g. for
+    /// display as "~30 m/pixel".
+    ///
+    /// East-west spacing shrinks with the cosine of latitude, so it
+    /// varies slightly from the tile's south edge to its north edge.
+    pub fn ground_resolution_m(&self) -> (C, C) {
+        self.row_spacing_m(self.dimensions().1 / 2)
+    }
+
     /// Returns and iterator over `self`'s grid squares.
     pub fn iter(&self) -> impl Iterator<Item = Sample<'_>> + '_ {
         (0..(self.dimensions().0 * self.dimensions().1)).map(|index| Sample { tile: self, index })
     }
 
+    /// Returns a parallel iterator over `self`'s grid squares.
+    ///
+    /// The linear index range of [`Tile::iter`] is split across
+    /// rayon's thread pool, so samples are visited in no particular
+    /// order, but indexed adaptors such as `collect` into a `Vec`
+    /// keep [`Tile::iter`]'s order.
+    #[cfg(feature = "rayon")]
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = Sample<'_>> + '_ {
+        (0..self.len())
+            .into_par_iter()
+            .map(move |index| Sample { tile: self, index })
+    }
+
+    /// Returns the same as [`elevation_range`](Self::elevation_range),
+    /// computing it with [`Tile::par_iter`] if it isn't cached yet.
+    ///
+    /// The result is cached for both methods.
+    #[cfg(feature = "rayon")]
+    pub fn par_elevation_range(&self) -> (Elev, Elev) {
+        *self.elevation_range.get_or_init(|| {
+            self.par_iter()
+                .map(|sample| sample.elevation())
+                .filter(|&elev| elev != VOID)
+                .fold_with(None, |acc, elev| match acc {
+                    None => Some((elev, elev)),
+                    Some((lo, hi)) => Some((Elev::min(lo, elev), Elev::max(hi, elev))),
+                })
+                .reduce(
+                    || None,
+                    |a, b| match (a, b) {
+                        (Some((a_lo, a_hi)), Some((b_lo, b_hi))) => {
+                            Some((a_lo.min(b_lo), a_hi.max(b_hi)))
+                        }
+                        (a, b) => a.or(b),
+                    },
+                )
+                .unwrap_or((VOID, VOID))
+        })
+    }
+
+    /// Returns an iterator over `self`'s rows of grid squares, north
+    /// to south, each from west to east.
+    ///
+    /// Rows and their samples are produced lazily from index ranges,
+    /// so this is as cheap as [`Tile::iter`], whose order the rows
+    /// follow when concatenated.
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = Sample<'_>> + '_> + '_ {
+        let (cols, rows) = self.dimensions();
+        (0..rows).map(move |y| {
+            let start = self.xy_to_linear((0, y));
+            (start..start + cols).map(|index| Sample { tile: self, index })
+        })
+    }
+
+    /// Returns an iterator over `self`'s columns of grid squares, west
+    /// to east, each from north to south.
+    ///
+    /// As with [`Tile::rows`], columns and their samples are produced
+    /// lazily from index ranges.
+    pub fn cols(&self) -> impl Iterator<Item = impl Iterator<Item = Sample<'_>> + '_> + '_ {
+        let (cols, rows) = self.dimensions();
+        (0..cols).map(move |x| {
+            (0..rows).map(move |y| Sample {
+                tile: self,
+                index: self.xy_to_linear((x, y)),
+            })
+        })
+    }
+
+    /// Folds every elevation sample, in linear order, into an
+    /// accumulator.
+    ///
+    /// This is cheaper than [`Tile::iter`] for reductions such as
+    /// sums and histograms, as it avoids per-sample index math and
+    /// reads the backing store directly.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use nasadem::Tile;
+    ///
+    /// let tile_path = format!(
+    ///     "{}/../data/nasadem/3arcsecond/N44W072.hgt",
+    ///     env!("CARGO_MANIFEST_DIR")
+    /// );
+    ///
+    /// let tile = Tile::memmap(tile_path).unwrap();
+    /// let above_1000m = tile.fold(0, |n, elev| n + usize::from(elev > 1000));
+    /// assert!(above_1000m > 0);
+    /// ```
+    pub fn fold<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, Elev) -> B,
+    {
+        self.samples.fold(init, f)
+    }
+
+    /// Returns the number of valid samples strictly below
+    /// `threshold`, e.g. cells inundated by a water level.
+    ///
+    /// Voids are not counted.
+    pub fn count_below(&self, threshold: Elev) -> usize {
+        self.fold(0, |n, elev| {
+            n + usize::from(elev != VOID && elev < threshold)
+        })
+    }
+
+    /// Returns the number of valid samples strictly above
+    /// `threshold`.
+    ///
+    /// Voids are not counted.
+    pub fn count_above(&self, threshold: Elev) -> usize {
+        self.fold(0, |n, elev| n + usize::from(elev > threshold))
+    }
+
+    /// Returns the mean location of this tile's valid samples, or
+    /// `None` if every sample is void.
+    ///
+    /// Unlike the tile's geometric center, this lands on the data,
+    /// e.g. on the land of a mostly-ocean tile. With `area_weighted`,
+    /// each sample is weighted by the ground area of its cell, which
+    /// shrinks with the cosine of latitude, pulling the centroid
+    /// slightly toward the equator.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn valid_centroid(&self, area_weighted: bool) -> Option<Coord<C>> {
+        let (cols, rows) = self.dimensions();
+        let (mut sum_x, mut sum_y, mut sum_w) = (0.0, 0.0, 0.0);
+        for y in 0..rows {
+            let weight = if area_weighted {
+                self.row_latitude(y).to_radians().cos()
+            } else {
+                1.0
+            };
+            let (mut n, mut row_sum_x) = (0_usize, 0_usize);
+            for x in (0..cols).filter(|&x| self.get_xy_unchecked((x, y)) != VOID) {
+                n += 1;
+                row_sum_x += x;
+            }
+            sum_x += weight * row_sum_x as C;
+            sum_y += weight * (n * y) as C;
+            sum_w += weight * n as C;
+        }
+        if sum_w == 0.0 {
+            return None;
+        }
+        let c = ARCSEC_PER_DEG / C::from(self.resolution);
+        Some(Coord {
+            x: self.sw_corner_center.x + sum_x / sum_w / c,
+            y: self.ne_corner_center.y - sum_y / sum_w / c,
+        })
+    }
+
+    /// Returns the lowest and highest valid elevations among the
+    /// samples whose centers lie within `rect`, edges included, or
+    /// `None` if there are none.
+    ///
+    /// Only the block of samples under `rect` is scanned, so this is
+    /// cheap for small windows, e.g. to pick a color scale per window
+    /// or skip flat regions.
+    pub fn elevation_range_in(&self, rect: Rect<C>) -> Option<(Elev, Elev)> {
+        let ((x0, y0), (x1, y1)) = self.xy_block(rect)?;
+        (y0..=y1)
+            .flat_map(|y| (x0..=x1).map(move |x| (x, y)))
+            .map(|xy| self.get_xy_unchecked(xy))
+            .filter(|&elev| elev != VOID)
+            .fold(None, |range, elev| match range {
+                None => Some((elev, elev)),
+                Some((lo, hi)) => Some((elev.min(lo), elev.max(hi))),
+            })
+    }
+
+    /// Returns a new in-memory tile of the samples whose centers lie
+    /// within `bbox`, edges included, or `None` if there are none.
+    ///
+    /// A `bbox` overhanging the tile is clamped to its edges. The crop
+    /// keeps this tile's grid, so the two return the same elevation
+    /// at every coordinate they share, and only the cropped samples
+    /// are copied, so cropping a memory-mapped tile never reads the
+    /// rest of it.
+    #[must_use]
+    pub fn crop(&self, bbox: Rect<C>) -> Option<Tile> {
+        let ((x0, y0), (x1, y1)) = self.xy_block(bbox)?;
+        let samples = (y0..=y1)
+            .flat_map(|y| (x0..=x1).map(move |x| (x, y)))
+            .map(|xy| self.get_xy_unchecked(xy))
+            .collect();
+        Some(Self {
+            // Rows count down from the north edge.
+            sw_corner_center: self.xy_to_geo((x0, y1)),
+            ne_corner_center: self.xy_to_geo((x1, y0)),
+            resolution: self.resolution,
+            dimensions: (x1 - x0 + 1, y1 - y0 + 1),
+            elevation_range: OnceLock::new(),
+            samples: SampleStore::InMem(samples),
+        })
+    }
+
+    /// Returns the polygon and elevation of each sample whose center
+    /// lies within `rect`, edges included, row by row from the NW.
+    ///
+    /// Items are produced lazily, so large regions can be streamed,
+    /// e.g. to a vector writer, without holding every polygon in
+    /// memory. Voids are included, with elevation [`VOID`].
+    pub fn iter_cells_in(&self, rect: Rect<C>) -> impl Iterator<Item = (Polygon<C>, Elev)> + '_ {
+        self.xy_block(rect)
+            .into_iter()
+            .flat_map(|((x0, y0), (x1, y1))| {
+                (y0..=y1).flat_map(move |y| (x0..=x1).map(move |x| (x, y)))
+            })
+            .map(|xy| (self.xy_to_polygon(xy), self.get_xy_unchecked(xy)))
+    }
+
+    /// Returns `true` if `self` and `other` sample the same grid.
+    ///
+    /// Compatible tiles have equal resolution, and their samples lie
+    /// on the same lattice (i.e., their corners are offset by a whole
+    /// number of samples). All tiles loaded from disk at the same
+    /// resolution are compatible, whether identical, adjacent, or
+    /// far apart. Operations combining tiles (mosaics, merges,
+    /// diffs) require compatible grids and return
+    /// [`NasademError::IncompatibleGrids`] otherwise.
+    pub fn is_grid_compatible(&self, other: &Tile) -> bool {
+        if self.resolution != other.resolution {
+            return false;
+        }
+        let samples_per_deg = ARCSEC_PER_DEG / C::from(self.resolution);
+        let on_lattice = |a: C, b: C| {
+            let offset = (a - b) * samples_per_deg;
+            (offset - offset.round()).abs() < 1e-6
+        };
+        on_lattice(self.sw_corner_center.x, other.sw_corner_center.x)
+            && on_lattice(self.sw_corner_center.y, other.sw_corner_center.y)
+    }
+
+    /// Returns a summed-area table (integral image) of this tile's
+    /// elevations.
+    ///
+    /// The table is `(cols + 1) * (rows + 1)` long, row-major with a
+    /// stride of `cols + 1`, and has a leading row and column of
+    /// zeros. Element `(x + 1, y + 1)` is the sum of every sample in
+    /// the inclusive box `(0, 0)..=(x, y)`, so the sum over any box
+    /// `(x0, y0)..=(x1, y1)` takes four lookups:
+    ///
+    /// ```text
+    /// t[y1+1][x1+1] - t[y0][x1+1] - t[y1+1][x0] + t[y0][x0]
+    /// ```
+    ///
+    /// Voids contribute zero. Sums are accumulated as `i64`: a
+    /// 1-arcsecond tile holds ~13M samples, which at the maximum
+    /// elevation easily overflows an `i32`.
+    pub fn integral_image(&self) -> Vec<i64> {
+        self.summed_area(|elev| if elev == VOID { 0 } else { i64::from(elev) })
+    }
+
+    /// Returns a new in-memory tile keeping every `factor`th sample
+    /// along each axis, each averaged with its neighbors.
+    ///
+    /// Output sample `(x, y)` lies on input sample `(x * factor, y *
+    /// factor)` and is the mean of the non-void input samples within
+    /// `factor / 2` samples of it along each axis, clipped at the
+    /// tile's edges. Blocks stay centered, so for an even `factor`
+    /// they are `factor + 1` samples wide. The result covers the same
+    /// extent at `factor` times the resolution. Blocks with no valid
+    /// samples are [`VOID`].
+    ///
+    /// Block sums come from [`Tile::integral_image`], so the cost is
+    /// independent of `factor`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is zero, does not evenly divide the number
+    /// of sample intervals along both axes, or the resulting
+    /// resolution exceeds 255 arcseconds per sample.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn downsample(&self, factor: usize) -> Tile {
+        let (cols, rows) = self.dimensions();
+        assert!(
+            factor > 0 && (cols - 1) % factor == 0 && (rows - 1) % factor == 0,
+            "factor {factor} does not evenly divide dimensions {:?}",
+            self.dimensions
+        );
+        let resolution = u8::try_from(usize::from(self.resolution) * factor)
+            .expect("downsampled resolution exceeds 255 arcseconds per sample");
+        let (out_cols, out_rows) = ((cols - 1) / factor + 1, (rows - 1) / factor + 1);
+        let sums = self.integral_image();
+        let counts = self.summed_area(|elev| i64::from(elev != VOID));
+        let block = |i: usize, len: usize| {
+            let center = i * factor;
+            (
+                center.saturating_sub(factor / 2),
+                (center + factor / 2).min(len - 1),
+            )
+        };
+        let mut samples = Vec::with_capacity(out_cols * out_rows);
+        for y in 0..out_rows {
+            let (y0, y1) = block(y, rows);
+            for x in 0..out_cols {
+                let (x0, x1) = block(x, cols);
+                let count = box_sum(&counts, cols, (x0, y0), (x1, y1));
+                samples.push(if count == 0 {
+                    VOID
+                } else {
+                    let sum = box_sum(&sums, cols, (x0, y0), (x1, y1));
+                    (sum as f64 / count as f64).round() as Elev
+                });
+            }
+        }
+        Tile::from_samples_at(
+            self.sw_corner_center,
+            resolution,
+            (out_cols, out_rows),
+            samples,
+        )
+    }
+
+    /// Returns a copy of this tile resampled to `target_arcsec`
+    /// arcseconds per sample, with its corner samples kept in place.
+    ///
+    /// A coarser target that is a multiple of this tile's resolution,
+    /// e.g. 1 to 3 arcseconds, averages blocks of samples, skipping
+    /// voids, as [`Tile::downsample`] does. Any other target,
+    /// including every finer one, bilinearly interpolates this tile
+    /// at each new sample center as [`Tile::get_bilinear`] does, so a
+    /// new sample next to a void is void.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target_arcsec` is zero or does not evenly divide
+    /// the tile's span in arcseconds along both axes, as the corners
+    /// could then not stay aligned.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn resample(&self, target_arcsec: u8) -> Tile {
+        let (cols, rows) = self.dimensions();
+        let resolution = usize::from(self.resolution);
+        let target = usize::from(target_arcsec);
+        let (span_x, span_y) = ((cols - 1) * resolution, (rows - 1) * resolution);
+        assert!(
+            target > 0 && span_x % target == 0 && span_y % target == 0,
+            "{target_arcsec} arcseconds per sample does not evenly divide span {:?}",
+            (span_x, span_y)
+        );
+        if target % resolution == 0 {
+            return self.downsample(target / resolution);
+        }
+        let (out_cols, out_rows) = (span_x / target + 1, span_y / target + 1);
+        let deg_per_sample = C::from(target_arcsec) / ARCSEC_PER_DEG;
+        let samples = (0..out_rows)
+            .flat_map(|y| (0..out_cols).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let coord = Coord {
+                    x: self.sw_corner_center.x + x as C * deg_per_sample,
+                    y: self.ne_corner_center.y - y as C * deg_per_sample,
+                };
+                self.get_geo_interpolated(coord)
+                    .map_or(VOID, |elev| elev.round() as Elev)
+            })
+            .collect();
+        Tile::from_samples_at(
+            self.sw_corner_center,
+            target_arcsec,
+            (out_cols, out_rows),
+            samples,
+        )
+    }
+
+    /// Returns the samples of row `y`, west to east, without copying.
+    ///
+    /// Only in-memory tiles store samples as native [`Elev`]s, so
+    /// this returns `None` for memory-mapped and tombstone tiles, as
+    /// well as when `y` is out of bounds.
+    pub fn row_slice(&self, y: usize) -> Option<&[Elev]> {
+        let (cols, rows) = self.dimensions();
+        match &self.samples {
+            SampleStore::InMem(samples) if y < rows => Some(&samples[y * cols..(y + 1) * cols]),
+            _ => None,
+        }
+    }
+
+    /// Returns the rect spanning the centers of this tile's corner
+    /// samples.
+    ///
+    /// This is the sample-center (or "pixel-is-point") convention used
+    /// by HGT files, whose SW corner sample sits exactly on a whole
+    /// degree. Every sample center, edges included, lies within it.
+    /// See [`extent_pixels`](Self::extent_pixels) for the area the
+    /// samples cover.
+    pub fn extent_centers(&self) -> Rect<C> {
+        Rect::new(self.sw_corner_center, self.ne_corner_center)
+    }
+
+    /// Returns the rect covered by this tile's samples, treating each
+    /// as a cell centered on its sample.
+    ///
+    /// This is the pixel-area (or "pixel-is-area") convention used by
+    /// most raster tools, e.g. GDAL's geotransform. It is
+    /// [`extent_centers`](Self::extent_centers) padded by half a
+    /// sample on each side, so a full 3-arcsecond tile at `N44W072`
+    /// spans `-72.000417..-70.999583` rather than `-72..-71`.
+    pub fn extent_pixels(&self) -> Rect<C> {
+        let delta = C::from(self.resolution) * HALF_ARCSEC;
+        Rect::new(
+            Coord {
+                x: self.sw_corner_center.x - delta,
+                y: self.sw_corner_center.y - delta,
+            },
+            Coord {
+                x: self.ne_corner_center.x + delta,
+                y: self.ne_corner_center.y + delta,
+            },
+        )
+    }
+
     /// Returns this tile's outline as a polygon.
+    ///
+    /// The outline is [`extent_pixels`](Self::extent_pixels), the area
+    /// covered by the samples.
     pub fn polygon(&self) -> Polygon {
-        let delta = C::from(self.resolution) * HALF_ARCSEC;
-        let n = self.ne_corner_center.y + delta;
-        let e = self.sw_corner_center.x + delta;
-        let s = self.sw_corner_center.y - delta;
-        let w = self.sw_corner_center.x - delta;
+        let extent = self.extent_pixels();
+        let (w, s) = extent.min().x_y();
+        let (e, n) = extent.max().x_y();
 
         polygon![
             (x: w, y: s),
@@ -247,7 +1047,8 @@ impl Tile {
     /// # Returns
     ///
     /// - `Some(Elev)` if the location is valid and contained within
-    ///    the tile.
+    ///    the tile. This is the raw sample, which may be [`VOID`]; see
+    ///    [`Tile::get_checked`] to treat voids as missing.
     /// - `None` if the location is out of bounds or invalid.
     ///
     /// # Examples
@@ -272,8 +1073,8 @@ impl Tile {
     /// // Using absolute geographic coordinates.
     /// assert_eq!(
     ///     tile.get(Coord {
-    ///         x: -104.993_472_222_222_22,
-    ///         y: 38.790_972_222_222_22,
+    ///         x: -104.993_333_333_333_33,
+    ///         y: 38.791_111_111_111_11,
     ///     }),
     ///     Some(3772)
     /// );
@@ -340,8 +1141,8 @@ impl Tile {
     /// // Using absolute geographic coordinates.
     /// assert_eq!(
     ///     tile.get_unchecked(Coord {
-    ///         x: -104.993_472_222_222_22,
-    ///         y: 38.790_972_222_222_22,
+    ///         x: -104.993_333_333_333_33,
+    ///         y: 38.791_111_111_111_11,
     ///     }),
     ///     3772
     /// );
@@ -357,10 +1158,257 @@ impl Tile {
             TileIndex::Geo(idx) => self.get_geo_unchecked(idx),
         }
     }
+
+    /// Returns the elevation at each of `locs`, as [`Tile::get`]
+    /// would, in one call.
+    ///
+    /// Locations are resolved to sample indices and read in a single
+    /// pass that dispatches on the backing store once, rather than
+    /// once per location as calling [`Tile::get`] in a loop does.
+    pub fn get_many<T>(&self, locs: &[T]) -> Vec<Option<Elev>>
+    where
+        T: Copy,
+        TileIndex: From<T>,
+    {
+        let indices = locs
+            .iter()
+            .map(|&loc| self.checked_linear(TileIndex::from(loc)).ok());
+        self.samples.get_many_linear(indices)
+    }
+
+    /// Returns the elevation at each of `locs`, as
+    /// [`Tile::get_unchecked`] would, in one call.
+    ///
+    /// See [`Tile::get_many`] for why this is cheaper than a loop.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any location is out of bounds.
+    pub fn get_many_unchecked<T>(&self, locs: &[T]) -> Vec<Elev>
+    where
+        T: Copy,
+        TileIndex: From<T>,
+    {
+        let indices = locs.iter().map(|&loc| match TileIndex::from(loc) {
+            TileIndex::Linear(idx) => idx,
+            TileIndex::XY(xy) => self.xy_to_linear(xy),
+            #[allow(clippy::cast_sign_loss)]
+            TileIndex::Geo(coord) => {
+                let (x, y) = self.geo_to_xy(coord);
+                self.xy_to_linear((x as usize, y as usize))
+            }
+        });
+        self.samples.get_many_linear(indices)
+    }
+
+    /// Returns the elevation at the given location, or why there is
+    /// none.
+    ///
+    /// Unlike [`Tile::get`], this distinguishes an index past the end
+    /// of the tile, a geographic coordinate off the tile, and a void
+    /// sample.
+    ///
+    /// # Errors
+    ///
+    /// - [`TileQueryError::OutOfBounds`] for linear and (x, y)
+    ///   indices outside the tile.
+    /// - [`TileQueryError::OffTile`] for coordinates outside the tile.
+    /// - [`TileQueryError::Void`] if the sample is [`VOID`].
+    pub fn try_get<T>(&self, loc: T) -> Result<Elev, TileQueryError>
+    where
+        TileIndex: From<T>,
+    {
+        let linear = self.checked_linear(TileIndex::from(loc))?;
+        match self.samples.get_linear_unchecked(linear) {
+            VOID => Err(TileQueryError::Void),
+            elev => Ok(elev),
+        }
+    }
+
+    /// Returns the elevation at the given location, or `None` if the
+    /// location is off this tile or the sample is [`VOID`].
+    ///
+    /// Accepts the same location types as [`Tile::get`].
+    pub fn get_checked<T>(&self, loc: T) -> Option<Elev>
+    where
+        TileIndex: From<T>,
+    {
+        self.try_get(loc).ok()
+    }
+
+    /// Returns the elevation at `coord` bilinearly interpolated
+    /// between the four surrounding samples.
+    ///
+    /// Returns `None` if `coord` lies outside the grid of sample
+    /// centers, or if any of the four samples is [`VOID`].
+    pub fn get_bilinear(&self, coord: Coord<C>) -> Option<C> {
+        self.get_geo_interpolated(coord)
+    }
+
+    /// Returns the [`Sample`] at the specified location.
+    ///
+    /// Accepts the same location types as [`Tile::get`], and returns
+    /// `None` if the location is outside the tile.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use nasadem::Tile;
+    ///
+    /// let tile_path = format!(
+    ///     "{}/../data/nasadem/1arcsecond/N38W105.hgt",
+    ///     env!("CARGO_MANIFEST_DIR")
+    /// );
+    ///
+    /// let tile = Tile::load(tile_path).unwrap();
+    ///
+    /// let sample = tile.sample((24, 752)).unwrap();
+    /// assert_eq!(sample.elevation(), 3772);
+    /// assert_eq!(sample.index(), 2_707_976);
+    /// ```
+    pub fn sample<T>(&self, loc: T) -> Option<Sample<'_>>
+    where
+        TileIndex: From<T>,
+    {
+        let (cols, rows) = self.dimensions();
+        let index = match TileIndex::from(loc) {
+            TileIndex::Linear(idx) => (idx < self.len()).then_some(idx)?,
+            TileIndex::XY((x, y)) => (x < cols && y < rows).then(|| self.xy_to_linear((x, y)))?,
+            TileIndex::Geo(coord) => {
+                let (x, y) = self.geo_to_xy(coord);
+                let x = usize::try_from(x).ok().filter(|&x| x < cols)?;
+                let y = usize::try_from(y).ok().filter(|&y| y < rows)?;
+                self.xy_to_linear((x, y))
+            }
+        };
+        Some(Sample { tile: self, index })
+    }
+
+    /// Returns the [`Sample`] at the specified location without
+    /// bounds checking.
+    ///
+    /// # Panics
+    ///
+    /// The returned sample will panic when queried if the location is
+    /// outside the tile.
+    pub fn sample_unchecked<T>(&self, loc: T) -> Sample<'_>
+    where
+        TileIndex: From<T>,
+    {
+        let index = match TileIndex::from(loc) {
+            TileIndex::Linear(idx) => idx,
+            TileIndex::XY(idx) => self.xy_to_linear(idx),
+            TileIndex::Geo(coord) => {
+                let (x, y) = self.geo_to_xy(coord);
+                #[allow(clippy::cast_sign_loss)]
+                self.xy_to_linear((x as usize, y as usize))
+            }
+        };
+        Sample { tile: self, index }
+    }
+
+    /// Returns the [`Sample`] nearest `coord` and the ground
+    /// distance in meters from `coord` to that sample's center, or
+    /// `None` if `coord` is off this tile.
+    ///
+    /// Distance uses an equirectangular approximation at the mean
+    /// latitude of the two points, which is accurate to well under a
+    /// meter at sample spacing.
+    pub fn nearest_sample(&self, coord: Coord<C>) -> Option<(Sample<'_>, C)> {
+        let sample = self.sample(coord)?;
+        let center = sample.geo();
+        let dy = (coord.y - center.y) * METERS_PER_DEG;
+        let dx =
+            (coord.x - center.x) * METERS_PER_DEG * ((coord.y + center.y) / 2.0).to_radians().cos();
+        Some((sample, dx.hypot(dy)))
+    }
+
+    /// Overwrites the sample at the specified location with `elev`.
+    ///
+    /// Only tiles that own their samples ([`Tile::load`],
+    /// [`Tile::from_samples`]) or that were opened with
+    /// [`Tile::memmap_cow`] can be edited. Cached minimum and maximum
+    /// elevations are recomputed on next use.
+    ///
+    /// # Errors
+    ///
+    /// - [`NasademError::Query`] if the location is outside the
+    ///   tile.
+    /// - [`NasademError::ReadOnly`] if this tile is a tombstone or a
+    ///   read-only [`Tile::memmap`].
+    pub fn set<T>(&mut self, loc: T, elev: Elev) -> Result<(), NasademError>
+    where
+        TileIndex: From<T>,
+    {
+        let index = self.checked_linear(TileIndex::from(loc))?;
+        self.samples.set_linear_unchecked(index, elev)?;
+        self.invalidate_extremes();
+        Ok(())
+    }
 }
 
 /// Private API
 impl Tile {
+    /// Clears the cached minimum and maximum elevations, so they are
+    /// recomputed on next use after samples change.
+    pub(crate) fn invalidate_extremes(&mut self) {
+        self.elevation_range.take();
+    }
+
+    /// Returns a Tile backed by a shared, read-only mapping of the
+    /// file, or a private, writable one if `copy_on_write` is set.
+    fn map_file<P: AsRef<Path>>(path: P, copy_on_write: bool) -> Result<Self, NasademError> {
+        if let Some(tile) = Self::load_compressed(&path) {
+            return tile;
+        }
+        let (resolution, dimensions) = util::extract_resolution(&path)?;
+        let sw_corner_center = {
+            let Coord { x, y } = util::parse_sw_corner(&path)?;
+            Coord {
+                x: C::from(x),
+                y: C::from(y),
+            }
+        };
+
+        #[allow(clippy::cast_precision_loss)]
+        let ne_corner_center = Coord {
+            y: sw_corner_center.y as C + 1.0,
+            x: sw_corner_center.x as C + 1.0,
+        };
+
+        let samples = {
+            let file = File::open(&path)?;
+            let (mapped_len, samples) = if copy_on_write {
+                let mmap = unsafe { MmapOptions::new().map_copy(&file)? };
+                (mmap.len(), SampleStore::MemMapCow(mmap))
+            } else {
+                let mmap = unsafe { Mmap::map(&file)? };
+                (mmap.len(), SampleStore::MemMap(mmap))
+            };
+            // The file may have changed between reading its metadata
+            // and mapping it.
+            let expected_len = dimensions.0 * dimensions.1 * size_of::<Elev>();
+            if mapped_len != expected_len {
+                return Err(NasademError::HgtLen(
+                    mapped_len as u64,
+                    path.as_ref().to_path_buf(),
+                ));
+            }
+            samples
+        };
+
+        let elevation_range = OnceLock::new();
+
+        Ok(Self {
+            sw_corner_center,
+            ne_corner_center,
+            resolution,
+            dimensions,
+            elevation_range,
+            samples,
+        })
+    }
+
     /// Returns the sample at the given geo coordinates.
     pub(crate) fn get_geo(&self, coord: Coord<C>) -> Option<Elev> {
         let (idx_x, idx_y) = self.geo_to_xy(coord);
@@ -378,6 +1426,83 @@ impl Tile {
         }
     }
 
+    /// Returns the elevation at `coord` bilinearly interpolated
+    /// between the four surrounding samples, or `None` if `coord`
+    /// lies outside the grid of sample centers or any of the samples
+    /// is [`VOID`].
+    ///
+    /// Coordinates within [`TIE_EPSILON`] samples of the outermost
+    /// rows and columns are clamped onto them, and the last row and
+    /// column interpolate within the cell before them.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub(crate) fn get_geo_interpolated(&self, coord: Coord<C>) -> Option<C> {
+        let (cols, rows) = self.dimensions();
+        let (fx, fy) = self.geo_to_xy_frac(coord);
+        let (max_x, max_y) = ((cols - 1) as C, (rows - 1) as C);
+        let on_grid = |v: C, max: C| (-TIE_EPSILON..=max + TIE_EPSILON).contains(&v);
+        if !on_grid(fx, max_x) || !on_grid(fy, max_y) {
+            return None;
+        }
+        let (fx, fy) = (fx.clamp(0.0, max_x), fy.clamp(0.0, max_y));
+        let x0 = (fx.floor() as usize).min(cols.saturating_sub(2));
+        let y0 = (fy.floor() as usize).min(rows.saturating_sub(2));
+        let (x1, y1) = ((x0 + 1).min(cols - 1), (y0 + 1).min(rows - 1));
+        let get = |x, y| match self.get_xy_unchecked((x, y)) {
+            VOID => None,
+            elev => Some(C::from(elev)),
+        };
+        let (tx, ty) = (fx - x0 as C, fy - y0 as C);
+        let north = get(x0, y0)? * (1.0 - tx) + get(x1, y0)? * tx;
+        let south = get(x0, y1)? * (1.0 - tx) + get(x1, y1)? * tx;
+        Some(north * (1.0 - ty) + south * ty)
+    }
+
+    /// Returns the elevation gradient `(dz/dx, dz/dy)` at `(x, y)` by
+    /// Horn's method, where x is east and y is north, or `None` if the
+    /// sample is void.
+    ///
+    /// Neighbors beyond the tile's edges are clamped to the nearest
+    /// edge sample, and void neighbors are replaced by the center
+    /// sample. Columns are spaced by their true ground distance at
+    /// row `y`'s latitude.
+    pub(crate) fn horn_gradient(&self, (x, y): (usize, usize)) -> Option<(C, C)> {
+        let (dx_m, dy_m) = self.row_spacing_m(y);
+        self.horn_gradient_with_spacing((x, y), dx_m, dy_m)
+    }
+
+    /// Returns [`Tile::horn_gradient`] over columns `dx_m` meters
+    /// apart and rows `dy_m` meters apart.
+    pub(crate) fn horn_gradient_with_spacing(
+        &self,
+        (x, y): (usize, usize),
+        dx_m: C,
+        dy_m: C,
+    ) -> Option<(C, C)> {
+        let center = self.get_xy_unchecked((x, y));
+        if center == VOID {
+            return None;
+        }
+        let (cols, rows) = self.dimensions();
+        let get = |x: usize, y: usize| {
+            let elev = self.get_xy_unchecked((x.min(cols - 1), y.min(rows - 1)));
+            C::from(if elev == VOID { center } else { elev })
+        };
+        let (west_x, east_x) = (x.saturating_sub(1), x + 1);
+        // Row 0 is north, so north is toward decreasing `y`.
+        let (north_y, south_y) = (y.saturating_sub(1), y + 1);
+        let (nw, north, ne) = (get(west_x, north_y), get(x, north_y), get(east_x, north_y));
+        let (west, east) = (get(west_x, y), get(east_x, y));
+        let (sw, south, se) = (get(west_x, south_y), get(x, south_y), get(east_x, south_y));
+
+        let dzdx = ((ne + 2.0 * east + se) - (nw + 2.0 * west + sw)) / (8.0 * dx_m);
+        let dzdy = ((nw + 2.0 * north + ne) - (sw + 2.0 * south + se)) / (8.0 * dy_m);
+        Some((dzdx, dzdy))
+    }
+
     /// Returns the sample at the given geo coordinates.
     pub(crate) fn get_geo_unchecked(&self, coord: Coord<C>) -> Elev {
         let (idx_x, idx_y) = self.geo_to_xy(coord);
@@ -388,7 +1513,8 @@ impl Tile {
 
     /// Returns the sample at the given raster coordinates.
     pub(crate) fn get_xy(&self, (x, y): (usize, usize)) -> Option<Elev> {
-        if x * y < self.len() {
+        let (cols, rows) = self.dimensions();
+        if x < cols && y < rows {
             Some(self.get_xy_unchecked((x, y)))
         } else {
             None
@@ -401,45 +1527,192 @@ impl Tile {
         self.samples.get_linear_unchecked(idx_1d)
     }
 
+    /// Returns the raster coordinates of the sample nearest `coord`.
+    ///
+    /// A coordinate exactly halfway between two samples (to within
+    /// [`TIE_EPSILON`] samples, absorbing floating point error)
+    /// snaps to the one with the larger index, i.e., east in x and
+    /// south in y. Offsets are measured from the tile's corners
+    /// rather than from the equator or prime meridian, so this
+    /// holds regardless of hemisphere; plain `round()` would instead
+    /// break ties away from zero and send a coordinate half a sample
+    /// outside the west or north edge out of bounds.
     pub(crate) fn geo_to_xy(&self, coord: Coord<C>) -> (isize, isize) {
-        let c = ARCSEC_PER_DEG / C::from(self.resolution);
-        let y = (self.sw_corner_center.y + 1.0 - coord.y) * c;
-        let x = (coord.x - self.sw_corner_center.x) * c;
+        self.snap_geo_to_xy(coord, SnapMode::Nearest)
+    }
+
+    /// Returns the NW and SE raster coordinates, inclusive, of the
+    /// block of samples whose centers lie within `rect`, edges
+    /// included, clipped to this tile, or `None` if there are none.
+    fn xy_block(&self, rect: Rect<C>) -> Option<((usize, usize), (usize, usize))> {
+        let (cols, rows) = self.dimensions();
+        // Rows count down from the north edge.
+        let nw = Coord {
+            x: rect.min().x,
+            y: rect.max().y,
+        };
+        let se = Coord {
+            x: rect.max().x,
+            y: rect.min().y,
+        };
+        let (x0, y0) = self.snap_geo_to_xy(nw, SnapMode::Ceil);
+        let (x1, y1) = self.snap_geo_to_xy(se, SnapMode::Floor);
+        // Clip to the tile; a negative far edge is off the tile.
+        let (x0, y0) = (x0.max(0).unsigned_abs(), y0.max(0).unsigned_abs());
+        let x1 = usize::try_from(x1).ok()?.min(cols - 1);
+        let y1 = usize::try_from(y1).ok()?.min(rows - 1);
+        if x0 > x1 || y0 > y1 {
+            return None;
+        }
+        Some(((x0, y0), (x1, y1)))
+    }
 
+    /// Returns the raster coordinates `coord` snaps to under `mode`,
+    /// which may be off this tile.
+    ///
+    /// A coordinate within [`TIE_EPSILON`] samples of a sample center
+    /// snaps to that sample in every mode.
+    fn snap_geo_to_xy(&self, coord: Coord<C>, mode: SnapMode) -> (isize, isize) {
+        let (x, y) = self.geo_to_xy_frac(coord);
+        let snap = |v: C| match mode {
+            SnapMode::Nearest => (v + 0.5 + TIE_EPSILON).floor(),
+            SnapMode::Floor => (v + TIE_EPSILON).floor(),
+            SnapMode::Ceil => (v - TIE_EPSILON).ceil(),
+        };
         #[allow(clippy::cast_possible_truncation)]
-        (x.round() as isize, y.round() as isize)
+        (snap(x) as isize, snap(y) as isize)
+    }
+
+    /// Returns the fractional raster coordinates of `coord`, where
+    /// whole numbers are sample centers and (0, 0) is the NW sample.
+    pub(crate) fn geo_to_xy_frac(&self, coord: Coord<C>) -> (C, C) {
+        let c = ARCSEC_PER_DEG / C::from(self.resolution);
+        let x = (coord.x - self.sw_corner_center.x) * c;
+        let y = (self.ne_corner_center.y - coord.y) * c;
+        (x, y)
     }
 
     pub(crate) fn xy_to_geo(&self, (x, y): (usize, usize)) -> Coord<C> {
         let c = ARCSEC_PER_DEG / C::from(self.resolution);
 
         #[allow(clippy::cast_precision_loss)]
-        let lat = self.sw_corner_center.y + 1.0 - (y as C) / c;
+        let lat = self.ne_corner_center.y - (y as C) / c;
         #[allow(clippy::cast_precision_loss)]
         let lon = self.sw_corner_center.x + (x as C) / c;
         Coord { x: lon, y: lat }
     }
 
+    /// Returns the linear index of `index`, or why it is not on this
+    /// tile.
+    pub(crate) fn checked_linear(&self, index: TileIndex) -> Result<usize, TileQueryError> {
+        let (cols, rows) = self.dimensions();
+        match index {
+            TileIndex::Linear(idx) if idx < self.len() => Ok(idx),
+            TileIndex::XY((x, y)) if x < cols && y < rows => Ok(self.xy_to_linear((x, y))),
+            TileIndex::Linear(_) | TileIndex::XY(_) => Err(TileQueryError::OutOfBounds {
+                index,
+                len: self.len(),
+            }),
+            TileIndex::Geo(coord) => {
+                let (x, y) = self.geo_to_xy(coord);
+                match (usize::try_from(x), usize::try_from(y)) {
+                    (Ok(x), Ok(y)) if x < cols && y < rows => Ok(self.xy_to_linear((x, y))),
+                    _ => Err(TileQueryError::OffTile { coord }),
+                }
+            }
+        }
+    }
+
     pub(crate) fn linear_to_xy(&self, idx: usize) -> (usize, usize) {
-        let y = idx / self.dimensions().0;
-        let x = idx % self.dimensions().1;
+        let y = idx / self.stride();
+        let x = idx % self.stride();
         (x, y)
     }
 
     pub(crate) fn xy_to_linear(&self, (x, y): (usize, usize)) -> usize {
-        self.dimensions().0 * y + x
+        self.stride() * y + x
     }
 
-    pub(crate) fn xy_to_polygon(&self, (x, y): (usize, usize)) -> Polygon<C> {
-        #[allow(clippy::cast_precision_loss)]
-        let center = Coord {
-            x: self.sw_corner_center.x + (x as C * C::from(self.resolution)) / ARCSEC_PER_DEG,
-            y: self.sw_corner_center.y + (y as C * C::from(self.resolution)) / ARCSEC_PER_DEG,
-        };
-        util::polygon(&center, C::from(self.resolution))
+    /// Returns the SW corner of the whole degree holding this tile's
+    /// SW sample, which names the tile.
+    ///
+    /// For tiles spanning whole degrees this is the corner they were
+    /// built from. Rebuild tiles from `sw_corner_center` instead, as
+    /// that of e.g. a [`Tile::crop`]ped tile isn't a whole degree.
+    pub(crate) fn sw_corner(&self) -> Coord<i16> {
+        // Half a sample in from the corner, clear of rounding error
+        // in either direction.
+        let half_sample = C::from(self.resolution) / ARCSEC_PER_DEG / 2.0;
+        util::sw_corner_for(Coord {
+            x: self.sw_corner_center.x + half_sample,
+            y: self.sw_corner_center.y + half_sample,
+        })
+    }
+
+    /// Returns the center of this tile's SW sample.
+    pub(crate) fn sw_corner_center(&self) -> Coord<C> {
+        self.sw_corner_center
+    }
+
+    /// Returns a summed-area table of `f` applied to every sample,
+    /// laid out as described in [`Tile::integral_image`].
+    pub(crate) fn summed_area<F>(&self, f: F) -> Vec<i64>
+    where
+        F: Fn(Elev) -> i64,
+    {
+        let (cols, rows) = self.dimensions();
+        let stride = cols + 1;
+        let mut table = vec![0; stride * (rows + 1)];
+        for y in 0..rows {
+            let mut row_sum = 0;
+            for x in 0..cols {
+                row_sum += f(self.get_xy_unchecked((x, y)));
+                let idx = (y + 1) * stride + x + 1;
+                table[idx] = table[idx - stride] + row_sum;
+            }
+        }
+        table
+    }
+
+    pub(crate) fn xy_to_polygon(&self, xy: (usize, usize)) -> Polygon<C> {
+        util::polygon(&self.xy_to_geo(xy), C::from(self.resolution))
     }
 }
 
+/// Returns the compass direction of steepest descent for the gradient
+/// `(dz/dx, dz/dy)`, in radians clockwise from north in `[0, 2π)`, or
+/// `None` if the gradient is zero.
+pub(crate) fn downslope_azimuth((dzdx, dzdy): (C, C)) -> Option<C> {
+    (dzdx != 0.0 || dzdy != 0.0).then(|| (-dzdx).atan2(-dzdy).rem_euclid(std::f64::consts::TAU))
+}
+
+/// Returns the sum over the inclusive box `(x0, y0)..=(x1, y1)` of a
+/// summed-area table built over `cols` columns.
+pub(crate) fn box_sum(
+    table: &[i64],
+    cols: usize,
+    (x0, y0): (usize, usize),
+    (x1, y1): (usize, usize),
+) -> i64 {
+    let stride = cols + 1;
+    table[(y1 + 1) * stride + x1 + 1] - table[y0 * stride + x1 + 1] - table[(y1 + 1) * stride + x0]
+        + table[y0 * stride + x0]
+}
+
+/// How [`Tile::geo_to_xy_mode`] snaps a coordinate that lies between
+/// sample centers to a sample.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapMode {
+    /// The nearest sample, treating samples as points. Ties go east
+    /// and south.
+    Nearest,
+    /// The sample to the west and north, treating each sample as the
+    /// NW corner of a pixel that extends to the next sample.
+    Floor,
+    /// The sample to the east and south.
+    Ceil,
+}
+
 /// Represents various ways to index into a [`Tile`].
 ///
 /// `TileIndex` is an enum that provides different indexing mechanisms
@@ -485,21 +1758,20 @@ impl From<Coord> for TileIndex {
 impl fmt::Debug for Tile {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // force lazy evaluation of max and min elevation.
-        let _ = self.max_elevation();
-        let _ = self.min_elevation();
+        let _ = self.elevation_range();
         f.debug_struct("Tile")
             .field("sw_corner_center", &self.sw_corner_center)
             .field("ne_corner_center", &self.ne_corner_center)
             .field("resolution", &self.resolution)
             .field("dimensions", &self.dimensions)
-            .field("min_elev", &self.min_elevation)
-            .field("max_elevation", &self.max_elevation)
+            .field("elevation_range", &self.elevation_range)
             .field(
                 "samples",
                 &match self.samples {
                     SampleStore::Tombstone(_) => "Tombstone",
                     SampleStore::InMem(_) => "InMem",
                     SampleStore::MemMap(_) => "MemMap",
+                    SampleStore::MemMapCow(_) => "MemMapCow",
                 },
             )
             .finish()
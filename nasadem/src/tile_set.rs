@@ -0,0 +1,395 @@
+//! Lookup across many tiles by geographic coordinate.
+
+use crate::{
+    geo::{Coord, HaversineDistance, HaversineIntermediate, Point, Rect},
+    util, Elev, NasademError, Tile, TileQueryError, C, HALF_ARCSEC,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// Number of memory-mapped tiles a directory-backed set keeps open
+/// unless told otherwise.
+const DEFAULT_CACHE_CAPACITY: usize = 16;
+
+/// Tiles keyed by their whole-degree SW corner, queried by coordinate
+/// as though they were one tile.
+///
+/// Tiles are either inserted already loaded, or found by
+/// [`TileSet::open_dir`] in a directory and memory-mapped on first
+/// use. Lookups hash the coordinate's integer latitude and longitude,
+/// so they take constant time however many tiles the set covers.
+#[derive(Debug, Default)]
+pub struct TileSet {
+    tiles: HashMap<Coord<i16>, Tile>,
+
+    /// Tiles in a directory, loaded on demand.
+    dir: Option<DirTiles>,
+
+    /// Elevation returned where no tile has coverage.
+    gap_elevation: Option<Elev>,
+}
+
+impl TileSet {
+    /// Returns an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a set covering the `.hgt` files in `dir`, without
+    /// reading their samples.
+    ///
+    /// Files are indexed by the SW corner parsed from their names,
+    /// and their resolution from their lengths; files that fail
+    /// either are skipped. A tile is memory-mapped when a lookup
+    /// first needs it, and the most recently used tiles stay mapped,
+    /// up to [`TileSet::with_cache_capacity`] of them (16 by default).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NasademError::Io`] if `dir` can't be read.
+    pub fn open_dir<P: AsRef<Path>>(dir: P) -> Result<Self, NasademError> {
+        let dir = dir.as_ref();
+        let mut files = HashMap::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if let (Ok(sw_corner), Ok((resolution, _))) = (
+                util::parse_sw_corner(&path),
+                util::extract_resolution(&path),
+            ) {
+                files.insert(sw_corner, (path, resolution));
+            }
+        }
+        Ok(Self {
+            dir: Some(DirTiles {
+                path: dir.to_path_buf(),
+                files,
+                cache: Mutex::default(),
+                capacity: DEFAULT_CACHE_CAPACITY,
+            }),
+            ..Self::default()
+        })
+    }
+
+    /// Returns this set, now keeping up to `capacity` tiles from its
+    /// directory memory-mapped at once, evicting the least recently
+    /// used beyond that.
+    ///
+    /// A capacity of zero is treated as one. Has no effect on sets
+    /// not opened with [`TileSet::open_dir`].
+    #[must_use]
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        if let Some(dir) = &mut self.dir {
+            dir.capacity = capacity.max(1);
+        }
+        self
+    }
+
+    /// Returns this set, now answering `elev` rather than `None` for
+    /// coordinates no tile covers, e.g. 0 for unmapped open ocean.
+    #[must_use]
+    pub fn with_gap_elevation(mut self, elev: Elev) -> Self {
+        self.gap_elevation = Some(elev);
+        self
+    }
+
+    /// Adds `tile`, returning the tile it replaces with the same SW
+    /// corner, if any.
+    ///
+    /// Inserted tiles take precedence over those in the set's
+    /// directory.
+    pub fn insert(&mut self, tile: Tile) -> Option<Tile> {
+        self.tiles.insert(tile.sw_corner(), tile)
+    }
+
+    /// Returns the number of tiles in the set, whether loaded or not.
+    pub fn len(&self) -> usize {
+        let unloaded = self.dir.as_ref().map_or(0, |dir| {
+            dir.files
+                .keys()
+                .filter(|sw_corner| !self.tiles.contains_key(sw_corner))
+                .count()
+        });
+        self.tiles.len() + unloaded
+    }
+
+    /// Returns `true` if the set contains no tiles.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the inserted tile whose SW corner is `sw_corner`, if
+    /// any.
+    pub fn tile(&self, sw_corner: Coord<i16>) -> Option<&Tile> {
+        self.tiles.get(&sw_corner)
+    }
+
+    /// Returns the elevation at `coord` from whichever tile contains
+    /// it.
+    ///
+    /// Adjacent tiles share their edge samples, and the tile whose
+    /// degree `coord` falls in answers first. Near an edge with no
+    /// such tile, a neighbor whose edge samples still cover `coord`
+    /// answers instead. Returns the gap elevation, `None` unless set
+    /// with [`TileSet::with_gap_elevation`], where no tile does, and
+    /// `None` if the covering tile fails to load; see
+    /// [`TileSet::try_get`] to tell these apart.
+    pub fn get(&self, coord: Coord<C>) -> Option<Elev> {
+        match self.lookup(coord, |tile| tile.get(coord)) {
+            Ok(Some(elev)) => Some(elev),
+            Ok(None) => self.gap_elevation,
+            Err(_) => None,
+        }
+    }
+
+    /// Returns the elevation at `coord`, as [`TileSet::get`], or why
+    /// there is none.
+    ///
+    /// # Errors
+    ///
+    /// - [`TileQueryError::OffTile`] if `coord` isn't a valid
+    ///   longitude and latitude.
+    /// - [`NasademError::Io`] with [`io::ErrorKind::NotFound`] naming
+    ///   the expected file, e.g. `N44W072.hgt`, if no tile covers
+    ///   `coord` and the set has no gap elevation.
+    /// - [`TileQueryError::Void`] if the sample is void.
+    /// - Any error from memory-mapping the covering tile.
+    pub fn try_get(&self, coord: Coord<C>) -> Result<Elev, NasademError> {
+        if !((-180.0..=180.0).contains(&coord.x) && (-90.0..=90.0).contains(&coord.y)) {
+            return Err(TileQueryError::OffTile { coord }.into());
+        }
+        match self.lookup(coord, |tile| tile.get(coord))? {
+            Some(crate::VOID) => Err(TileQueryError::Void.into()),
+            Some(elev) => Ok(elev),
+            None => self.gap_elevation.ok_or_else(|| {
+                let name = util::tile_name_for(coord);
+                let msg = match &self.dir {
+                    Some(dir) => format!("{name} is not in {}", dir.path.display()),
+                    None => format!("{name} is not in the tile set"),
+                };
+                io::Error::new(io::ErrorKind::NotFound, msg).into()
+            }),
+        }
+    }
+
+    /// Returns the rect spanning every tile's
+    /// [`extent_pixels`](Tile::extent_pixels), or `None` if the set is
+    /// empty.
+    ///
+    /// Tiles in the set's directory count without being loaded.
+    /// Coordinates within the bounds may still fall in gaps between
+    /// tiles.
+    pub fn bounds(&self) -> Option<Rect<C>> {
+        let unloaded = self
+            .dir
+            .iter()
+            .flat_map(|dir| dir.files.iter())
+            .map(|(&sw_corner, &(_, resolution))| pixel_extent(sw_corner, resolution));
+        self.tiles
+            .values()
+            .map(Tile::extent_pixels)
+            .chain(unloaded)
+            .reduce(|a, b| {
+                Rect::new(
+                    Coord {
+                        x: a.min().x.min(b.min().x),
+                        y: a.min().y.min(b.min().y),
+                    },
+                    Coord {
+                        x: a.max().x.max(b.max().x),
+                        y: a.max().y.max(b.max().y),
+                    },
+                )
+            })
+    }
+
+    /// Returns `(distance_m, elevation)` pairs sampled every `step_m`
+    /// meters along the great circle from `start` to `end`.
+    ///
+    /// The first sample is at `start` and the last at `end`, which may
+    /// be less than `step_m` past the one before it. Each elevation is
+    /// bilinearly interpolated from the four surrounding samples of
+    /// whichever tile covers the point, so diagonal paths don't alias
+    /// onto the grid, and rounded to the nearest meter. Points no tile
+    /// covers, whose tile fails to load, or next to a void sample are
+    /// `None`; the gap elevation is not used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step_m` isn't positive.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn profile(&self, start: Coord<C>, end: Coord<C>, step_m: f64) -> Vec<(f64, Option<Elev>)> {
+        assert!(step_m > 0.0, "step must be positive, got {step_m}");
+        let (start, end) = (Point::from(start), Point::from(end));
+        let distance_m = start.haversine_distance(&end);
+        let steps = (distance_m / step_m).ceil() as usize;
+        (0..=steps)
+            .map(|i| {
+                #[allow(clippy::cast_precision_loss)]
+                let along_m = (i as f64 * step_m).min(distance_m);
+                let point = if distance_m == 0.0 {
+                    start
+                } else {
+                    start.haversine_intermediate(&end, along_m / distance_m)
+                };
+                let coord = point.0;
+                let elev = self
+                    .lookup(coord, |tile| tile.get_bilinear(coord))
+                    .ok()
+                    .flatten()
+                    .map(|elev| elev.round() as Elev);
+                (along_m, elev)
+            })
+            .collect()
+    }
+
+    /// Returns the SW corners of the directory tiles currently
+    /// mapped, most recently used first.
+    #[cfg(test)]
+    pub(crate) fn cached(&self) -> Vec<Coord<i16>> {
+        self.dir.as_ref().map_or_else(Vec::new, |dir| {
+            let cache = dir.lock_cache();
+            cache.iter().map(|(sw_corner, _)| *sw_corner).collect()
+        })
+    }
+
+    /// Returns `query` of the inserted tile covering `coord`, or else
+    /// of the one in the set's directory, or `None` if no tile covers
+    /// it.
+    ///
+    /// `query` returns `None` for coordinates off its tile, so the
+    /// inserted tiles are tried in [`candidates`] order.
+    fn lookup<T>(
+        &self,
+        coord: Coord<C>,
+        query: impl Fn(&Tile) -> Option<T>,
+    ) -> Result<Option<T>, NasademError> {
+        if let Some(found) =
+            candidates(coord).find_map(|sw_corner| query(self.tiles.get(&sw_corner)?))
+        {
+            return Ok(Some(found));
+        }
+        let Some(dir) = &self.dir else {
+            return Ok(None);
+        };
+        let covering = candidates(coord).find(|sw_corner| {
+            dir.files.get(sw_corner).is_some_and(|&(_, resolution)| {
+                let extent = pixel_extent(*sw_corner, resolution);
+                (extent.min().x..=extent.max().x).contains(&coord.x)
+                    && (extent.min().y..=extent.max().y).contains(&coord.y)
+            })
+        });
+        match covering {
+            Some(sw_corner) => Ok(query(&*dir.tile(sw_corner)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Extend<Tile> for TileSet {
+    fn extend<I: IntoIterator<Item = Tile>>(&mut self, tiles: I) {
+        for tile in tiles {
+            self.insert(tile);
+        }
+    }
+}
+
+impl FromIterator<Tile> for TileSet {
+    fn from_iter<I: IntoIterator<Item = Tile>>(tiles: I) -> Self {
+        let mut set = Self::new();
+        set.extend(tiles);
+        set
+    }
+}
+
+/// Tiles in a directory, memory-mapped on first use.
+#[derive(Debug)]
+struct DirTiles {
+    path: PathBuf,
+
+    /// Path and resolution of each tile file, keyed by SW corner.
+    files: HashMap<Coord<i16>, (PathBuf, u8)>,
+
+    /// Mapped tiles, most recently used first.
+    cache: Mutex<VecDeque<(Coord<i16>, Arc<Tile>)>>,
+
+    /// Most tiles `cache` holds.
+    capacity: usize,
+}
+
+impl DirTiles {
+    /// Returns the tile whose SW corner is `sw_corner`, mapping it
+    /// and evicting the least recently used tile if it isn't cached.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no file for `sw_corner`.
+    fn tile(&self, sw_corner: Coord<i16>) -> Result<Arc<Tile>, NasademError> {
+        if let Some(tile) = self.cached(sw_corner) {
+            return Ok(tile);
+        }
+        // Map outside the lock so lookups of cached tiles don't wait
+        // on file I/O.
+        let tile = Arc::new(Tile::memmap(&self.files[&sw_corner].0)?);
+        let mut cache = self.lock_cache();
+        // Another thread may have mapped the same tile meanwhile.
+        if let Some(pos) = cache.iter().position(|(cached, _)| *cached == sw_corner) {
+            cache.remove(pos);
+        }
+        cache.truncate(self.capacity - 1);
+        cache.push_front((sw_corner, Arc::clone(&tile)));
+        Ok(tile)
+    }
+
+    /// Returns the cached tile whose SW corner is `sw_corner`, marking
+    /// it most recently used.
+    fn cached(&self, sw_corner: Coord<i16>) -> Option<Arc<Tile>> {
+        let mut cache = self.lock_cache();
+        let pos = cache.iter().position(|(cached, _)| *cached == sw_corner)?;
+        let entry = cache.remove(pos).expect("position is in bounds");
+        let tile = Arc::clone(&entry.1);
+        cache.push_front(entry);
+        Some(tile)
+    }
+
+    fn lock_cache(&self) -> MutexGuard<'_, VecDeque<(Coord<i16>, Arc<Tile>)>> {
+        // The cache is consistent between every statement, so a panic
+        // elsewhere while it was locked can't have corrupted it.
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Returns the SW corners of the tiles that may cover `coord`: its
+/// own degree's, then the eight around it, whose edge samples reach
+/// half a sample past their degree.
+fn candidates(coord: Coord<C>) -> impl Iterator<Item = Coord<i16>> {
+    let home = util::sw_corner_for(coord);
+    let neighbors = (-1..=1)
+        .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+        .filter(|&offset| offset != (0, 0))
+        .map(move |(dx, dy)| Coord {
+            x: home.x + dx,
+            y: home.y + dy,
+        });
+    std::iter::once(home).chain(neighbors)
+}
+
+/// Returns [`Tile::extent_pixels`] for a full-degree tile at
+/// `sw_corner` and `resolution` without loading it.
+fn pixel_extent(sw_corner: Coord<i16>, resolution: u8) -> Rect<C> {
+    let delta = C::from(resolution) * HALF_ARCSEC;
+    let (x, y) = (C::from(sw_corner.x), C::from(sw_corner.y));
+    Rect::new(
+        Coord {
+            x: x - delta,
+            y: y - delta,
+        },
+        Coord {
+            x: x + 1.0 + delta,
+            y: y + 1.0 + delta,
+        },
+    )
+}
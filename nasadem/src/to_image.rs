@@ -1,7 +1,88 @@
-use crate::{Elev, Tile};
-use image::{ImageBuffer, Luma};
+use crate::{Elev, Tile, C, VOID};
+use image::{ImageBuffer, Luma, Rgb, RgbImage};
 use num_traits::AsPrimitive;
 
+/// A color ramp for [`Tile::to_color_image`], from a tile's lowest
+/// elevation to its highest.
+///
+/// Stops are placed at fractions of the elevation range, from `0.0`
+/// at the lowest to `1.0` at the highest, and colors are interpolated
+/// linearly between them. Stops may be placed on any other scale,
+/// such as elevations in meters, as long as [`Palette::color`] is
+/// looked up on the same one. The default is a hypsometric ramp from
+/// lowland green through tan and brown to snowy white, with black
+/// voids.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    /// `(fraction, color)` stops, sorted by fraction.
+    stops: Vec<(f32, [u8; 3])>,
+
+    void: [u8; 3],
+}
+
+impl Palette {
+    /// Returns a palette through `stops`, each a fraction of the
+    /// elevation range and the color there, with black voids.
+    ///
+    /// Stops may be given in any order. Fractions below the lowest
+    /// stop or above the highest take its color.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stops` is empty or a fraction is `NaN`.
+    pub fn new(stops: impl IntoIterator<Item = (f32, [u8; 3])>) -> Self {
+        let mut stops: Vec<_> = stops.into_iter().collect();
+        assert!(!stops.is_empty(), "a palette needs at least one stop");
+        assert!(
+            stops.iter().all(|(frac, _)| !frac.is_nan()),
+            "palette stops can't be NaN"
+        );
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self {
+            stops,
+            void: [0, 0, 0],
+        }
+    }
+
+    /// Sets the color voids are filled with.
+    #[must_use]
+    pub fn with_void_color(self, void: [u8; 3]) -> Self {
+        Self { void, ..self }
+    }
+
+    /// Returns the color voids are filled with.
+    pub fn void_color(&self) -> Rgb<u8> {
+        Rgb(self.void)
+    }
+
+    /// Returns the color at `frac` of the elevation range.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn color(&self, frac: f32) -> Rgb<u8> {
+        let upper = self.stops.partition_point(|&(stop, _)| stop < frac);
+        let (lo_stop, lo) = self.stops[upper.saturating_sub(1)];
+        let Some(&(hi_stop, hi)) = self.stops.get(upper) else {
+            return Rgb(lo);
+        };
+        if upper == 0 {
+            return Rgb(hi);
+        }
+        let t = (frac - lo_stop) / (hi_stop - lo_stop);
+        Rgb([0, 1, 2]
+            .map(|c| (f32::from(lo[c]) + t * (f32::from(hi[c]) - f32::from(lo[c]))).round() as u8))
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new([
+            (0.0, [0x4a, 0x7c, 0x3e]),
+            (0.35, [0xc8, 0xc0, 0x6e]),
+            (0.7, [0x8b, 0x5a, 0x2b]),
+            (1.0, [0xff, 0xff, 0xff]),
+        ])
+    }
+}
+
 impl Tile {
     /// Returns an [`ImageBuffer`] of this tile.
     ///
@@ -11,19 +92,43 @@ impl Tile {
     /// The original, pre-scaled, elevation can be computed with:
     /// `(pixel_value / 16::MAX) * (max_elev - min_elev) + min_elev`
     ///
-    #[allow(clippy::cast_possible_truncation)]
+    /// A flat tile, such as a tombstone, has no range to scale, so
+    /// every pixel is `0`. See [`Tile::to_image_ranged`] to scale
+    /// several tiles alike.
     pub fn to_image<Pix>(&self) -> ImageBuffer<Luma<Pix>, Vec<Pix>>
+    where
+        Pix: image::Primitive + 'static,
+        f32: AsPrimitive<Pix> + From<Pix>,
+    {
+        let (min, max) = self.elevation_range();
+        self.to_image_ranged(min, max)
+    }
+
+    /// Returns an [`ImageBuffer`] of this tile scaled so that `min`
+    /// is `0` and `max` is the highest value of `Pix`.
+    ///
+    /// Samples below `min`, including voids, are `0`, and those above
+    /// `max` the highest value, so tiles rendered with the same
+    /// bounds, e.g. for a mosaic, map each elevation to the same
+    /// value. If `max` isn't above `min`, every pixel is `0`.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn to_image_ranged<Pix>(&self, min: Elev, max: Elev) -> ImageBuffer<Luma<Pix>, Vec<Pix>>
     where
         Pix: image::Primitive + 'static,
         f32: AsPrimitive<Pix> + From<Pix>,
     {
         let (x_dim, y_dim) = self.dimensions();
         let mut img = ImageBuffer::new(x_dim as u32, y_dim as u32);
-        let min_elev: f32 = self.min_elevation().into();
-        let max_elev: f32 = self.max_elevation().into();
+        let min_elev: f32 = min.into();
+        let max_elev: f32 = max.into();
+        let range = max_elev - min_elev;
         let scale = |elev: Elev| {
             let elev: f32 = elev.into();
-            (elev - min_elev) / (max_elev - min_elev) * f32::from(Pix::max_value())
+            if range > 0.0 {
+                ((elev - min_elev) / range).clamp(0.0, 1.0) * f32::from(Pix::max_value())
+            } else {
+                0.0
+            }
         };
         for sample in self.iter() {
             let (x, y) = sample.xy();
@@ -34,4 +139,67 @@ impl Tile {
         }
         img
     }
+
+    /// Returns a color image of this tile, coloring each sample by
+    /// `palette` at its height within the tile's elevation range.
+    ///
+    /// Unlike [`Tile::to_image`], which scales elevation to a single
+    /// gray channel, this maps the same range onto the palette's
+    /// stops, and fills voids with the palette's void color rather
+    /// than black. The range is the tile's cached
+    /// [`elevation_range`](Self::elevation_range), so a flat tile is
+    /// entirely the color at `0.0`. Pixel `(0, 0)` is the NW corner
+    /// of the tile.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn to_color_image(&self, palette: &Palette) -> RgbImage {
+        let (cols, rows) = self.dimensions();
+        let (min, max) = self.elevation_range();
+        let min_elev = f32::from(min);
+        let range = f32::from(max) - min_elev;
+        RgbImage::from_fn(cols as u32, rows as u32, |x, y| {
+            let elev = self.get_unchecked((x as usize, y as usize));
+            if elev == VOID {
+                return Rgb(palette.void);
+            }
+            let frac = if range > 0.0 {
+                (f32::from(elev) - min_elev) / range
+            } else {
+                0.0
+            };
+            palette.color(frac)
+        })
+    }
+
+    /// Returns a normal map of this tile for lighting terrain in 3D
+    /// engines.
+    ///
+    /// Each pixel encodes the unit surface normal of its sample,
+    /// computed from the same gradient as
+    /// [`Sample::slope`](crate::Sample::slope), in the tangent-space
+    /// convention engines consume: red is the eastward component,
+    /// green the northward (Y+ up, as in OpenGL), and blue the upward,
+    /// each remapped from `[-1, 1]` to `[0, 255]`. Flat ground is
+    /// therefore `(128, 128, 255)`.
+    ///
+    /// Void samples are encoded as flat. Void neighbors are replaced
+    /// by the center sample, and off-tile neighbors by the nearest
+    /// edge sample. Pixel `(0, 0)` is the NW corner of the tile.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn to_normal_map(&self) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+        let (cols, rows) = self.dimensions();
+        let encode = |v: C| ((v + 1.0) / 2.0 * 255.0).round() as u8;
+        ImageBuffer::from_fn(cols as u32, rows as u32, |x, y| {
+            let (x, y) = (x as usize, y as usize);
+            let (dx_m, dy_m) = self.row_spacing_m(y);
+            let (dzdx, dzdy) = self
+                .horn_gradient_with_spacing((x, y), dx_m, dy_m)
+                .unwrap_or((0.0, 0.0));
+            let norm = (dzdx * dzdx + dzdy * dzdy + 1.0).sqrt();
+            Rgb([
+                encode(-dzdx / norm),
+                encode(-dzdy / norm),
+                encode(1.0 / norm),
+            ])
+        })
+    }
 }
@@ -0,0 +1,74 @@
+//! One-call conversion between tile files.
+
+use crate::{NasademError, Tile};
+use std::path::Path;
+
+/// Compression of a tile written by [`Tile::transcode`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Compression {
+    /// A plain `.hgt` file, as written by [`Tile::save`].
+    #[default]
+    None,
+    /// An LZ4 frame compressed `.hgt.lz4` file, as written by
+    /// [`Tile::save_lz4`].
+    #[cfg(feature = "lz4")]
+    Lz4,
+    /// A Zstandard compressed `.hgt.zst` file, as written by
+    /// [`Tile::save_zstd`].
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+/// Parameters for [`Tile::transcode`].
+///
+/// The default copies a tile unchanged to a plain `.hgt` file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TranscodeOptions {
+    /// Fill voids with [`Tile::fill_voids`] using this search radius,
+    /// in cells.
+    pub fill_voids: Option<usize>,
+    /// Downsample by this factor with [`Tile::downsample`].
+    pub downsample: Option<usize>,
+    /// Compression of the output.
+    pub compression: Compression,
+}
+
+impl Tile {
+    /// Loads the tile at `src`, processes it as `options` ask, and
+    /// saves it to `dst`.
+    ///
+    /// `src` may be in any format [`Tile::load`] reads. Voids are
+    /// filled before downsampling, so filled samples contribute to
+    /// the averages. As with [`Tile::save`], if `dst` is a directory
+    /// the tile is written inside it, named after its SW corner.
+    ///
+    /// # Errors
+    ///
+    /// Any error from [`Tile::load`] or from saving the result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `options.downsample` is a factor
+    /// [`Tile::downsample`] rejects.
+    pub fn transcode<P, Q>(src: P, dst: Q, options: TranscodeOptions) -> Result<(), NasademError>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        let mut tile = Tile::load(src)?;
+        if let Some(radius_cells) = options.fill_voids {
+            // An all-void tile has nothing to fill from and is kept as is.
+            let _ = tile.fill_voids(radius_cells);
+        }
+        if let Some(factor) = options.downsample {
+            tile = tile.downsample(factor);
+        }
+        match options.compression {
+            Compression::None => tile.save(dst),
+            #[cfg(feature = "lz4")]
+            Compression::Lz4 => tile.save_lz4(dst),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => tile.save_zstd(dst),
+        }
+    }
+}
@@ -7,25 +7,105 @@ use crate::{
 };
 use std::path::Path;
 
-pub(crate) fn extract_resolution<P: AsRef<Path>>(
-    path: P,
-) -> Result<(u8, (usize, usize)), NasademError> {
-    const RES_1_ARCSECONDS_FILE_LEN: u64 = 3601 * 3601 * size_of::<u16>() as u64;
-    const RES_3_ARCSECONDS_FILE_LEN: u64 = 1201 * 1201 * size_of::<u16>() as u64;
-    match path.as_ref().metadata().map(|m| m.len())? {
-        RES_1_ARCSECONDS_FILE_LEN => Ok((1, (3601, 3601))),
-        RES_3_ARCSECONDS_FILE_LEN => Ok((3, (1201, 1201))),
-        invalid_len => Err(NasademError::HgtLen(
-            invalid_len,
-            path.as_ref().to_path_buf(),
-        )),
+/// Returns the resolution, in arcseconds per sample, and `(cols,
+/// rows)` of the `.hgt` file at `path`, inferred from its length
+/// without reading any samples.
+///
+/// For a gzip-compressed `.hgt.gz` file, the length is the
+/// uncompressed size recorded in the gzip trailer.
+///
+/// # Errors
+///
+/// Returns [`NasademError::HgtLen`] if the length does not match a
+/// supported tile (see below), or [`NasademError::Io`] if the file's
+/// metadata can't be read.
+///
+/// Supported tiles are square grids spanning one degree at a whole
+/// number of arcseconds per sample, such as 3601 x 3601 (1
+/// arcsecond), 1201 x 1201 (3 arcseconds), or 121 x 121 (30
+/// arcseconds).
+pub fn extract_resolution<P: AsRef<Path>>(path: P) -> Result<(u8, (usize, usize)), NasademError> {
+    let len = if is_gzip(&path) {
+        gzip_len(&path)?
+    } else {
+        path.as_ref().metadata().map(|m| m.len())?
+    };
+    resolution_for_len(len).ok_or_else(|| NasademError::HgtLen(len, path.as_ref().to_path_buf()))
+}
+
+/// Returns the resolution, in arcseconds per sample, and `(cols,
+/// rows)` of a `.hgt` file that is `len` bytes long, if it is a
+/// square grid spanning one degree at a whole number of arcseconds
+/// per sample, e.g. 3601 x 3601 for 1 arcsecond or 121 x 121 for 30.
+///
+/// Sub-arcsecond grids, such as 7201 x 7201 at half an arcsecond,
+/// aren't supported, as resolution is a whole number of arcseconds.
+pub(crate) fn resolution_for_len(len: u64) -> Option<(u8, (usize, usize))> {
+    const ARCSEC_PER_DEG: usize = 3600;
+    let dim = square_dim_for_len(len)?;
+    let intervals = dim.checked_sub(1).filter(|&n| n > 0)?;
+    if ARCSEC_PER_DEG % intervals != 0 {
+        return None;
+    }
+    let resolution = u8::try_from(ARCSEC_PER_DEG / intervals).ok()?;
+    Some((resolution, (dim, dim)))
+}
+
+/// Returns the side length of a square grid of `Elev` samples
+/// occupying exactly `len` bytes, if any.
+pub(crate) fn square_dim_for_len(len: u64) -> Option<usize> {
+    if len == 0 || len % size_of::<Elev>() as u64 != 0 {
+        return None;
     }
+    let samples = len / size_of::<Elev>() as u64;
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    let dim = (samples as f64).sqrt().round() as u64;
+    (dim * dim == samples).then_some(usize::try_from(dim).ok()?)
 }
 
-pub(crate) fn parse_sw_corner<P: AsRef<Path>>(path: P) -> Result<Coord<Elev>, NasademError> {
+/// Returns `true` if `path` has a `.gz` extension.
+pub(crate) fn is_gzip<P: AsRef<Path>>(path: P) -> bool {
+    has_extension(path, "gz")
+}
+
+/// Returns `true` if `path`'s extension is `ext`, ignoring case.
+pub(crate) fn has_extension<P: AsRef<Path>>(path: P, ext: &str) -> bool {
+    path.as_ref()
+        .extension()
+        .is_some_and(|path_ext| path_ext.eq_ignore_ascii_case(ext))
+}
+
+/// Returns the uncompressed length of the gzip file at `path`, as
+/// recorded (modulo 2^32) in the last four bytes of its trailer.
+fn gzip_len<P: AsRef<Path>>(path: P) -> std::io::Result<u64> {
+    use std::io::{Read, Seek, SeekFrom};
+    let mut file = std::fs::File::open(path)?;
+    file.seek(SeekFrom::End(-4))?;
+    let mut isize = [0u8; 4];
+    file.read_exact(&mut isize)?;
+    Ok(u64::from(u32::from_le_bytes(isize)))
+}
+
+/// Returns the SW corner encoded in a `.hgt` file name, e.g. `(x:
+/// -72, y: 44)` for `N44W072.hgt`, `N44W072.hgt.gz`,
+/// `N44W072.hgt.lz4`, or `N44W072.hgt.zst`.
+///
+/// # Errors
+///
+/// Returns [`NasademError::HgtName`] if the name is malformed.
+pub fn parse_sw_corner<P: AsRef<Path>>(path: P) -> Result<Coord<Elev>, NasademError> {
     let mk_err = || NasademError::HgtName(path.as_ref().to_owned());
-    let name = path
-        .as_ref()
+    let uncompressed =
+        if is_gzip(&path) || has_extension(&path, "lz4") || has_extension(&path, "zst") {
+            Path::new(path.as_ref().file_stem().ok_or_else(mk_err)?)
+        } else {
+            path.as_ref()
+        };
+    let name = uncompressed
         .file_stem()
         .and_then(std::ffi::OsStr::to_str)
         .ok_or_else(mk_err)?;
@@ -47,6 +127,42 @@ pub(crate) fn parse_sw_corner<P: AsRef<Path>>(path: P) -> Result<Coord<Elev>, Na
     Ok(Coord { x: lon, y: lat })
 }
 
+/// Returns the `.hgt` file name of the tile whose SW corner is
+/// `sw_corner`, e.g. `N44W072.hgt`.
+pub fn filename_for(Coord { x, y }: Coord<i16>) -> String {
+    let (n_s, lat) = {
+        let lat = y.abs();
+        let n_s = if y.is_negative() { 'S' } else { 'N' };
+        (n_s, lat)
+    };
+    let (e_w, lon) = {
+        let lon = x.abs();
+        let e_w = if x.is_negative() { 'W' } else { 'E' };
+        (e_w, lon)
+    };
+    format!("{n_s}{lat:02}{e_w}{lon:03}.hgt")
+}
+
+/// Returns the `.hgt` file name of the tile containing `coord`,
+/// e.g. `S01W001.hgt` for `(x: -0.5, y: -0.5)`.
+///
+/// A tile is named after its SW corner, which is the floor of the
+/// coordinate's longitude and latitude, so slightly negative
+/// coordinates belong to the tile south or west of the equator or
+/// prime meridian.
+pub fn tile_name_for(coord: Coord<C>) -> String {
+    filename_for(sw_corner_for(coord))
+}
+
+/// Returns the SW corner of the degree `coord` falls in.
+#[allow(clippy::cast_possible_truncation)]
+pub(crate) fn sw_corner_for(Coord { x, y }: Coord<C>) -> Coord<i16> {
+    Coord {
+        x: x.floor() as i16,
+        y: y.floor() as i16,
+    }
+}
+
 // Parses a big-endian Elev from a slice of two bytes.
 //
 // # Panics
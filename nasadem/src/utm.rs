@@ -0,0 +1,47 @@
+//! Elevation lookup by UTM coordinate.
+
+use crate::{geo::Coord, Elev, Tile};
+use proj4rs::{proj::Proj, transform::transform};
+
+/// Hemisphere of a UTM coordinate, which determines whether its
+/// northing has the 10,000 km false northing applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hemisphere {
+    /// Northings are meters north of the equator.
+    North,
+    /// Northings are 10,000 km minus meters south of the equator.
+    South,
+}
+
+impl Tile {
+    /// Returns the sample nearest the WGS84 UTM coordinate
+    /// (`easting`, `northing`) in `zone` and `hemisphere`.
+    ///
+    /// The coordinate is converted to geographic and looked up as
+    /// with [`Tile::get`]. Returns `None` if `zone` is not in `1..=60`,
+    /// the conversion fails, or the coordinate is outside the tile.
+    pub fn get_utm(
+        &self,
+        zone: u8,
+        hemisphere: Hemisphere,
+        easting: f64,
+        northing: f64,
+    ) -> Option<Elev> {
+        if !(1..=60).contains(&zone) {
+            return None;
+        }
+        let south = match hemisphere {
+            Hemisphere::North => "",
+            Hemisphere::South => " +south",
+        };
+        let utm =
+            Proj::from_proj_string(&format!("+proj=utm +zone={zone}{south} +datum=WGS84")).ok()?;
+        let wgs84 = Proj::from_proj_string("+proj=longlat +datum=WGS84").ok()?;
+        let mut point = (easting, northing, 0.0);
+        transform(&utm, &wgs84, &mut point).ok()?;
+        self.get(Coord {
+            x: point.0.to_degrees(),
+            y: point.1.to_degrees(),
+        })
+    }
+}
@@ -0,0 +1,261 @@
+//! Line of sight over a single tile.
+
+#[cfg(feature = "nalgebra")]
+use crate::VOID;
+use crate::{
+    geo::{Coord, HaversineDistance, Point},
+    Tile, ARCSEC_PER_DEG, C, MEAN_EARTH_RADIUS_M,
+};
+#[cfg(feature = "nalgebra")]
+use nalgebra::DMatrix;
+
+/// Speed of light in vacuum, in meters per second.
+const SPEED_OF_LIGHT_M_PER_S: C = 299_792_458.0;
+
+/// Distance, in samples, by which a path may overrun a whole number
+/// of samples, e.g. from rounding, without taking an extra step.
+const STEP_EPSILON: C = 1e-9;
+
+/// Ratio of the effective to the true earth radius under standard
+/// atmospheric refraction, which bends radio rays back toward the
+/// ground.
+pub const STANDARD_K_FACTOR: C = 4.0 / 3.0;
+
+impl Tile {
+    /// Returns `true` if no terrain between `observer` and `target`
+    /// rises above the straight line joining them.
+    ///
+    /// Each endpoint is a coordinate and an antenna height, in meters,
+    /// above the terrain there. The earth is treated as flat; see
+    /// [`Tile::line_of_sight_curved`] for long paths.
+    ///
+    /// The path is a straight line in latitude and longitude, walked
+    /// in one step per sample row or column it crosses, whichever is
+    /// more, so no sample it passes is skipped. Terrain at each step
+    /// is bilinearly interpolated from the four surrounding samples.
+    ///
+    /// Only this tile's samples are consulted, so for a cropped or
+    /// otherwise partial tile, steps outside its grid, like those
+    /// next to a void, are taken not to obstruct. Returns `false` if
+    /// either endpoint's own terrain is unknown, as then the line
+    /// can't be placed.
+    pub fn line_of_sight(&self, observer: (Coord<C>, C), target: (Coord<C>, C)) -> bool {
+        self.line_of_sight_impl(observer, target, None)
+    }
+
+    /// Returns `true` if no terrain between `observer` and `target`
+    /// rises above the straight line joining them, accounting for the
+    /// earth's curvature.
+    ///
+    /// As [`Tile::line_of_sight`], except that terrain at each step is
+    /// raised by the earth's bulge `d1 * d2 / (2 * k * R)`, where `d1`
+    /// and `d2` are the ground distances to each endpoint, `R` the
+    /// mean earth radius, and `k` the [`STANDARD_K_FACTOR`] of 4/3.
+    /// Over a 50 km path the bulge at the midpoint is about 37 m.
+    pub fn line_of_sight_curved(&self, observer: (Coord<C>, C), target: (Coord<C>, C)) -> bool {
+        self.line_of_sight_impl(
+            observer,
+            target,
+            Some(STANDARD_K_FACTOR * MEAN_EARTH_RADIUS_M),
+        )
+    }
+
+    /// Returns the clearance, in meters, of the first Fresnel zone of
+    /// a radio link at `freq_hz` between `observer` and `target` at
+    /// each step of the path.
+    ///
+    /// The path and its steps are those of [`Tile::line_of_sight`],
+    /// excluding the endpoints. At each step the zone's radius is
+    /// `sqrt(λ * d1 * d2 / (d1 + d2))`, where `λ` is the wavelength
+    /// and `d1` and `d2` the ground distances to each endpoint, and
+    /// the clearance is the height of the line of sight above the
+    /// terrain less that radius. Negative values mean terrain intrudes
+    /// into the zone. As with [`Tile::line_of_sight`], the earth is
+    /// treated as flat.
+    ///
+    /// Steps whose terrain is unknown are `NaN`, and the result is
+    /// empty if either endpoint's terrain is unknown.
+    pub fn fresnel_clearance(
+        &self,
+        observer: (Coord<C>, C),
+        target: (Coord<C>, C),
+        freq_hz: C,
+    ) -> Vec<C> {
+        let wavelength_m = SPEED_OF_LIGHT_M_PER_S / freq_hz;
+        self.walk(observer, target).map_or_else(Vec::new, |steps| {
+            steps
+                .map(|step| {
+                    let radius_m = (wavelength_m * step.from_observer * step.to_target
+                        / (step.from_observer + step.to_target))
+                        .sqrt();
+                    step.ground
+                        .map_or(C::NAN, |ground_m| step.los - ground_m - radius_m)
+                })
+                .collect()
+        })
+    }
+
+    /// Returns which samples within `radius_samples` of the sample at
+    /// `observer_xy` are visible from `observer_height` meters above
+    /// it.
+    ///
+    /// The output covers the square window of samples within
+    /// `radius_samples` rows and columns of the observer, clipped to
+    /// the tile's edges, so element `(0, 0)` is the sample at
+    /// `(observer_xy.0.saturating_sub(radius_samples),
+    /// observer_xy.1.saturating_sub(radius_samples))`. Samples in the
+    /// window's corners beyond `radius_samples` are hidden.
+    ///
+    /// Visibility is found by horizon-angle accumulation: a ray is
+    /// cast from the observer to every sample on the window's
+    /// perimeter, stepping one sample at a time along its major axis,
+    /// and a sample is visible from a ray if its elevation angle is no
+    /// lower than that of every sample before it on the ray. Distances
+    /// are true ground distances at the observer's latitude, and the
+    /// earth is treated as flat.
+    ///
+    /// The observer's own sample is always visible. Void samples are
+    /// hidden and don't block the view; if the observer's sample is
+    /// void, nothing else is visible.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `observer_xy` is off the tile.
+    #[cfg(feature = "nalgebra")]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn viewshed(
+        &self,
+        (ox, oy): (usize, usize),
+        observer_height: C,
+        radius_samples: usize,
+    ) -> DMatrix<bool> {
+        let (cols, rows) = self.dimensions();
+        assert!(
+            ox < cols && oy < rows,
+            "observer {:?} is off the {cols}x{rows} tile",
+            (ox, oy)
+        );
+        let (x0, y0) = (
+            ox.saturating_sub(radius_samples),
+            oy.saturating_sub(radius_samples),
+        );
+        let (x1, y1) = (
+            (ox + radius_samples).min(cols - 1),
+            (oy + radius_samples).min(rows - 1),
+        );
+        let mut visible = DMatrix::from_element(y1 - y0 + 1, x1 - x0 + 1, false);
+        visible[(oy - y0, ox - x0)] = true;
+        let observer_elev = self.get_unchecked((ox, oy));
+        if observer_elev == VOID {
+            return visible;
+        }
+        let eye_m = C::from(observer_elev) + observer_height;
+        let (dx_m, dy_m) = self.row_spacing_m(oy);
+        let radius_sq = (radius_samples * radius_samples) as isize;
+
+        let perimeter = (x0..=x1)
+            .flat_map(|x| [(x, y0), (x, y1)])
+            .chain((y0..=y1).flat_map(|y| [(x0, y), (x1, y)]));
+        for (tx, ty) in perimeter {
+            let (run, rise) = (tx as isize - ox as isize, ty as isize - oy as isize);
+            let steps = run.abs().max(rise.abs());
+            let mut horizon = C::NEG_INFINITY;
+            for i in 1..=steps {
+                let cx = ((run * i) as C / steps as C).round() as isize;
+                let cy = ((rise * i) as C / steps as C).round() as isize;
+                if cx * cx + cy * cy > radius_sq {
+                    break;
+                }
+                let (x, y) = ((ox as isize + cx) as usize, (oy as isize + cy) as usize);
+                let elev = self.get_unchecked((x, y));
+                if elev == VOID {
+                    continue;
+                }
+                let distance_m = (cx as C * dx_m).hypot(cy as C * dy_m);
+                let slope = (C::from(elev) - eye_m) / distance_m;
+                if slope >= horizon {
+                    visible[(y - y0, x - x0)] = true;
+                    horizon = slope;
+                }
+            }
+        }
+        visible
+    }
+
+    fn line_of_sight_impl(
+        &self,
+        observer: (Coord<C>, C),
+        target: (Coord<C>, C),
+        effective_radius_m: Option<C>,
+    ) -> bool {
+        let Some(mut steps) = self.walk(observer, target) else {
+            return false;
+        };
+        steps.all(|step| {
+            let Some(ground_m) = step.ground else {
+                return true;
+            };
+            let bulge_m = effective_radius_m.map_or(0.0, |radius_m| {
+                step.from_observer * step.to_target / (2.0 * radius_m)
+            });
+            ground_m + bulge_m <= step.los
+        })
+    }
+
+    /// Returns the steps strictly between `observer` and `target`, or
+    /// `None` if either endpoint's terrain is unknown.
+    ///
+    /// See [`Tile::line_of_sight`] for how the path is walked.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn walk(
+        &self,
+        (observer, observer_height_m): (Coord<C>, C),
+        (target, target_height_m): (Coord<C>, C),
+    ) -> Option<impl Iterator<Item = PathStep> + '_> {
+        let observer_m = self.get_bilinear(observer)? + observer_height_m;
+        let target_m = self.get_bilinear(target)? + target_height_m;
+        let samples_per_deg = ARCSEC_PER_DEG / C::from(self.resolution());
+        let steps = (((target.x - observer.x).abs() * samples_per_deg)
+            .max((target.y - observer.y).abs() * samples_per_deg)
+            - STEP_EPSILON)
+            .ceil() as usize;
+        let distance_m = Point::from(observer).haversine_distance(&Point::from(target));
+        Some((1..steps).map(move |i| {
+            let t = i as C / steps as C;
+            let coord = Coord {
+                x: observer.x + (target.x - observer.x) * t,
+                y: observer.y + (target.y - observer.y) * t,
+            };
+            PathStep {
+                from_observer: t * distance_m,
+                to_target: (1.0 - t) * distance_m,
+                los: observer_m + (target_m - observer_m) * t,
+                ground: self.get_bilinear(coord),
+            }
+        }))
+    }
+}
+
+/// A step along a path between two antennas, in meters.
+struct PathStep {
+    /// Ground distance from the observer.
+    from_observer: C,
+
+    /// Ground distance to the target.
+    to_target: C,
+
+    /// Height of the straight line between the antennas.
+    los: C,
+
+    /// Interpolated terrain height, if known.
+    ground: Option<C>,
+}
@@ -9,3 +9,10 @@
 /// - <https://sci-hub.se/https://doi.org/10.1007/s001900050278>
 /// - <https://en.wikipedia.org/wiki/Earth_radius#Mean_radius>
 pub const MEAN_EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// Speed of light in a vacuum in meters per second.
+pub const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+
+/// Approximate ground distance of one arcsecond of latitude in
+/// meters.
+pub const METERS_PER_ARCSEC: f64 = 30.87;
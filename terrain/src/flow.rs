@@ -0,0 +1,153 @@
+//! Surface water flow over the terrain.
+
+use crate::{constants::METERS_PER_ARCSEC, tiles::C, TerrainError, Tiles};
+use geo::geometry::{Coord, LineString};
+use nasadem::VOID;
+
+/// Most steps [`Tiles::flow_path`] takes before giving up.
+const MAX_FLOW_STEPS: usize = 100_000;
+
+/// Grid offsets `(dx, dy)`, with y north, of a cell's eight
+/// neighbors.
+const D8: [(i64, i64); 8] = [
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+];
+
+impl Tiles {
+    /// Returns the path water would take downhill from `start`.
+    ///
+    /// Starting at the sample nearest `start`, the path repeatedly
+    /// steps to whichever of the eight neighboring samples (D8) has
+    /// the steepest descent, with slopes measured over true ground
+    /// distance, continuing across tile boundaries. It ends at a
+    /// flat or a sink, where no neighbor is lower, or before stepping
+    /// onto a void or a missing tile. Samples lie on a single grid at
+    /// the resolution of the tile containing `start`.
+    ///
+    /// Each step strictly descends, so the path can't revisit a
+    /// sample, and it is capped at 100 000 steps regardless.
+    ///
+    /// The result is a line through the centers of the visited
+    /// samples, or empty if `start` itself has no data.
+    pub fn flow_path(&self, start: Coord<C>) -> Result<LineString<C>, TerrainError> {
+        let arcsec_per_sample = f64::from(self.get(start)?.resolution());
+        let samples_per_deg = 3600.0 / arcsec_per_sample;
+        let dy_m = arcsec_per_sample * METERS_PER_ARCSEC;
+        #[allow(clippy::cast_precision_loss)]
+        let to_geo = |(x, y): (i64, i64)| Coord {
+            x: x as f64 / samples_per_deg,
+            y: y as f64 / samples_per_deg,
+        };
+        let elevation = |cell| -> Result<Option<f64>, TerrainError> {
+            let coord = to_geo(cell);
+            let tile = self.get(coord)?;
+            if tile.is_tombstone() {
+                return Ok(None);
+            }
+            Ok(match tile.get(coord) {
+                Some(VOID) | None => None,
+                Some(elev) => Some(f64::from(elev)),
+            })
+        };
+
+        #[allow(clippy::cast_possible_truncation)]
+        let mut cell = (
+            (start.x * samples_per_deg).round() as i64,
+            (start.y * samples_per_deg).round() as i64,
+        );
+        let Some(mut elev) = elevation(cell)? else {
+            return Ok(LineString::new(Vec::new()));
+        };
+        let mut path = vec![to_geo(cell)];
+        while path.len() <= MAX_FLOW_STEPS {
+            let dx_m = dy_m * to_geo(cell).y.to_radians().cos();
+            let mut steepest: Option<((i64, i64), f64, f64)> = None;
+            for (dx, dy) in D8 {
+                let neighbor = (cell.0 + dx, cell.1 + dy);
+                let Some(neighbor_elev) = elevation(neighbor)? else {
+                    continue;
+                };
+                #[allow(clippy::cast_precision_loss)]
+                let run_m = (dx as f64 * dx_m).hypot(dy as f64 * dy_m);
+                let slope = (elev - neighbor_elev) / run_m;
+                if slope > 0.0 && steepest.map_or(true, |(_, _, max)| slope > max) {
+                    steepest = Some((neighbor, neighbor_elev, slope));
+                }
+            }
+            let Some((next, next_elev, _)) = steepest else {
+                break;
+            };
+            (cell, elev) = (next, next_elev);
+            path.push(to_geo(cell));
+        }
+        Ok(LineString::new(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{TileMode, Tiles};
+    use geo::coord;
+
+    /// Writes a 3-arcsecond N44W072 tile with a paraboloid hill
+    /// rising 500 m above a flat 100 m plain, its summit at the
+    /// tile's center and its base 200 samples away.
+    #[allow(clippy::cast_precision_loss)]
+    fn dome_dir() -> tempfile::TempDir {
+        const DIM: usize = 1201;
+        let dir = tempfile::tempdir().unwrap();
+        let center = (DIM / 2) as f64;
+        let samples: Vec<u8> = (0..DIM * DIM)
+            .flat_map(|idx| {
+                let x = (idx % DIM) as f64 - center;
+                let y = (idx / DIM) as f64 - center;
+                let r = x.hypot(y) / 200.0;
+                #[allow(clippy::cast_possible_truncation)]
+                let elev = (100.0 + 500.0 * (1.0 - r * r).max(0.0)).round() as i16;
+                elev.to_be_bytes()
+            })
+            .collect();
+        std::fs::write(dir.path().join("N44W072.hgt"), samples).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_flow_path() {
+        let dir = dome_dir();
+        let tiles = Tiles::new(dir.path().to_path_buf(), TileMode::InMem).unwrap();
+        let summit = coord!(x: -71.5, y: 44.5);
+        // Upper slope, 50 samples east of the summit.
+        let start = coord!(x: -71.5 + 50.0 * 3.0 / 3600.0, y: 44.5);
+        let path = tiles.flow_path(start).unwrap();
+        assert!(path.0.len() > 100, "{}", path.0.len());
+
+        let elevs: Vec<i16> = path
+            .coords()
+            .map(|&c| tiles.get(c).unwrap().get(c).unwrap())
+            .collect();
+        assert!(elevs.windows(2).all(|w| w[1] < w[0]), "{elevs:?}");
+        assert_eq!(*elevs.last().unwrap(), 100);
+
+        // Water runs straight away from the summit, east to the base.
+        let end = *path.0.last().unwrap();
+        assert!((end.y - 44.5).abs() < 1e-9);
+        assert!(end.x > start.x);
+        let samples_from_summit = (end.x - summit.x) * 3600.0 / 3.0;
+        assert!((195.0..=205.0).contains(&samples_from_summit));
+
+        // The plain is flat, so water goes nowhere.
+        let plain = coord!(x: -71.9, y: 44.1);
+        assert_eq!(tiles.flow_path(plain).unwrap().0.len(), 1);
+
+        // Missing tiles have no data.
+        let ocean = coord!(x: -40.5, y: 30.5);
+        assert!(tiles.flow_path(ocean).unwrap().0.is_empty());
+    }
+}
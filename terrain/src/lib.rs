@@ -77,9 +77,13 @@
 
 mod constants;
 mod error;
+mod flow;
 mod math;
 mod profile;
+#[cfg(feature = "cache")]
+mod profile_cache;
 mod tiles;
+mod visibility;
 
 pub use crate::{
     constants::MEAN_EARTH_RADIUS,
@@ -88,6 +92,9 @@ pub use crate::{
     tiles::{TileMode, Tiles},
 };
 
+#[cfg(feature = "cache")]
+pub use crate::profile_cache::ProfileCache;
+
 pub use geo;
 
 #[cfg(test)]
@@ -0,0 +1,39 @@
+use num_traits::Float;
+
+/// Returns the loss, in dB, of a single ideal knife-edge with
+/// Fresnel-Kirchhoff diffraction parameter `nu`.
+///
+/// Uses the approximation from ITU-R P.526-15 eq. 31, which is within
+/// ~0.1 dB of the exact Fresnel integral for `nu > -0.78`, below which
+/// the loss is taken as zero.
+pub fn knife_edge_loss_db<T: Float>(nu: T) -> T {
+    let c = |v: f64| T::from(v).unwrap();
+    if nu <= c(-0.78) {
+        T::zero()
+    } else {
+        let v = nu - c(0.1);
+        c(6.9) + c(20.0) * ((v * v + T::one()).sqrt() + v).log10()
+    }
+}
+
+/// Returns the Fresnel-Kirchhoff diffraction parameter `ν` for an
+/// obstruction `height_m` above the direct path, `d1_m` and `d2_m`
+/// from either end.
+pub fn fresnel_nu<T: Float>(height_m: T, d1_m: T, d2_m: T, wavelength_m: T) -> T {
+    let two = T::one() + T::one();
+    height_m * (two * (d1_m + d2_m) / (wavelength_m * d1_m * d2_m)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::knife_edge_loss_db;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_knife_edge_loss() {
+        assert_relative_eq!(knife_edge_loss_db(-1.0), 0.0);
+        // Grazing incidence is ~6 dB.
+        assert_relative_eq!(knife_edge_loss_db(0.0), 6.03, epsilon = 0.01);
+        assert_relative_eq!(knife_edge_loss_db(1.0), 13.93, epsilon = 0.01);
+    }
+}
@@ -35,7 +35,6 @@ impl<T: CoordFloat> HaversineIter<T> {
         }
     }
 
-    #[allow(dead_code)]
     pub fn step_size_m(&self) -> T {
         self.step_size_m
     }
@@ -66,6 +65,34 @@ impl<T: CoordFloat + AsPrimitive<usize>> ExactSizeIterator for HaversineIter<T>
     }
 }
 
+/// Returns the point `fraction` of the way along the great circle
+/// from `start` to `end`.
+pub fn haversine_intermediate<T>(start: &Point<T>, end: &Point<T>, fraction: T) -> Point<T>
+where
+    T: CoordFloat + FromPrimitive,
+{
+    get_point(&get_params(start, end), fraction)
+}
+
+/// Returns the point `distance_m` along the great circle leaving
+/// `start` at `bearing_rad` clockwise from north.
+pub fn haversine_destination<T>(start: &Point<T>, bearing_rad: T, distance_m: T) -> Point<T>
+where
+    T: CoordFloat + FromPrimitive,
+{
+    let d = distance_m / T::from(MEAN_EARTH_RADIUS).unwrap();
+    let lat1 = start.y().to_radians();
+    let lon1 = start.x().to_radians();
+    let (lat1_sin, lat1_cos) = lat1.sin_cos();
+    let (d_sin, d_cos) = d.sin_cos();
+    let (bearing_sin, bearing_cos) = bearing_rad.sin_cos();
+
+    let lat2 = (lat1_sin * d_cos + lat1_cos * d_sin * bearing_cos).asin();
+    let lon2 = lon1 + (bearing_sin * d_sin * lat1_cos).atan2(d_cos - lat1_sin * lat2.sin());
+
+    Point::new(lon2.to_degrees(), lat2.to_degrees())
+}
+
 #[allow(clippy::many_single_char_names)]
 struct HaversineParams<T> {
     d: T,
@@ -149,10 +176,24 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::HaversineIter;
+    use super::{haversine_destination, HaversineIter};
+    use crate::constants::MEAN_EARTH_RADIUS;
     use approx::assert_relative_eq;
     use geo::point;
 
+    #[test]
+    fn test_haversine_destination() {
+        let start = point!(x: -71.0, y: 44.0);
+        // One degree of arc due north and due east along the equator.
+        let one_deg_m = MEAN_EARTH_RADIUS.to_radians();
+        let north = haversine_destination(&start, 0.0, one_deg_m);
+        assert_relative_eq!(north.x(), -71.0, epsilon = 1e-9);
+        assert_relative_eq!(north.y(), 45.0, epsilon = 1e-9);
+        let east = haversine_destination(&point!(x: 10.0, y: 0.0), 90_f64.to_radians(), one_deg_m);
+        assert_relative_eq!(east.x(), 11.0, epsilon = 1e-9);
+        assert_relative_eq!(east.y(), 0.0, epsilon = 1e-9);
+    }
+
     #[test]
     fn test_haversine_iter() {
         let start = point!(x: -0.5, y: -0.5);
@@ -1,5 +1,13 @@
+mod diffraction;
 mod elevation_angle;
 mod haversine;
 mod linspace;
+mod rhumb;
 
-pub(crate) use {elevation_angle::elevation_angle, haversine::HaversineIter, linspace::linspace};
+pub(crate) use {
+    diffraction::{fresnel_nu, knife_edge_loss_db},
+    elevation_angle::elevation_angle,
+    haversine::{haversine_destination, haversine_intermediate, HaversineIter},
+    linspace::linspace,
+    rhumb::{rhumb_distance, rhumb_intermediate},
+};
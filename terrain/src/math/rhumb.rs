@@ -0,0 +1,146 @@
+//! Rhumb line (loxodrome) routines on a spherical earth.
+//!
+//! A rhumb line crosses every meridian at the same angle, so it is a
+//! straight line on a Mercator projection. It is generally longer
+//! than the great circle between the same points.
+
+use crate::constants::MEAN_EARTH_RADIUS;
+use geo::{CoordFloat, Point};
+use num_traits::{FloatConst, FromPrimitive};
+
+/// Below this difference in Mercator latitude, in radians, a rhumb
+/// line is treated as running due east or west.
+const PARALLEL_EPSILON: f64 = 1e-12;
+
+/// Returns the length, in meters, of the rhumb line from `start` to
+/// `end`.
+pub fn rhumb_distance<T>(start: &Point<T>, end: &Point<T>) -> T
+where
+    T: CoordFloat + FromPrimitive + FloatConst,
+{
+    let RhumbParams {
+        lat1,
+        d_lat,
+        d_lon,
+        d_psi,
+        ..
+    } = get_params(start, end);
+    // Ratio of north-south distance to change in Mercator latitude.
+    let q = if d_psi.abs() > T::from(PARALLEL_EPSILON).unwrap() {
+        d_lat / d_psi
+    } else {
+        lat1.cos()
+    };
+    d_lat.hypot(q * d_lon) * T::from(MEAN_EARTH_RADIUS).unwrap()
+}
+
+/// Returns the point `fraction` of the way along the rhumb line from
+/// `start` to `end`.
+pub fn rhumb_intermediate<T>(start: &Point<T>, end: &Point<T>, fraction: T) -> Point<T>
+where
+    T: CoordFloat + FromPrimitive + FloatConst,
+{
+    let RhumbParams {
+        lat1,
+        lon1,
+        d_lat,
+        d_lon,
+        psi1,
+        d_psi,
+    } = get_params(start, end);
+    // Latitude changes linearly with distance along a rhumb line, and
+    // longitude linearly with Mercator latitude.
+    let lat = lat1 + fraction * d_lat;
+    let lon = if d_psi.abs() > T::from(PARALLEL_EPSILON).unwrap() {
+        lon1 + d_lon * (mercator_lat(lat) - psi1) / d_psi
+    } else {
+        lon1 + fraction * d_lon
+    };
+    Point::new(wrap_lon(lon).to_degrees(), lat.to_degrees())
+}
+
+/// Start and change in position, in radians, of a rhumb line.
+struct RhumbParams<T> {
+    lat1: T,
+    lon1: T,
+    d_lat: T,
+    /// Change in longitude, the short way around.
+    d_lon: T,
+    /// Mercator latitude of the start.
+    psi1: T,
+    /// Change in Mercator latitude.
+    d_psi: T,
+}
+
+fn get_params<T>(start: &Point<T>, end: &Point<T>) -> RhumbParams<T>
+where
+    T: CoordFloat + FloatConst,
+{
+    let lat1 = start.y().to_radians();
+    let lon1 = start.x().to_radians();
+    let lat2 = end.y().to_radians();
+    let lon2 = end.x().to_radians();
+    let psi1 = mercator_lat(lat1);
+    RhumbParams {
+        lat1,
+        lon1,
+        d_lat: lat2 - lat1,
+        d_lon: wrap_lon(lon2 - lon1),
+        psi1,
+        d_psi: mercator_lat(lat2) - psi1,
+    }
+}
+
+/// Returns the Mercator projected latitude of `lat_rad`.
+fn mercator_lat<T: CoordFloat + FloatConst>(lat_rad: T) -> T {
+    let two = T::one() + T::one();
+    (T::FRAC_PI_4() + lat_rad / two).tan().ln()
+}
+
+/// Wraps `lon_rad` into `[-π, π]`.
+fn wrap_lon<T: CoordFloat + FloatConst>(lon_rad: T) -> T {
+    if lon_rad.abs() <= T::PI() {
+        lon_rad
+    } else {
+        let two_pi = T::PI() + T::PI();
+        lon_rad - two_pi * ((lon_rad + T::PI()) / two_pi).floor()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rhumb_distance, rhumb_intermediate};
+    use crate::constants::MEAN_EARTH_RADIUS;
+    use approx::assert_relative_eq;
+    use geo::point;
+
+    #[test]
+    fn test_rhumb() {
+        // Due north along a meridian is also a great circle.
+        let one_deg_m = MEAN_EARTH_RADIUS.to_radians();
+        let (a, b) = (point!(x: -71.0, y: 44.0), point!(x: -71.0, y: 45.0));
+        assert_relative_eq!(rhumb_distance(&a, &b), one_deg_m, epsilon = 1e-6);
+        let mid = rhumb_intermediate(&a, &b, 0.5);
+        assert_relative_eq!(mid.x(), -71.0, epsilon = 1e-9);
+        assert_relative_eq!(mid.y(), 44.5, epsilon = 1e-9);
+
+        // Due east follows the parallel, shrinking with latitude.
+        let (a, b) = (point!(x: -72.0, y: 60.0), point!(x: -71.0, y: 60.0));
+        assert_relative_eq!(rhumb_distance(&a, &b), one_deg_m / 2.0, epsilon = 1e-6);
+        let mid = rhumb_intermediate(&a, &b, 0.25);
+        assert_relative_eq!(mid.x(), -71.75, epsilon = 1e-9);
+        assert_relative_eq!(mid.y(), 60.0, epsilon = 1e-9);
+
+        // Across the antimeridian, the short way around.
+        let (a, b) = (point!(x: 179.5, y: 0.0), point!(x: -179.5, y: 0.0));
+        assert_relative_eq!(rhumb_distance(&a, &b), one_deg_m, epsilon = 1e-6);
+        let mid = rhumb_intermediate(&a, &b, 0.75);
+        assert_relative_eq!(mid.x(), -179.75, epsilon = 1e-9);
+
+        // Endpoints are exact on a diagonal.
+        let (a, b) = (point!(x: -71.95, y: 44.05), point!(x: -71.05, y: 44.95));
+        let end = rhumb_intermediate(&a, &b, 1.0);
+        assert_relative_eq!(end.x(), b.x(), epsilon = 1e-9);
+        assert_relative_eq!(end.y(), b.y(), epsilon = 1e-9);
+    }
+}
@@ -1,6 +1,11 @@
+#[cfg(feature = "cache")]
+use crate::profile_cache::ProfileKey;
 use crate::{
-    constants::MEAN_EARTH_RADIUS,
-    math::{elevation_angle, linspace, HaversineIter},
+    constants::{MEAN_EARTH_RADIUS, METERS_PER_ARCSEC, SPEED_OF_LIGHT},
+    math::{
+        elevation_angle, fresnel_nu, haversine_intermediate, knife_edge_loss_db, linspace,
+        rhumb_distance, rhumb_intermediate, HaversineIter,
+    },
     TerrainError, Tiles,
 };
 use geo::{
@@ -46,6 +51,280 @@ where
     }
 }
 
+impl<C> Profile<C>
+where
+    C: CoordFloat + FromPrimitive + FloatConst + AsPrimitive<usize>,
+    f64: From<C>,
+{
+    /// Returns a profile from `start` to `end` whose points are
+    /// densest where the terrain bends.
+    ///
+    /// The path is first sampled at most every `max_step_m`, as with
+    /// [`ProfileBuilder::max_step`]. Each segment is then recursively
+    /// halved while the terrain's second derivative across it,
+    /// estimated from the elevation at its midpoint, exceeds
+    /// `curvature_tol` (in 1/m), down to a spacing of about one
+    /// sample of the tile containing `start`. This captures peaks
+    /// and ridges without oversampling plains.
+    ///
+    /// `distances_m` holds each point's actual, non-uniform distance
+    /// from `start`. No antenna heights or earth curvature are
+    /// applied, so `los_elev_m` runs straight from the first to the
+    /// last terrain sample.
+    pub fn adaptive(
+        start: Coord<C>,
+        end: Coord<C>,
+        tiles: &Tiles,
+        max_step_m: C,
+        curvature_tol: C,
+    ) -> Result<Self, TerrainError> {
+        let to_f64 = |coord: Coord<C>| Coord {
+            x: coord.x.into(),
+            y: coord.y.into(),
+        };
+        let min_step_m = C::from(tiles.get(to_f64(start))?.resolution()).unwrap()
+            * C::from(METERS_PER_ARCSEC).unwrap();
+        let densifier = Densifier {
+            start: Point::from(start),
+            end: Point::from(end),
+            distance_m: Point::from(start).haversine_distance(&Point::from(end)),
+            min_step_m,
+            curvature_tol,
+            tiles,
+        };
+
+        let coarse: Vec<Point<C>> =
+            HaversineIter::new(densifier.start, max_step_m, densifier.end).collect();
+        let segments = C::from(coarse.len() - 1).unwrap();
+        let mut samples: Vec<AdaptiveSample<C>> = Vec::with_capacity(coarse.len());
+        for (i, point) in coarse.into_iter().enumerate() {
+            let sample = AdaptiveSample {
+                fraction: C::from(i).unwrap() / segments,
+                point,
+                elev_m: densifier.elevation(point)?,
+            };
+            if let Some(&prev) = samples.last() {
+                densifier.refine(prev, sample, &mut samples)?;
+            }
+            samples.push(sample);
+        }
+
+        let first_m = samples.first().map_or(C::zero(), |s| s.elev_m);
+        let last_m = samples.last().map_or(C::zero(), |s| s.elev_m);
+        Ok(Profile {
+            distances_m: samples
+                .iter()
+                .map(|s| s.fraction * densifier.distance_m)
+                .collect(),
+            great_circle: samples.iter().map(|s| s.point).collect(),
+            terrain_elev_m: samples.iter().map(|s| s.elev_m).collect(),
+            los_elev_m: samples
+                .iter()
+                .map(|s| first_m + (last_m - first_m) * s.fraction)
+                .collect(),
+        })
+    }
+
+    /// Returns a profile from `start` to `end` along the rhumb line,
+    /// the path of constant bearing, rather than the great circle.
+    ///
+    /// Points are evenly spaced at most `max_step_m` apart, and are
+    /// stored in `great_circle` despite not lying on one. As with
+    /// [`Profile::adaptive`], no antenna heights or earth curvature
+    /// are applied.
+    pub fn rhumb(
+        start: Coord<C>,
+        end: Coord<C>,
+        max_step_m: C,
+        tiles: &Tiles,
+    ) -> Result<Self, TerrainError> {
+        let (start, end) = (Point::from(start), Point::from(end));
+        let distance_m = rhumb_distance(&start, &end);
+        let steps = (distance_m / max_step_m).ceil().max(C::one());
+        let n = steps.as_() + 1;
+        let points: Box<[Point<C>]> = (0..n)
+            .map(|i| rhumb_intermediate(&start, &end, C::from(i).unwrap() / steps))
+            .collect();
+        let terrain_elev_m = points
+            .iter()
+            .map(|point| {
+                let coord = Coord {
+                    x: point.0.x.into(),
+                    y: point.0.y.into(),
+                };
+                let elevation = tiles.get(coord)?.get_unchecked(coord);
+                Ok(C::from(elevation).unwrap())
+            })
+            .collect::<Result<Box<[C]>, TerrainError>>()?;
+        Ok(Profile {
+            distances_m: linspace(C::zero(), distance_m, n).collect(),
+            los_elev_m: linspace(terrain_elev_m[0], terrain_elev_m[n - 1], n).collect(),
+            great_circle: points,
+            terrain_elev_m,
+        })
+    }
+}
+
+/// A point of an adaptive profile.
+#[derive(Clone, Copy)]
+struct AdaptiveSample<C: CoordFloat> {
+    /// Fraction of the path from its start.
+    fraction: C,
+    point: Point<C>,
+    elev_m: C,
+}
+
+/// Recursively subdivides adaptive profile segments.
+struct Densifier<'a, C: CoordFloat> {
+    start: Point<C>,
+    end: Point<C>,
+    distance_m: C,
+    min_step_m: C,
+    curvature_tol: C,
+    tiles: &'a Tiles,
+}
+
+impl<C> Densifier<'_, C>
+where
+    C: CoordFloat + FromPrimitive,
+    f64: From<C>,
+{
+    fn elevation(&self, point: Point<C>) -> Result<C, TerrainError> {
+        let coord = Coord {
+            x: point.0.x.into(),
+            y: point.0.y.into(),
+        };
+        let elevation = self.tiles.get(coord)?.get_unchecked(coord);
+        Ok(C::from(elevation).unwrap())
+    }
+
+    /// Appends points strictly between `a` and `b` to `out`, in
+    /// order.
+    fn refine(
+        &self,
+        a: AdaptiveSample<C>,
+        b: AdaptiveSample<C>,
+        out: &mut Vec<AdaptiveSample<C>>,
+    ) -> Result<(), TerrainError> {
+        let two = C::one() + C::one();
+        let half_step_m = (b.fraction - a.fraction) * self.distance_m / two;
+        if half_step_m < self.min_step_m {
+            return Ok(());
+        }
+        let fraction = (a.fraction + b.fraction) / two;
+        let point = haversine_intermediate(&self.start, &self.end, fraction);
+        let mid = AdaptiveSample {
+            fraction,
+            point,
+            elev_m: self.elevation(point)?,
+        };
+        // Central second difference over the two half segments.
+        let curvature = (a.elev_m - two * mid.elev_m + b.elev_m) / (half_step_m * half_step_m);
+        if curvature.abs() > self.curvature_tol {
+            self.refine(a, mid, out)?;
+            out.push(mid);
+            self.refine(mid, b, out)?;
+        }
+        Ok(())
+    }
+}
+
+impl<C> Profile<C>
+where
+    C: CoordFloat,
+    f64: From<C>,
+{
+    /// Returns `true` if no terrain between the endpoints rises above
+    /// the line of sight.
+    ///
+    /// This holds for profiles built with or without earth curvature.
+    pub fn has_line_of_sight(&self) -> bool {
+        let n = self.terrain_elev_m.len();
+        self.terrain_elev_m
+            .iter()
+            .zip(self.los_elev_m.iter())
+            .skip(1)
+            .take(n.saturating_sub(2))
+            .all(|(&terrain, &los)| terrain <= los)
+    }
+
+    /// Returns the terrain diffraction loss, in dB, of a radio link
+    /// between antennas `tx_height_m` and `rx_height_m` above the
+    /// first and last terrain samples.
+    ///
+    /// This uses the Bullington method from ITU-R P.526-15 §4.5.1:
+    /// all obstructions are replaced by a single equivalent knife
+    /// edge at the intersection of the steepest rays from each
+    /// antenna that clear the terrain. When the path has line of
+    /// sight, the loss is that of the sample intruding furthest into
+    /// the first Fresnel zone, and is zero once the path is
+    /// sufficiently clear.
+    ///
+    /// Terrain elevations are used as is, so earth curvature is only
+    /// accounted for when the profile was built with
+    /// [`ProfileBuilder::earth_curve`].
+    #[allow(clippy::similar_names)]
+    pub fn diffraction_loss_db(
+        &self,
+        frequency_hz: f64,
+        tx_height_m: f64,
+        rx_height_m: f64,
+    ) -> f64 {
+        let wavelength_m = SPEED_OF_LIGHT / frequency_hz;
+        let distances: Vec<f64> = self.distances_m.iter().map(|&d| f64::from(d)).collect();
+        let terrain: Vec<f64> = self.terrain_elev_m.iter().map(|&e| f64::from(e)).collect();
+        let (Some(&d), Some(&tx_ground), Some(&rx_ground)) =
+            (distances.last(), terrain.first(), terrain.last())
+        else {
+            return 0.0;
+        };
+        let h_tx = tx_ground + tx_height_m;
+        let h_rx = rx_ground + rx_height_m;
+        let intermediate = || {
+            distances
+                .iter()
+                .zip(terrain.iter())
+                .skip(1)
+                .take(distances.len().saturating_sub(2))
+        };
+
+        // Steepest slope from the transmitter to any obstruction.
+        let s_tim = intermediate()
+            .map(|(&di, &hi)| (hi - h_tx) / di)
+            .fold(f64::NEG_INFINITY, f64::max);
+        // Slope of the direct path.
+        let s_tr = (h_rx - h_tx) / d;
+
+        let nu = if s_tim <= s_tr {
+            // Line of sight: use the sample with the largest
+            // diffraction parameter.
+            intermediate()
+                .map(|(&di, &hi)| {
+                    let los_m = (h_tx * (d - di) + h_rx * di) / d;
+                    fresnel_nu(hi - los_m, di, d - di, wavelength_m)
+                })
+                .fold(f64::NEG_INFINITY, f64::max)
+        } else {
+            // Obstructed: build the equivalent (Bullington) knife
+            // edge.
+            let s_rim = intermediate()
+                .map(|(&di, &hi)| (hi - h_rx) / (d - di))
+                .fold(f64::NEG_INFINITY, f64::max);
+            let d_b = (h_rx - h_tx + s_rim * d) / (s_tim + s_rim);
+            let edge_m = h_tx + s_tim * d_b;
+            let los_m = (h_tx * (d - d_b) + h_rx * d_b) / d;
+            fresnel_nu(edge_m - los_m, d_b, d - d_b, wavelength_m)
+        };
+
+        if nu.is_finite() {
+            knife_edge_loss_db(nu)
+        } else {
+            // Fewer than three samples, so no obstructions.
+            0.0
+        }
+    }
+}
+
 pub struct ProfileBuilder<C: CoordFloat = f32> {
     /// Start point of the path (required).
     start: Option<Coord<C>>,
@@ -137,7 +416,32 @@ where
         self
     }
 
+    /// Returns the profile these parameters describe.
+    ///
+    /// If `tiles` has a profile cache, the profile is taken from it
+    /// when present and stored in it otherwise.
     pub fn build(&self, tiles: &Tiles) -> Result<Profile<C>, TerrainError>
+    where
+        C: FloatConst + AsPrimitive<usize>,
+    {
+        #[cfg(feature = "cache")]
+        if let (Some(cache), Some(start), Some(max_step_m), Some(end)) =
+            (tiles.profile_cache(), self.start, self.max_step_m, self.end)
+        {
+            let key = ProfileKey::new(
+                Point::from(start),
+                Point::from(end),
+                max_step_m,
+                [self.start_alt_m, self.end_alt_m, self.earth_radius],
+                self.earth_curve,
+                self.normalize,
+            );
+            return cache.get_or_try_insert(key, || self.compute(tiles));
+        }
+        self.compute(tiles)
+    }
+
+    fn compute(&self, tiles: &Tiles) -> Result<Profile<C>, TerrainError>
     where
         C: FloatConst + AsPrimitive<usize>,
     {
@@ -258,8 +562,9 @@ where
 mod tests {
     #![allow(clippy::excessive_precision)]
 
-    use super::{Coord, Profile, Tiles};
-    use crate::tiles::TileMode;
+    use super::{Coord, Point, Profile, Tiles};
+    use crate::{constants::SPEED_OF_LIGHT, math::linspace, tiles::TileMode};
+    use approx::assert_relative_eq;
 
     /// ```xml
     /// <?xml version="1.0" encoding="UTF-8"?>
@@ -329,4 +634,167 @@ mod tests {
             .unwrap();
         assert_eq!(36, profile.great_circle.len());
     }
+
+    /// Returns a flat, 10 km, sea-level profile with a single knife
+    /// edge of `edge_m` at its midpoint.
+    fn knife_edge_profile(edge_m: f64) -> Profile<f64> {
+        let n = 101;
+        let distances_m: Box<[f64]> = linspace(0.0, 10_000.0, n).collect();
+        let mut terrain_elev_m = vec![0.0; n].into_boxed_slice();
+        terrain_elev_m[n / 2] = edge_m;
+        Profile {
+            great_circle: vec![Point::new(0.0, 0.0); n].into_boxed_slice(),
+            los_elev_m: vec![0.0; n].into_boxed_slice(),
+            distances_m,
+            terrain_elev_m,
+        }
+    }
+
+    #[test]
+    fn test_diffraction_loss() {
+        let freq_hz = 1e9;
+        let antenna_m = 10.0;
+        let wavelength_m = SPEED_OF_LIGHT / freq_hz;
+        // Edge height above the direct path yielding a diffraction
+        // parameter of ν = 1, where the textbook loss is ~13.9 dB.
+        let nu_1_m = (wavelength_m * 5_000.0 * 5_000.0 / (2.0 * 10_000.0)).sqrt();
+
+        let grazing = knife_edge_profile(antenna_m);
+        assert_relative_eq!(
+            grazing.diffraction_loss_db(freq_hz, antenna_m, antenna_m),
+            6.0,
+            epsilon = 1.0
+        );
+
+        let obstructed = knife_edge_profile(antenna_m + nu_1_m);
+        assert_relative_eq!(
+            obstructed.diffraction_loss_db(freq_hz, antenna_m, antenna_m),
+            13.9,
+            epsilon = 1.0
+        );
+
+        let clear = knife_edge_profile(0.0);
+        assert_relative_eq!(clear.diffraction_loss_db(freq_hz, 100.0, 100.0), 0.0);
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn test_adaptive_profile() {
+        let tiles = Tiles::new(crate::three_arcsecond_dir(), TileMode::MemMap).unwrap();
+        // West to east over the summit of Mt Washington.
+        let start = Coord {
+            x: -71.36,
+            y: 44.2705,
+        };
+        let end = Coord {
+            x: -71.25,
+            y: 44.2705,
+        };
+        let adaptive = Profile::adaptive(start, end, &tiles, 1_000.0, 1e-3).unwrap();
+        let n = adaptive.distances_m.len();
+        assert!(adaptive.distances_m.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(adaptive.terrain_elev_m.len(), n);
+        assert_eq!(adaptive.los_elev_m.len(), n);
+
+        let total_m = adaptive.distances_m[n - 1];
+        let uniform = Profile::builder()
+            .start(start)
+            .max_step(total_m / (n - 1) as f64 * 1.000_1)
+            .end(end)
+            .build(&tiles)
+            .unwrap();
+        assert_eq!(uniform.distances_m.len(), n);
+
+        let (summit_idx, _) =
+            adaptive
+                .terrain_elev_m
+                .iter()
+                .enumerate()
+                .fold(
+                    (0, f64::MIN),
+                    |max, (i, &elev)| {
+                        if elev > max.1 {
+                            (i, elev)
+                        } else {
+                            max
+                        }
+                    },
+                );
+        let summit_m = adaptive.distances_m[summit_idx];
+        let near_summit = |profile: &Profile<f64>| {
+            profile
+                .distances_m
+                .iter()
+                .filter(|&&d| (d - summit_m).abs() <= 1_000.0)
+                .count()
+        };
+        assert!(near_summit(&adaptive) > near_summit(&uniform));
+    }
+
+    #[test]
+    fn test_rhumb_profile() {
+        let tiles = Tiles::new(crate::three_arcsecond_dir(), TileMode::MemMap).unwrap();
+        let max_separation = |a: &Profile<f64>, b: &Profile<f64>| {
+            assert_eq!(a.great_circle.len(), b.great_circle.len());
+            a.great_circle
+                .iter()
+                .zip(b.great_circle.iter())
+                .map(|(a, b)| (a.x() - b.x()).hypot(a.y() - b.y()))
+                .fold(0.0, f64::max)
+        };
+        let geodesic = |start, end| {
+            Profile::builder()
+                .start(start)
+                .max_step(90.0)
+                .end(end)
+                .build(&tiles)
+                .unwrap()
+        };
+
+        // Along a parallel, the paths differ by only a couple meters
+        // over ~9 km, well within one sample.
+        let start = Coord {
+            x: -71.36,
+            y: 44.2705,
+        };
+        let end = Coord {
+            x: -71.25,
+            y: 44.2705,
+        };
+        let rhumb = Profile::rhumb(start, end, 90.0, &tiles).unwrap();
+        let great_circle = geodesic(start, end);
+        assert!(max_separation(&rhumb, &great_circle) < 1e-4);
+        assert!(rhumb
+            .great_circle
+            .iter()
+            .all(|p| (p.y() - 44.2705).abs() < 1e-12));
+        assert_relative_eq!(
+            rhumb.distances_m.last().unwrap(),
+            great_circle.distances_m.last().unwrap(),
+            max_relative = 1e-6
+        );
+        let same_elev = rhumb
+            .terrain_elev_m
+            .iter()
+            .zip(great_circle.terrain_elev_m.iter())
+            .filter(|(a, b)| (*a - *b).abs() < 0.5)
+            .count();
+        assert!(same_elev * 10 >= rhumb.terrain_elev_m.len() * 9);
+
+        // Across the tile's diagonal, the great circle bows ~100 m
+        // north of the rhumb line.
+        let start = Coord {
+            x: -71.95,
+            y: 44.05,
+        };
+        let end = Coord {
+            x: -71.05,
+            y: 44.95,
+        };
+        let rhumb = Profile::rhumb(start, end, 90.0, &tiles).unwrap();
+        let great_circle = geodesic(start, end);
+        assert!(max_separation(&rhumb, &great_circle) > 5e-4);
+        assert!(rhumb.distances_m.last().unwrap() > great_circle.distances_m.last().unwrap());
+        assert_ne!(rhumb.terrain_elev_m, great_circle.terrain_elev_m);
+    }
 }
@@ -0,0 +1,243 @@
+//! Memoization of computed profiles.
+
+use crate::{Profile, TerrainError};
+use geo::{geometry::Point, CoordFloat};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, PoisonError,
+    },
+};
+
+/// Endpoints closer than this, in degrees (about 1 cm), share a cache
+/// entry.
+const ENDPOINT_QUANTUM_DEG: f64 = 1e-7;
+
+/// Steps closer than this, in meters, share a cache entry.
+const STEP_QUANTUM_M: f64 = 1e-3;
+
+/// A least recently used cache of profiles built by
+/// [`ProfileBuilder::build`](crate::ProfileBuilder::build), enabled
+/// with [`Tiles::with_profile_cache`](crate::Tiles::with_profile_cache).
+///
+/// Entries are keyed by the builder's endpoints, quantized to about a
+/// centimeter, its step, quantized to a millimeter, and its remaining
+/// parameters exactly. A hit returns a clone of the stored profile.
+#[derive(Debug)]
+pub struct ProfileCache {
+    /// Most entries held.
+    capacity: usize,
+
+    /// Entries, most recently used first.
+    entries: Mutex<VecDeque<(ProfileKey, Profile<f64>)>>,
+
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ProfileCache {
+    /// Returns an empty cache holding up to `capacity` profiles.
+    ///
+    /// A capacity of zero is treated as one.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Mutex::default(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the number of profiles held.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if the cache holds no profiles.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of builds answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of builds that computed their profile.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Removes every profile, keeping the hit and miss counts.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Returns the profile stored under `key`, or else the one
+    /// `build` returns, storing it and evicting the least recently
+    /// used entry if full.
+    ///
+    /// The cache isn't locked while `build` runs, so concurrent
+    /// misses on the same key may each build the profile.
+    pub(crate) fn get_or_try_insert<C>(
+        &self,
+        key: ProfileKey,
+        build: impl FnOnce() -> Result<Profile<C>, TerrainError>,
+    ) -> Result<Profile<C>, TerrainError>
+    where
+        C: CoordFloat,
+        f64: From<C>,
+    {
+        {
+            let mut entries = self.lock();
+            if let Some(pos) = entries.iter().position(|(cached, _)| *cached == key) {
+                let entry = entries.remove(pos).expect("position is in bounds");
+                let profile = convert(&entry.1, |v| C::from(v).unwrap());
+                entries.push_front(entry);
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(profile);
+            }
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let profile = build()?;
+        let mut entries = self.lock();
+        entries.retain(|(cached, _)| *cached != key);
+        entries.truncate(self.capacity - 1);
+        entries.push_front((key, convert(&profile, f64::from)));
+        Ok(profile)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<(ProfileKey, Profile<f64>)>> {
+        // Entries are consistent between every statement, so a panic
+        // while they were locked can't have corrupted them.
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Parameters identifying a profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ProfileKey {
+    /// Start and end longitude and latitude, and step, quantized.
+    quantized: [i64; 5],
+
+    /// Altitudes and earth radius, as bits.
+    exact: [u64; 3],
+
+    earth_curve: bool,
+    normalize: bool,
+
+    /// Size of the profile's float type, as `f32` profiles are
+    /// computed with less precision.
+    precision: usize,
+}
+
+impl ProfileKey {
+    pub(crate) fn new<C>(
+        start: Point<C>,
+        end: Point<C>,
+        max_step_m: C,
+        [start_alt_m, end_alt_m, earth_radius]: [C; 3],
+        earth_curve: bool,
+        normalize: bool,
+    ) -> Self
+    where
+        C: CoordFloat,
+        f64: From<C>,
+    {
+        #[allow(clippy::cast_possible_truncation)]
+        let quantize = |v: C, quantum: f64| (f64::from(v) / quantum).round() as i64;
+        Self {
+            quantized: [
+                quantize(start.x(), ENDPOINT_QUANTUM_DEG),
+                quantize(start.y(), ENDPOINT_QUANTUM_DEG),
+                quantize(end.x(), ENDPOINT_QUANTUM_DEG),
+                quantize(end.y(), ENDPOINT_QUANTUM_DEG),
+                quantize(max_step_m, STEP_QUANTUM_M),
+            ],
+            exact: [start_alt_m, end_alt_m, earth_radius].map(|v| f64::from(v).to_bits()),
+            earth_curve,
+            normalize,
+            precision: std::mem::size_of::<C>(),
+        }
+    }
+}
+
+/// Returns `profile` with each value mapped by `f`.
+fn convert<A, B>(profile: &Profile<A>, f: impl Fn(A) -> B) -> Profile<B>
+where
+    A: CoordFloat,
+    B: CoordFloat,
+{
+    Profile {
+        distances_m: profile.distances_m.iter().map(|&v| f(v)).collect(),
+        great_circle: profile
+            .great_circle
+            .iter()
+            .map(|p| Point::new(f(p.x()), f(p.y())))
+            .collect(),
+        terrain_elev_m: profile.terrain_elev_m.iter().map(|&v| f(v)).collect(),
+        los_elev_m: profile.los_elev_m.iter().map(|&v| f(v)).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Profile, TileMode, Tiles};
+    use geo::geometry::Coord;
+
+    #[test]
+    fn test_profile_cache() {
+        let start = Coord {
+            x: -71.308_307_164_413_69,
+            y: 44.283_098_066_031_65,
+        };
+        let end = Coord {
+            x: -71.297_207_328_376_8,
+            y: 44.256_280_984_242_78,
+        };
+        let tiles = Tiles::new(crate::three_arcsecond_dir(), TileMode::MemMap)
+            .unwrap()
+            .with_profile_cache(1);
+        let build = |end: Coord<f64>| {
+            Profile::builder()
+                .start(start)
+                .max_step(90.0)
+                .end(end)
+                .earth_curve(true)
+                .build(&tiles)
+                .unwrap()
+        };
+        let cache = tiles.profile_cache().unwrap();
+
+        let first = build(end);
+        assert_eq!((cache.hits(), cache.misses()), (0, 1));
+        let second = build(end);
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+        assert_eq!(first, second);
+
+        // Nudged by about 10 m.
+        let nudged = Coord {
+            x: end.x + 1e-4,
+            ..end
+        };
+        let third = build(nudged);
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+        assert_ne!(first, third);
+        // Capacity 1 evicted the first profile.
+        assert_eq!(cache.len(), 1);
+        build(end);
+        assert_eq!((cache.hits(), cache.misses()), (1, 3));
+
+        // Clones share the cache.
+        let cloned = tiles.clone();
+        Profile::builder()
+            .start(start)
+            .max_step(90.0)
+            .end(end)
+            .earth_curve(true)
+            .build(&cloned)
+            .unwrap();
+        assert_eq!(cache.hits(), 2);
+    }
+}
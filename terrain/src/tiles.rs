@@ -1,10 +1,12 @@
 //! NASADEM file aggregator.
 
+#[cfg(feature = "cache")]
+use crate::ProfileCache;
 use crate::TerrainError;
 use dashmap::DashMap;
 use geo::geometry::Coord;
 use log::debug;
-use nasadem::{NasademError, Tile};
+use nasadem::{filename_for, NasademError, Tile, VOID};
 use std::{
     io::ErrorKind,
     path::{Path, PathBuf},
@@ -24,6 +26,10 @@ pub struct Tiles {
 
     /// Tiles which have been loaded on demand.
     tiles: DashMap<Coord<i16>, Arc<Tile>>,
+
+    /// Profiles built from these tiles, shared between clones.
+    #[cfg(feature = "cache")]
+    profile_cache: Option<Arc<ProfileCache>>,
 }
 
 impl Tiles {
@@ -46,18 +52,93 @@ impl Tiles {
                 tile_dir,
                 tile_mode,
                 tiles,
+                #[cfg(feature = "cache")]
+                profile_cache: None,
             })
         } else {
             Err(TerrainError::Path(tile_dir))
         }
     }
 
+    /// Returns these tiles, now memoizing up to `capacity` profiles
+    /// built from them.
+    ///
+    /// See [`ProfileCache`] for how profiles are matched.
+    #[cfg(feature = "cache")]
+    #[must_use]
+    pub fn with_profile_cache(mut self, capacity: usize) -> Self {
+        self.profile_cache = Some(Arc::new(ProfileCache::new(capacity)));
+        self
+    }
+
+    /// Returns the profile cache, if enabled with
+    /// [`Tiles::with_profile_cache`].
+    #[cfg(feature = "cache")]
+    pub fn profile_cache(&self) -> Option<&ProfileCache> {
+        self.profile_cache.as_deref()
+    }
+
     /// Returns the tile containiong `coord`, if any.
     ///
     /// `Tiles` will attempt to fetch the tile from disk if it doesn't
     /// already have it in memory.
     pub fn get(&self, coord: Coord<C>) -> Result<Arc<Tile>, TerrainError> {
-        let sw_corner = sw_corner(coord);
+        self.tile(sw_corner(coord))
+    }
+
+    /// Returns the elevation at `coord` bilinearly interpolated
+    /// between the four surrounding samples, which may come from
+    /// different tiles.
+    ///
+    /// Samples lie on a single grid spanning all tiles, at the
+    /// resolution of the tile containing `coord`, so queries near a
+    /// degree boundary interpolate across it without a seam. A sample
+    /// on a boundary is read from whichever tile sharing it isn't
+    /// missing. Returns `None` if any of the four samples is void.
+    pub fn get_interpolated(&self, coord: Coord<C>) -> Result<Option<f64>, TerrainError> {
+        let samples_per_deg = 3600.0 / f64::from(self.get(coord)?.resolution());
+        let (gx, gy) = (coord.x * samples_per_deg, coord.y * samples_per_deg);
+        let (x0, y0) = (gx.floor(), gy.floor());
+        let sample = |x: f64, y: f64| -> Result<Option<f64>, TerrainError> {
+            let corner = Coord {
+                x: x / samples_per_deg,
+                y: y / samples_per_deg,
+            };
+            let mut tile = self.get(corner)?;
+            'covering: for lat in covering_degrees(y, samples_per_deg) {
+                for lon in covering_degrees(x, samples_per_deg) {
+                    let candidate = self.tile(Coord { x: lon, y: lat })?;
+                    if !candidate.is_tombstone() {
+                        tile = candidate;
+                        break 'covering;
+                    }
+                }
+            }
+            Ok(match tile.get(corner) {
+                Some(VOID) | None => None,
+                Some(elev) => Some(f64::from(elev)),
+            })
+        };
+        let (Some(sw), Some(se), Some(nw), Some(ne)) = (
+            sample(x0, y0)?,
+            sample(x0 + 1.0, y0)?,
+            sample(x0, y0 + 1.0)?,
+            sample(x0 + 1.0, y0 + 1.0)?,
+        ) else {
+            return Ok(None);
+        };
+        let (tx, ty) = (gx - x0, gy - y0);
+        let south = sw * (1.0 - tx) + se * tx;
+        let north = nw * (1.0 - tx) + ne * tx;
+        Ok(Some(south * (1.0 - ty) + north * ty))
+    }
+}
+
+/// Private API.
+impl Tiles {
+    /// Returns the tile whose SW corner is `sw_corner`, loading it, or
+    /// a tombstone if its file is missing, on first use.
+    fn tile(&self, sw_corner: Coord<i16>) -> Result<Arc<Tile>, TerrainError> {
         self.tiles
             .entry(sw_corner)
             .or_try_insert_with(|| match self.load_tile(sw_corner) {
@@ -71,13 +152,10 @@ impl Tiles {
             })
             .map(|r| r.clone())
     }
-}
 
-/// Private API.
-impl Tiles {
     fn load_tile(&self, sw_corner: Coord<i16>) -> Result<Tile, TerrainError> {
         let tile_path = {
-            let file_name = file_name(sw_corner);
+            let file_name = filename_for(sw_corner);
             let mut tile_path: PathBuf = [&self.tile_dir, Path::new(&file_name)].iter().collect();
             if !tile_path.exists() {
                 let file_name = file_name.to_lowercase();
@@ -126,24 +204,19 @@ fn sw_corner(Coord { x, y }: Coord<C>) -> Coord<i16> {
     }
 }
 
-/// Returns the expected file name for coord
-fn file_name(Coord { x, y }: Coord<i16>) -> String {
-    let (n_s, lat) = {
-        let lat = y.abs();
-        let n_s = if y.is_negative() { 'S' } else { 'N' };
-        (n_s, lat)
-    };
-    let (e_w, lon) = {
-        let lon = x.abs();
-        let e_w = if x.is_negative() { 'W' } else { 'E' };
-        (e_w, lon)
-    };
-    format!("{n_s}{lat:02}{e_w}{lon:03}.hgt")
+/// Returns the degrees whose tiles hold the samples on grid line
+/// `g`: the one it falls in, then, if `g` is on that degree's edge,
+/// the one before it, whose far edge samples are the same.
+#[allow(clippy::cast_possible_truncation)]
+fn covering_degrees(g: C, samples_per_deg: C) -> impl Iterator<Item = i16> {
+    let deg = (g / samples_per_deg).floor() as i16;
+    let on_edge = g.rem_euclid(samples_per_deg) == 0.0;
+    std::iter::once(deg).chain(on_edge.then_some(deg - 1))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{file_name, sw_corner, Coord, TileMode, Tiles};
+    use super::{filename_for, sw_corner, Coord, TileMode, Tiles};
 
     const MT_WASHINGTON: Coord = Coord {
         y: 44.2705,
@@ -167,27 +240,88 @@ mod tests {
         assert_eq!(tile.get_unchecked(MT_WASHINGTON), 1903);
     }
 
+    #[test]
+    fn test_get_interpolated() {
+        let tile_src = Tiles::new(crate::three_arcsecond_dir(), TileMode::MemMap).unwrap();
+        let elev = tile_src.get_interpolated(MT_WASHINGTON).unwrap().unwrap();
+        assert!((elev - 1903.0).abs() < 5.0, "{elev}");
+    }
+
+    #[test]
+    fn test_get_interpolated_across_tiles() {
+        // Flat 3-arcsecond tiles at 100 m and, to the east, 300 m.
+        let dir = tempfile::tempdir().unwrap();
+        for (name, elev) in [("N44W072.hgt", 100_i16), ("N44W071.hgt", 300)] {
+            let samples = elev.to_be_bytes().repeat(1201 * 1201);
+            std::fs::write(dir.path().join(name), samples).unwrap();
+        }
+        let tile_src = Tiles::new(dir.path().to_path_buf(), TileMode::InMem).unwrap();
+
+        // Halfway between the west tile's last interior column and
+        // the shared edge, whose samples are taken from the east
+        // tile.
+        let boundary = Coord {
+            x: -71.0 - 1.5 / 3600.0,
+            y: 44.5,
+        };
+        let elev = tile_src.get_interpolated(boundary).unwrap().unwrap();
+        assert!((elev - 200.0).abs() < 1e-6, "{elev}");
+
+        let inside = Coord { x: -71.5, y: 44.5 };
+        assert_eq!(tile_src.get_interpolated(inside).unwrap(), Some(100.0));
+    }
+
+    #[test]
+    fn test_get_interpolated_missing_neighbor() {
+        // A lone flat tile, with no tiles to its north or east.
+        let dir = tempfile::tempdir().unwrap();
+        let samples = 100_i16.to_be_bytes().repeat(1201 * 1201);
+        std::fs::write(dir.path().join("N44W072.hgt"), samples).unwrap();
+        let tile_src = Tiles::new(dir.path().to_path_buf(), TileMode::InMem).unwrap();
+
+        // In the last cell before the east, north, and NE edges, whose
+        // samples this tile has too.
+        let half_cell = 1.5 / 3600.0;
+        for edge in [
+            Coord {
+                x: -71.0 - half_cell,
+                y: 44.5,
+            },
+            Coord {
+                x: -71.5,
+                y: 45.0 - half_cell,
+            },
+            Coord {
+                x: -71.0 - half_cell,
+                y: 45.0 - half_cell,
+            },
+        ] {
+            let elev = tile_src.get_interpolated(edge).unwrap().unwrap();
+            assert!((elev - 100.0).abs() < 1e-6, "{edge:?}: {elev}");
+        }
+    }
+
     #[test]
     fn test_file_name() {
-        let name = file_name(sw_corner(Coord {
+        let name = filename_for(sw_corner(Coord {
             y: 0.0 + f64::EPSILON,
             x: 0.0 + f64::EPSILON,
         }));
         assert_eq!(name, "N00E000.hgt");
 
-        let name = file_name(sw_corner(Coord {
+        let name = filename_for(sw_corner(Coord {
             y: 0.0 + f64::EPSILON,
             x: 0.0 - f64::EPSILON,
         }));
         assert_eq!(name, "N00W001.hgt");
 
-        let name = file_name(sw_corner(Coord {
+        let name = filename_for(sw_corner(Coord {
             y: 0.0 - f64::EPSILON,
             x: 0.0 - f64::EPSILON,
         }));
         assert_eq!(name, "S01W001.hgt");
 
-        let name = file_name(sw_corner(Coord {
+        let name = filename_for(sw_corner(Coord {
             y: 0.0 - f64::EPSILON,
             x: 0.0 + f64::EPSILON,
         }));
@@ -0,0 +1,191 @@
+//! Visibility between points on the terrain.
+
+use crate::{
+    constants::{MEAN_EARTH_RADIUS, METERS_PER_ARCSEC},
+    math::{elevation_angle, haversine_destination, HaversineIter},
+    tiles::C,
+    Profile, TerrainError, Tiles,
+};
+use geo::geometry::{Coord, Point};
+use nasadem::VOID;
+
+impl Tiles {
+    /// Returns the line-of-sight matrix between every ordered pair of
+    /// `points`, each a location and antenna height above ground in
+    /// meters.
+    ///
+    /// Element `[i][j]` is `true` if terrain (including earth
+    /// curvature) does not obstruct the path from `points[i]` to
+    /// `points[j]`. A point always sees itself. Paths are sampled at
+    /// the resolution of the tile containing the first point, and
+    /// tiles are loaded once and shared across all paths.
+    pub fn intervisibility(
+        &self,
+        points: &[(Coord<C>, f64)],
+    ) -> Result<Vec<Vec<bool>>, TerrainError> {
+        let Some(&(first, _)) = points.first() else {
+            return Ok(Vec::new());
+        };
+        let max_step_m = f64::from(self.get(first)?.resolution()) * METERS_PER_ARCSEC;
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, &(start, start_alt_m))| {
+                points
+                    .iter()
+                    .enumerate()
+                    .map(|(j, &(end, end_alt_m))| {
+                        if i == j {
+                            return Ok(true);
+                        }
+                        let profile = Profile::builder()
+                            .start(start)
+                            .start_alt(start_alt_m)
+                            .max_step(max_step_m)
+                            .end(end)
+                            .end_alt(end_alt_m)
+                            .earth_curve(true)
+                            .build(self)?;
+                        Ok(profile.has_line_of_sight())
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the skyline seen from `observer_height_m` meters above
+    /// the ground at `observer`.
+    ///
+    /// For each of `n_bearings` azimuths, evenly spaced clockwise
+    /// from north starting at north, the result holds the highest
+    /// elevation angle, in radians above the horizontal, of any
+    /// terrain within `max_distance_m` along that bearing. Angles
+    /// account for earth curvature, so over flat terrain the skyline
+    /// dips slightly below zero. Terrain is sampled at the resolution
+    /// of the observer's tile, and void samples are ignored. A void or
+    /// missing sample under the observer counts as sea level.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_distance_m` is not positive.
+    pub fn horizon(
+        &self,
+        observer: Coord<C>,
+        observer_height_m: f64,
+        max_distance_m: f64,
+        n_bearings: usize,
+    ) -> Result<Vec<f64>, TerrainError> {
+        assert!(max_distance_m > 0.0, "max_distance_m must be positive");
+        let observer_tile = self.get(observer)?;
+        let max_step_m = f64::from(observer_tile.resolution()) * METERS_PER_ARCSEC;
+        let ground_m = match observer_tile.get(observer) {
+            Some(VOID) | None => 0.0,
+            Some(elev) => f64::from(elev),
+        };
+        let observer_elev_m = ground_m + observer_height_m;
+        let start = Point::from(observer);
+        #[allow(clippy::cast_precision_loss)]
+        (0..n_bearings)
+            .map(|i| {
+                let bearing_rad = std::f64::consts::TAU * i as f64 / n_bearings as f64;
+                let end = haversine_destination(&start, bearing_rad, max_distance_m);
+                let path = HaversineIter::new(start, max_step_m, end);
+                let step_size_m = path.step_size_m();
+                let mut max_angle = f64::NEG_INFINITY;
+                for (i, point) in path.enumerate().skip(1) {
+                    let coord = point.into();
+                    let elev = match self.get(coord)?.get(coord) {
+                        Some(VOID) | None => continue,
+                        Some(elev) => f64::from(elev),
+                    };
+                    let angle = elevation_angle(
+                        observer_elev_m,
+                        i as f64 * step_size_m,
+                        elev,
+                        MEAN_EARTH_RADIUS,
+                    );
+                    max_angle = max_angle.max(angle);
+                }
+                Ok(max_angle)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{TileMode, Tiles, MEAN_EARTH_RADIUS};
+    use approx::assert_relative_eq;
+    use geo::coord;
+    use nasadem::VOID;
+
+    #[test]
+    fn test_intervisibility() {
+        let tiles = Tiles::new(crate::three_arcsecond_dir(), TileMode::MemMap).unwrap();
+        // A mast on the summit is visible from both flanks, but the
+        // summit blocks the flanks from each other.
+        let west = coord!(x: -71.31, y: 44.2705);
+        let mt_washington = coord!(x: -71.30325, y: 44.2705);
+        let east = coord!(x: -71.29, y: 44.2705);
+        let matrix = tiles
+            .intervisibility(&[(west, 30.0), (mt_washington, 60.0), (east, 2.0)])
+            .unwrap();
+        assert_eq!(
+            matrix,
+            vec![
+                vec![true, true, false],
+                vec![true, true, true],
+                vec![false, true, true],
+            ]
+        );
+        assert!(tiles.intervisibility(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_horizon_flat() {
+        let tiles = Tiles::new(crate::three_arcsecond_dir(), TileMode::MemMap).unwrap();
+        // There are no tiles here, so the terrain is flat at sea
+        // level, and the skyline is the geometric horizon, which dips
+        // by acos(R / (R + h)) ~= sqrt(2h / R).
+        let height_m = 10.0;
+        let horizon = tiles
+            .horizon(coord!(x: 0.5, y: 0.5), height_m, 20_000.0, 8)
+            .unwrap();
+        let dip = (MEAN_EARTH_RADIUS / (MEAN_EARTH_RADIUS + height_m)).acos();
+        assert_eq!(horizon.len(), 8);
+        for angle in horizon {
+            assert_relative_eq!(angle, -dip, max_relative = 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_horizon_void_observer() {
+        // Flat sea level, but void under the observer at its center.
+        let dir = tempfile::tempdir().unwrap();
+        let mut samples = vec![0_i16; 1201 * 1201];
+        samples[600 * 1201 + 600] = VOID;
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_be_bytes()).collect();
+        std::fs::write(dir.path().join("N00E000.hgt"), bytes).unwrap();
+        let tiles = Tiles::new(dir.path().to_path_buf(), TileMode::InMem).unwrap();
+
+        let height_m = 10.0;
+        let horizon = tiles
+            .horizon(coord!(x: 0.5, y: 0.5), height_m, 20_000.0, 8)
+            .unwrap();
+        let dip = (MEAN_EARTH_RADIUS / (MEAN_EARTH_RADIUS + height_m)).acos();
+        for angle in horizon {
+            assert_relative_eq!(angle, -dip, max_relative = 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_horizon_peak() {
+        let tiles = Tiles::new(crate::three_arcsecond_dir(), TileMode::MemMap).unwrap();
+        // Mt Washington is ~3.5 km due east.
+        let observer = coord!(x: -71.3475, y: 44.2705);
+        let horizon = tiles.horizon(observer, 2.0, 10_000.0, 4).unwrap();
+        let east = horizon[1];
+        assert!(east > 0.1, "{horizon:?}");
+        assert!(horizon.iter().all(|&angle| angle <= east), "{horizon:?}");
+    }
+}